@@ -0,0 +1,98 @@
+//! capture 模块 —— 屏幕区域截图
+//!
+//! "画个框，海鸥叼走一张截图"：前端收到 "gesture-rectangle" 后调用 capture_region，
+//! 这里把逻辑像素矩形换算为物理像素，抓取桌面上该区域的像素，编码为 PNG
+//! 写入应用数据目录下的 `captures/`。
+//!
+//! - Windows：GDI BitBlt（屏幕 DC → 内存位图）+ GetDIBits
+//! - Linux：X11 GetImage（根窗口，ZPixmap）
+//! - 其他平台：返回错误
+//!
+//! 截取的是屏幕上实际显示的内容，覆盖窗口上的墨迹 / 宠物也会被截进去，
+//! 需要干净的截图时前端应先隐藏它们再调用。
+
+#[cfg(windows)]
+mod windows;
+#[cfg(target_os = "linux")]
+mod x11;
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::display::{self, PhysicalRect};
+
+/// 截图所在子目录
+const CAPTURE_DIR: &str = "captures";
+
+/// "region-captured" 事件 payload / capture_region 返回值
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct CapturedRegion {
+    /// PNG 文件的绝对路径
+    pub path: String,
+    /// 实际截取的物理像素矩形（已裁剪到虚拟桌面内）
+    pub bounds: PhysicalRect,
+}
+
+/// 截取逻辑像素矩形（换算使用左上角所在显示器的缩放因子），保存到 `data_dir/captures/`
+pub fn capture_region(
+    data_dir: &Path,
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+) -> Result<CapturedRegion, String> {
+    if !(width > 0.0 && height > 0.0) {
+        return Err("截图区域的宽高必须大于 0".into());
+    }
+    let topology = display::topology();
+    let scale = topology.monitor_at_logical(x, y).scale;
+    let (left, top) = topology.to_physical(x, y);
+    let requested = PhysicalRect::new(
+        left.round() as i32,
+        top.round() as i32,
+        (width * scale).round() as u32,
+        (height * scale).round() as u32,
+    );
+    let bounds = requested
+        .intersect(&topology.virtual_bounds)
+        .ok_or("截图区域不在任何显示器上")?;
+
+    let rgba = grab(bounds)?;
+    let dir = data_dir.join(CAPTURE_DIR);
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis());
+    let path = dir.join(format!("capture-{}.png", millis));
+    write_png(&path, bounds.width, bounds.height, &rgba)?;
+
+    eprintln!("[capture] 📸 {:?} → {}", bounds, path.display());
+    Ok(CapturedRegion {
+        path: path.to_string_lossy().into_owned(),
+        bounds,
+    })
+}
+
+/// 读取物理像素矩形的 RGBA 像素（逐行，自上而下）
+fn grab(bounds: PhysicalRect) -> Result<Vec<u8>, String> {
+    #[cfg(windows)]
+    return windows::grab(bounds);
+    #[cfg(target_os = "linux")]
+    return x11::grab(bounds);
+    #[cfg(not(any(windows, target_os = "linux")))]
+    {
+        let _ = bounds;
+        Err("当前平台不支持截图".into())
+    }
+}
+
+fn write_png(path: &Path, width: u32, height: u32, rgba: &[u8]) -> Result<(), String> {
+    let file = File::create(path).map_err(|e| e.to_string())?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+    writer.write_image_data(rgba).map_err(|e| e.to_string())
+}
@@ -0,0 +1,80 @@
+//! Windows 屏幕截图
+//!
+//! 屏幕 DC BitBlt 到兼容内存位图（CAPTUREBLT 一并截取分层窗口），
+//! 再用 GetDIBits 读出 32 位自上而下的 BGRA 像素。
+//! 进程已声明 Per-Monitor DPI Awareness V2（见 main.rs），坐标均为物理像素。
+
+use std::mem::size_of;
+
+use windows::Win32::Graphics::Gdi::{
+    BitBlt, CreateCompatibleBitmap, CreateCompatibleDC, DeleteDC, DeleteObject, GetDC, GetDIBits,
+    ReleaseDC, SelectObject, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, CAPTUREBLT, DIB_RGB_COLORS,
+    SRCCOPY,
+};
+
+use crate::display::PhysicalRect;
+
+pub(super) fn grab(bounds: PhysicalRect) -> Result<Vec<u8>, String> {
+    let (width, height) = (bounds.width as i32, bounds.height as i32);
+    let mut pixels = vec![0u8; bounds.width as usize * bounds.height as usize * 4];
+    unsafe {
+        let screen = GetDC(None);
+        if screen.is_invalid() {
+            return Err("GetDC 失败".into());
+        }
+        let memory = CreateCompatibleDC(screen);
+        let bitmap = CreateCompatibleBitmap(screen, width, height);
+        let previous = SelectObject(memory, bitmap);
+        let copied = BitBlt(
+            memory,
+            0,
+            0,
+            width,
+            height,
+            screen,
+            bounds.x,
+            bounds.y,
+            SRCCOPY | CAPTUREBLT,
+        );
+        SelectObject(memory, previous);
+
+        let mut info = BITMAPINFO {
+            bmiHeader: BITMAPINFOHEADER {
+                biSize: size_of::<BITMAPINFOHEADER>() as u32,
+                biWidth: width,
+                // 负高度 = 自上而下
+                biHeight: -height,
+                biPlanes: 1,
+                biBitCount: 32,
+                biCompression: BI_RGB.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let lines = GetDIBits(
+            memory,
+            bitmap,
+            0,
+            height as u32,
+            Some(pixels.as_mut_ptr().cast()),
+            &mut info,
+            DIB_RGB_COLORS,
+        );
+
+        let _ = DeleteObject(bitmap);
+        let _ = DeleteDC(memory);
+        ReleaseDC(None, screen);
+
+        copied.map_err(|e| format!("BitBlt 失败：{}", e))?;
+        if lines == 0 {
+            return Err("GetDIBits 失败".into());
+        }
+    }
+
+    // BGRA → RGBA，屏幕像素不透明
+    for pixel in pixels.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+        pixel[3] = 255;
+    }
+    Ok(pixels)
+}
@@ -0,0 +1,57 @@
+//! Linux / X11 屏幕截图
+//!
+//! 对根窗口做 GetImage（ZPixmap），只支持 24 / 32 位深度（每像素 4 字节，BGRX 小端序），
+//! 这也是几乎所有现代 X server 的默认格式。
+
+use x11rb::connection::Connection;
+use x11rb::protocol::xproto::{ConnectionExt as _, ImageFormat, ImageOrder};
+
+use crate::display::PhysicalRect;
+
+pub(super) fn grab(bounds: PhysicalRect) -> Result<Vec<u8>, String> {
+    let (conn, screen_num) = x11rb::connect(None).map_err(|e| e.to_string())?;
+    let setup = conn.setup();
+    let root = setup.roots[screen_num].root;
+    if bounds.width > u16::MAX as u32 || bounds.height > u16::MAX as u32 {
+        return Err("截图区域过大".into());
+    }
+
+    let reply = conn
+        .get_image(
+            ImageFormat::Z_PIXMAP,
+            root,
+            bounds.x as i16,
+            bounds.y as i16,
+            bounds.width as u16,
+            bounds.height as u16,
+            !0,
+        )
+        .map_err(|e| e.to_string())?
+        .reply()
+        .map_err(|e| e.to_string())?;
+
+    let bits_per_pixel = setup
+        .pixmap_formats
+        .iter()
+        .find(|f| f.depth == reply.depth)
+        .map(|f| f.bits_per_pixel);
+    if !matches!(reply.depth, 24 | 32) || bits_per_pixel != Some(32) {
+        return Err(format!("不支持的颜色深度 {}", reply.depth));
+    }
+    if setup.image_byte_order != ImageOrder::LSB_FIRST {
+        return Err("不支持大端序的 X server".into());
+    }
+
+    // 每行按 32 位对齐，4 字节像素时没有行尾填充
+    let mut pixels = reply.data;
+    let expected = bounds.width as usize * bounds.height as usize * 4;
+    if pixels.len() < expected {
+        return Err("GetImage 返回的数据不完整".into());
+    }
+    pixels.truncate(expected);
+    for pixel in pixels.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+        pixel[3] = 255;
+    }
+    Ok(pixels)
+}
@@ -0,0 +1,168 @@
+//! display 模块 —— 显示器拓扑
+//!
+//! 枚举每个显示器的物理像素矩形、工作区（去掉任务栏 / 面板）和缩放因子，
+//! 并据此计算覆盖所有显示器的虚拟桌面矩形。
+//!
+//! 枚举结果缓存为 `MonitorTopology`（见 topology.rs），全局钩子线程
+//! 做坐标换算时直接读取缓存，不会在热路径上触发系统调用。
+//! 显示配置变化时由 watch.rs 根据平台通知刷新缓存。
+//!
+//! - Windows：EnumDisplayMonitors + GetMonitorInfoW + GetDpiForMonitor
+//! - Linux：X11 RandR（GetMonitors）+ _NET_WORKAREA + Xft.dpi
+//! - 其他平台 / 枚举失败：回退为单个 1920×1080 显示器
+
+mod topology;
+mod watch;
+#[cfg(windows)]
+mod windows;
+#[cfg(target_os = "linux")]
+mod x11;
+
+use std::sync::{Arc, RwLock};
+
+pub use topology::{logical_to_physical, physical_to_logical, MonitorTopology};
+pub use watch::spawn_watcher;
+
+/// 最近一次枚举得到的拓扑
+static TOPOLOGY: RwLock<Option<Arc<MonitorTopology>>> = RwLock::new(None);
+
+/// 物理像素矩形（虚拟桌面坐标系，左上角可能为负）
+#[derive(serde::Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PhysicalRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl PhysicalRect {
+    pub fn new(x: i32, y: i32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// 由左上 / 右下两个角构造（右下角不含）
+    fn from_edges(left: i32, top: i32, right: i32, bottom: i32) -> Self {
+        Self::new(
+            left,
+            top,
+            (right - left).max(0) as u32,
+            (bottom - top).max(0) as u32,
+        )
+    }
+
+    pub fn right(&self) -> i32 {
+        self.x + self.width as i32
+    }
+
+    pub fn bottom(&self) -> i32 {
+        self.y + self.height as i32
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.x as f64
+            && x < self.right() as f64
+            && y >= self.y as f64
+            && y < self.bottom() as f64
+    }
+
+    /// 同时包含两个矩形的最小矩形
+    pub fn union(&self, other: &PhysicalRect) -> PhysicalRect {
+        Self::from_edges(
+            self.x.min(other.x),
+            self.y.min(other.y),
+            self.right().max(other.right()),
+            self.bottom().max(other.bottom()),
+        )
+    }
+
+    /// 两个矩形的交集，不相交时返回 None
+    pub fn intersect(&self, other: &PhysicalRect) -> Option<PhysicalRect> {
+        let rect = Self::from_edges(
+            self.x.max(other.x),
+            self.y.max(other.y),
+            self.right().min(other.right()),
+            self.bottom().min(other.bottom()),
+        );
+        (!rect.is_empty()).then_some(rect)
+    }
+}
+
+/// 单个显示器的信息
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct MonitorInfo {
+    /// 系统给出的显示器标识（Windows 设备名 / RandR 输出名）
+    pub id: String,
+    /// 显示器完整矩形（物理像素）
+    pub bounds: PhysicalRect,
+    /// 工作区：去掉任务栏 / 停靠面板后的可用区域（物理像素）
+    pub work_area: PhysicalRect,
+    /// DPI 缩放因子（1.0 = 96 DPI）
+    pub scale: f64,
+    pub primary: bool,
+}
+
+/// 平台枚举失败时使用的默认显示器
+fn fallback_monitor() -> MonitorInfo {
+    let rect = PhysicalRect::new(0, 0, 1920, 1080);
+    MonitorInfo {
+        id: "default".into(),
+        bounds: rect,
+        work_area: rect,
+        scale: 1.0,
+        primary: true,
+    }
+}
+
+/// 枚举所有显示器（物理像素）；失败时为空，由 `MonitorTopology::new` 换成默认显示器
+pub fn enumerate_monitors() -> Vec<MonitorInfo> {
+    #[cfg(windows)]
+    let monitors = windows::enumerate();
+    #[cfg(target_os = "linux")]
+    let monitors = x11::enumerate().unwrap_or_else(|e| {
+        eprintln!("[display] ⚠️ RandR 枚举显示器失败：{}", e);
+        Vec::new()
+    });
+    #[cfg(not(any(windows, target_os = "linux")))]
+    let monitors: Vec<MonitorInfo> = Vec::new();
+
+    monitors
+}
+
+/// 虚拟桌面矩形：所有显示器矩形的并集（物理像素）
+pub fn virtual_bounds(monitors: &[MonitorInfo]) -> PhysicalRect {
+    match monitors.split_first() {
+        Some((first, rest)) => rest
+            .iter()
+            .fold(first.bounds, |acc, m| acc.union(&m.bounds)),
+        None => fallback_monitor().bounds,
+    }
+}
+
+/// 当前显示器拓扑（首次调用时枚举，之后复用缓存）
+pub fn topology() -> Arc<MonitorTopology> {
+    if let Some(topology) = TOPOLOGY.read().unwrap().as_ref() {
+        return Arc::clone(topology);
+    }
+    refresh_topology()
+}
+
+/// 重新枚举显示器并替换缓存
+pub fn refresh_topology() -> Arc<MonitorTopology> {
+    let topology = Arc::new(MonitorTopology::new(enumerate_monitors()));
+    *TOPOLOGY.write().unwrap() = Some(Arc::clone(&topology));
+    topology
+}
+
+/// 虚拟桌面矩形（来自缓存的拓扑）
+pub fn virtual_screen() -> PhysicalRect {
+    topology().virtual_bounds
+}
@@ -0,0 +1,247 @@
+//! 显示器拓扑模型 + 物理 / 逻辑像素换算
+//!
+//! 坐标约定（与前端一致）：
+//!   逻辑像素 = 物理像素 ÷ 该点所在显示器的缩放因子。
+//!   每个显示器在逻辑坐标系中占据 `bounds / scale` 的矩形。
+//!
+//! 本文件中的换算函数都是纯函数，不做任何系统调用；
+//! 系统枚举与缓存见 `display::topology()` / `display::refresh_topology()`。
+
+use super::{MonitorInfo, PhysicalRect};
+
+/// 全部显示器 + 虚拟桌面矩形
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct MonitorTopology {
+    pub monitors: Vec<MonitorInfo>,
+    /// 所有显示器矩形的并集（物理像素）
+    pub virtual_bounds: PhysicalRect,
+}
+
+impl MonitorTopology {
+    /// 由显示器列表构造；列表为空时使用默认显示器
+    pub fn new(monitors: Vec<MonitorInfo>) -> Self {
+        let monitors = if monitors.is_empty() {
+            vec![super::fallback_monitor()]
+        } else {
+            monitors
+        };
+        let virtual_bounds = super::virtual_bounds(&monitors);
+        Self {
+            monitors,
+            virtual_bounds,
+        }
+    }
+
+    /// 主显示器（没有标记主显示器时取第一个）
+    pub fn primary(&self) -> &MonitorInfo {
+        self.monitors
+            .iter()
+            .find(|m| m.primary)
+            .unwrap_or(&self.monitors[0])
+    }
+
+    /// 物理坐标所在的显示器；落在所有显示器之外时取最近的一个
+    pub fn monitor_at(&self, x: f64, y: f64) -> &MonitorInfo {
+        nearest(&self.monitors, x, y, |m| rect_f64(&m.bounds, 1.0))
+    }
+
+    /// 逻辑坐标所在的显示器；落在所有显示器之外时取最近的一个
+    pub fn monitor_at_logical(&self, x: f64, y: f64) -> &MonitorInfo {
+        nearest(&self.monitors, x, y, |m| rect_f64(&m.bounds, m.scale))
+    }
+
+    /// 物理坐标处的缩放因子
+    pub fn scale_at(&self, x: f64, y: f64) -> f64 {
+        self.monitor_at(x, y).scale
+    }
+
+    /// 物理像素 → 逻辑像素
+    pub fn to_logical(&self, x: f64, y: f64) -> (f64, f64) {
+        physical_to_logical(self.monitor_at(x, y), x, y)
+    }
+
+    /// 逻辑像素 → 物理像素
+    pub fn to_physical(&self, x: f64, y: f64) -> (f64, f64) {
+        logical_to_physical(self.monitor_at_logical(x, y), x, y)
+    }
+
+    /// 把物理坐标钳制进某个显示器的工作区（四周至少留出 margin）
+    ///
+    /// 已经落在任一工作区内时原样返回；否则移到最近工作区内离它最近的点。
+    pub fn clamp_to_work_area(&self, x: f64, y: f64, margin: f64) -> (f64, f64) {
+        let inner = |m: &MonitorInfo| shrink(rect_f64(&m.work_area, 1.0), margin);
+        let inside = |(left, top, right, bottom): (f64, f64, f64, f64)| {
+            x >= left && x <= right && y >= top && y <= bottom
+        };
+        if self.monitors.iter().any(|m| inside(inner(m))) {
+            return (x, y);
+        }
+
+        let (left, top, right, bottom) = inner(nearest(&self.monitors, x, y, inner));
+        (x.clamp(left, right), y.clamp(top, bottom))
+    }
+}
+
+/// 按指定显示器的缩放因子把物理坐标换算为逻辑坐标
+pub fn physical_to_logical(monitor: &MonitorInfo, x: f64, y: f64) -> (f64, f64) {
+    (x / monitor.scale, y / monitor.scale)
+}
+
+/// 按指定显示器的缩放因子把逻辑坐标换算为物理坐标
+pub fn logical_to_physical(monitor: &MonitorInfo, x: f64, y: f64) -> (f64, f64) {
+    (x * monitor.scale, y * monitor.scale)
+}
+
+/// (left, top, right, bottom)，按 scale 缩小后的矩形
+fn rect_f64(rect: &PhysicalRect, scale: f64) -> (f64, f64, f64, f64) {
+    (
+        rect.x as f64 / scale,
+        rect.y as f64 / scale,
+        rect.right() as f64 / scale,
+        rect.bottom() as f64 / scale,
+    )
+}
+
+/// 四边各向内收缩 margin；矩形太小时收缩为中心点
+fn shrink((left, top, right, bottom): (f64, f64, f64, f64), margin: f64) -> (f64, f64, f64, f64) {
+    let mx = margin.min((right - left) / 2.0);
+    let my = margin.min((bottom - top) / 2.0);
+    (left + mx, top + my, right - mx, bottom - my)
+}
+
+/// 包含该点的显示器，否则为到矩形距离最近的显示器
+fn nearest<F>(monitors: &[MonitorInfo], x: f64, y: f64, rect_of: F) -> &MonitorInfo
+where
+    F: Fn(&MonitorInfo) -> (f64, f64, f64, f64),
+{
+    let distance_sq = |m: &MonitorInfo| {
+        let (left, top, right, bottom) = rect_of(m);
+        let dx = (left - x).max(0.0).max(x - right);
+        let dy = (top - y).max(0.0).max(y - bottom);
+        dx * dx + dy * dy
+    };
+    monitors
+        .iter()
+        .map(|m| (m, distance_sq(m)))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(m, _)| m)
+        .expect("MonitorTopology 至少包含一个显示器")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(id: &str, bounds: PhysicalRect, work_area: PhysicalRect, scale: f64) -> MonitorInfo {
+        MonitorInfo {
+            id: id.into(),
+            bounds,
+            work_area,
+            scale,
+            primary: id == "main",
+        }
+    }
+
+    /// 主屏 100%，左侧副屏 150%、原点为负（逻辑坐标 -1920 ~ 0）
+    fn mixed_dpi() -> MonitorTopology {
+        MonitorTopology::new(vec![
+            monitor(
+                "main",
+                PhysicalRect::new(0, 0, 1920, 1080),
+                PhysicalRect::new(0, 0, 1920, 1040),
+                1.0,
+            ),
+            monitor(
+                "side",
+                PhysicalRect::new(-2880, 0, 2880, 1620),
+                PhysicalRect::new(-2880, 0, 2880, 1620),
+                1.5,
+            ),
+        ])
+    }
+
+    #[test]
+    fn empty_list_uses_fallback_monitor() {
+        let topology = MonitorTopology::new(Vec::new());
+        assert_eq!(topology.monitors.len(), 1);
+        assert_eq!(topology.primary().id, "default");
+        assert_eq!(topology.virtual_bounds, PhysicalRect::new(0, 0, 1920, 1080));
+    }
+
+    #[test]
+    fn virtual_bounds_cover_negative_origin() {
+        assert_eq!(
+            mixed_dpi().virtual_bounds,
+            PhysicalRect::new(-2880, 0, 4800, 1620)
+        );
+    }
+
+    #[test]
+    fn to_logical_uses_the_monitor_under_the_point() {
+        let topology = mixed_dpi();
+        assert_eq!(topology.to_logical(300.0, 200.0), (300.0, 200.0));
+        assert_eq!(topology.to_logical(-1440.0, 810.0), (-960.0, 540.0));
+        assert_eq!(topology.scale_at(-1.0, 0.0), 1.5);
+    }
+
+    #[test]
+    fn to_physical_uses_the_monitor_under_the_point() {
+        let topology = mixed_dpi();
+        assert_eq!(topology.to_physical(300.0, 200.0), (300.0, 200.0));
+        assert_eq!(topology.to_physical(-960.0, 540.0), (-1440.0, 810.0));
+    }
+
+    #[test]
+    fn monitor_at_logical_picks_containing_or_nearest() {
+        let topology = mixed_dpi();
+        assert_eq!(topology.monitor_at_logical(10.0, 10.0).id, "main");
+        assert_eq!(topology.monitor_at_logical(-10.0, 10.0).id, "side");
+        // 副屏逻辑高度 1080，以下的空白处离副屏更近
+        assert_eq!(topology.monitor_at_logical(-1000.0, 1500.0).id, "side");
+        assert_eq!(topology.monitor_at_logical(5000.0, 10.0).id, "main");
+    }
+
+    #[test]
+    fn physical_logical_round_trip() {
+        let topology = mixed_dpi();
+        for (x, y) in [
+            (0.0, 0.0),
+            (1919.0, 1079.0),
+            (-1.5, 3.0),
+            (-2880.0, 1619.0),
+            (-1234.5, 42.0),
+        ] {
+            let (lx, ly) = topology.to_logical(x, y);
+            let (px, py) = topology.to_physical(lx, ly);
+            assert!(
+                (px - x).abs() < 1e-9 && (py - y).abs() < 1e-9,
+                "({x}, {y}) → ({px}, {py})"
+            );
+        }
+    }
+
+    #[test]
+    fn clamp_to_work_area_keeps_inside_and_pulls_in_outside() {
+        let topology = mixed_dpi();
+        // 已在工作区内（含负坐标的副屏）
+        assert_eq!(
+            topology.clamp_to_work_area(-1000.0, 500.0, 10.0),
+            (-1000.0, 500.0)
+        );
+        // 落在主屏任务栏上：推回工作区内
+        assert_eq!(
+            topology.clamp_to_work_area(100.0, 1060.0, 10.0),
+            (100.0, 1030.0)
+        );
+        // 远在左侧之外：钳进副屏
+        assert_eq!(
+            topology.clamp_to_work_area(-5000.0, -100.0, 10.0),
+            (-2870.0, 10.0)
+        );
+        // 主屏右下方之外：钳进主屏
+        assert_eq!(
+            topology.clamp_to_work_area(3000.0, 1200.0, 10.0),
+            (1910.0, 1030.0)
+        );
+    }
+}
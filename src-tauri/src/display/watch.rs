@@ -0,0 +1,65 @@
+//! 显示配置变化监听（热插拔 / 分辨率 / 缩放 / 任务栏位置）
+//!
+//! 后台线程阻塞等待平台的变化通知，收到后重新枚举显示器并与缓存的拓扑比较；
+//! 发生变化时替换缓存并回调 `on_change`。
+//!   - Linux：RandR 通知 + 根窗口属性变化，复用同一个 X 连接枚举（见 x11.rs）
+//!   - Windows：隐藏窗口接收 WM_DISPLAYCHANGE / WM_DPICHANGED 等广播（见 windows.rs）
+//!
+//! 平台监听不可用或中断时退回定期轮询。
+
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use super::{enumerate_monitors, MonitorInfo, MonitorTopology, TOPOLOGY};
+
+/// 平台监听不可用时的轮询间隔
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 启动监听线程，拓扑变化时在该线程上调用 `on_change`
+pub fn spawn_watcher<F>(on_change: F)
+where
+    F: Fn(Arc<MonitorTopology>) + Send + 'static,
+{
+    thread::spawn(move || {
+        let mut update = |monitors: Vec<MonitorInfo>| publish(monitors, &on_change);
+
+        #[cfg(windows)]
+        let result = super::windows::watch(&mut update);
+        #[cfg(target_os = "linux")]
+        let result = super::x11::watch(&mut update);
+        #[cfg(not(any(windows, target_os = "linux")))]
+        let result: Result<(), String> = Err("当前平台没有显示变化通知".into());
+
+        if let Err(e) = result {
+            eprintln!(
+                "[display] ⚠️ 无法监听显示配置变化（{}），改为每 {:?} 轮询",
+                e, POLL_INTERVAL
+            );
+        }
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            update(enumerate_monitors());
+        }
+    });
+}
+
+/// 与缓存比较，变化时替换缓存并回调
+fn publish<F>(monitors: Vec<MonitorInfo>, on_change: &F)
+where
+    F: Fn(Arc<MonitorTopology>),
+{
+    let fresh = MonitorTopology::new(monitors);
+    if TOPOLOGY.read().unwrap().as_deref() == Some(&fresh) {
+        return;
+    }
+
+    let fresh = Arc::new(fresh);
+    *TOPOLOGY.write().unwrap() = Some(Arc::clone(&fresh));
+    eprintln!(
+        "[display] 🔄 显示配置变化：{} 个显示器，虚拟桌面 {:?}",
+        fresh.monitors.len(),
+        fresh.virtual_bounds
+    );
+    on_change(fresh);
+}
@@ -0,0 +1,160 @@
+//! Windows 显示器枚举
+//!
+//! EnumDisplayMonitors 逐个回调，GetMonitorInfoW 给出显示器矩形与工作区
+//! （rcWork 已去掉任务栏），GetDpiForMonitor 给出该显示器的有效 DPI。
+//! 进程已声明 Per-Monitor DPI Awareness V2（见 main.rs），返回值均为物理像素。
+//!
+//! 变化监听（`watch`）：在独立线程上创建一个隐藏的顶层窗口，
+//! 接收 WM_DISPLAYCHANGE / WM_DPICHANGED / WM_SETTINGCHANGE(SPI_SETWORKAREA) 广播后重新枚举。
+//! message-only 窗口收不到这些广播，所以必须是（不可见的）顶层窗口。
+
+use windows::core::w;
+use windows::Win32::Foundation::{BOOL, HWND, LPARAM, LRESULT, RECT, TRUE, WPARAM};
+use windows::Win32::Graphics::Gdi::{
+    EnumDisplayMonitors, GetMonitorInfoW, HDC, HMONITOR, MONITORINFO, MONITORINFOEXW,
+};
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::HiDpi::{GetDpiForMonitor, MDT_EFFECTIVE_DPI};
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, DestroyWindow, DispatchMessageW, GetMessageW, PeekMessageW,
+    PostMessageW, RegisterClassW, MONITORINFOF_PRIMARY, MSG, PM_REMOVE, SPI_SETWORKAREA,
+    WINDOW_EX_STYLE, WM_APP, WM_DISPLAYCHANGE, WM_DPICHANGED, WM_SETTINGCHANGE, WNDCLASSW,
+    WS_OVERLAPPED,
+};
+
+/// 窗口过程收到变化广播后投递给自己的消息，在消息循环里合并处理
+const WM_APP_DISPLAY_CHANGED: u32 = WM_APP + 1;
+
+use super::{MonitorInfo, PhysicalRect};
+
+pub(super) fn enumerate() -> Vec<MonitorInfo> {
+    let mut monitors: Vec<MonitorInfo> = Vec::new();
+    unsafe {
+        let _ = EnumDisplayMonitors(
+            HDC::default(),
+            None,
+            Some(monitor_enum_proc),
+            LPARAM(&mut monitors as *mut Vec<MonitorInfo> as isize),
+        );
+    }
+    monitors
+}
+
+unsafe extern "system" fn monitor_enum_proc(
+    hmon: HMONITOR,
+    _hdc: HDC,
+    _rect: *mut RECT,
+    data: LPARAM,
+) -> BOOL {
+    let monitors = &mut *(data.0 as *mut Vec<MonitorInfo>);
+
+    let mut info = MONITORINFOEXW::default();
+    info.monitorInfo.cbSize = std::mem::size_of::<MONITORINFOEXW>() as u32;
+    if !GetMonitorInfoW(hmon, &mut info as *mut MONITORINFOEXW as *mut MONITORINFO).as_bool() {
+        return TRUE; // 跳过该显示器，继续枚举
+    }
+
+    let mut dpi_x = 0u32;
+    let mut dpi_y = 0u32;
+    let scale =
+        if GetDpiForMonitor(hmon, MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y).is_ok() && dpi_x > 0 {
+            dpi_x as f64 / 96.0
+        } else {
+            1.0
+        };
+
+    let name_len = info
+        .szDevice
+        .iter()
+        .position(|&c| c == 0)
+        .unwrap_or(info.szDevice.len());
+    monitors.push(MonitorInfo {
+        id: String::from_utf16_lossy(&info.szDevice[..name_len]),
+        bounds: rect_from_win(&info.monitorInfo.rcMonitor),
+        work_area: rect_from_win(&info.monitorInfo.rcWork),
+        scale,
+        primary: info.monitorInfo.dwFlags & MONITORINFOF_PRIMARY != 0,
+    });
+
+    TRUE
+}
+
+fn rect_from_win(rect: &RECT) -> PhysicalRect {
+    PhysicalRect::from_edges(rect.left, rect.top, rect.right, rect.bottom)
+}
+
+/// 阻塞监听显示配置变化，每批变化消息之后重新枚举一次并调用 `on_change`
+///
+/// 只在窗口创建失败或消息循环退出时返回。
+pub(super) fn watch(mut on_change: impl FnMut(Vec<MonitorInfo>)) -> Result<(), String> {
+    unsafe {
+        let hinstance = GetModuleHandleW(None).map_err(|e| e.to_string())?;
+        let class_name = w!("MetDisplayWatch");
+        let class = WNDCLASSW {
+            lpfnWndProc: Some(watch_wnd_proc),
+            hInstance: hinstance.into(),
+            lpszClassName: class_name,
+            ..Default::default()
+        };
+        if RegisterClassW(&class) == 0 {
+            return Err("RegisterClassW 失败".into());
+        }
+        // 不带 WS_VISIBLE，窗口始终隐藏
+        let hwnd = CreateWindowExW(
+            WINDOW_EX_STYLE::default(),
+            class_name,
+            w!(""),
+            WS_OVERLAPPED,
+            0,
+            0,
+            0,
+            0,
+            None,
+            None,
+            hinstance,
+            None,
+        )
+        .map_err(|e| e.to_string())?;
+
+        let mut msg = MSG::default();
+        while GetMessageW(&mut msg, None, 0, 0).0 > 0 {
+            if msg.hwnd == hwnd && msg.message == WM_APP_DISPLAY_CHANGED {
+                // 一次改动通常伴随多条广播，合并已到达的通知后只枚举一次
+                let mut pending = MSG::default();
+                while PeekMessageW(
+                    &mut pending,
+                    hwnd,
+                    WM_APP_DISPLAY_CHANGED,
+                    WM_APP_DISPLAY_CHANGED,
+                    PM_REMOVE,
+                )
+                .as_bool()
+                {}
+                on_change(enumerate());
+                continue;
+            }
+            DispatchMessageW(&msg);
+        }
+        let _ = DestroyWindow(hwnd);
+    }
+    Err("显示变化监听的消息循环已退出".into())
+}
+
+/// 广播消息是同步发送（SendMessage）的，直接在窗口过程里枚举会阻塞发送方，
+/// 这里只投递一条通知，回到消息循环再处理
+unsafe extern "system" fn watch_wnd_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    let changed = match msg {
+        WM_DISPLAYCHANGE | WM_DPICHANGED => true,
+        WM_SETTINGCHANGE => wparam.0 as u32 == SPI_SETWORKAREA.0,
+        _ => false,
+    };
+    if changed {
+        let _ = PostMessageW(hwnd, WM_APP_DISPLAY_CHANGED, WPARAM(0), LPARAM(0));
+    }
+    DefWindowProcW(hwnd, msg, wparam, lparam)
+}
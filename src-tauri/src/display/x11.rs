@@ -0,0 +1,233 @@
+//! Linux / X11 显示器枚举
+//!
+//! - 显示器矩形：RandR 1.5 GetMonitors（不支持时回退为整个根窗口）
+//! - 工作区：EWMH `_NET_WORKAREA`（当前桌面），与每个显示器矩形求交集
+//! - 缩放因子：`GDK_SCALE` 环境变量优先，其次 X 资源 `Xft.dpi` / 96
+//!
+//! X11 下所有显示器共用同一个缩放因子；GTK 的窗口缩放只支持整数，
+//! 这里同样取整，保证与 WebView 的 devicePixelRatio 一致。
+//!
+//! 变化监听（`watch`）：在同一个连接上订阅 RandR 屏幕 / 输出 / CRTC 变化通知，
+//! 以及根窗口上 `_NET_WORKAREA`、`_NET_CURRENT_DESKTOP`、`RESOURCE_MANAGER`（Xft.dpi）的属性变化，
+//! 阻塞等待事件，收到后在该连接上重新枚举。
+
+use x11rb::connection::{Connection, RequestConnection};
+use x11rb::protocol::randr::{self, ConnectionExt as _};
+use x11rb::protocol::xproto::{
+    AtomEnum, ChangeWindowAttributesAux, ConnectionExt as _, EventMask, Window,
+};
+use x11rb::protocol::Event;
+use x11rb::rust_connection::RustConnection;
+
+use super::{MonitorInfo, PhysicalRect};
+
+pub(super) fn enumerate() -> Result<Vec<MonitorInfo>, String> {
+    let (conn, screen_num) = x11rb::connect(None).map_err(|e| e.to_string())?;
+    enumerate_on(&conn, screen_num)
+}
+
+/// 阻塞监听显示配置变化，每批变化事件之后重新枚举一次并调用 `on_change`
+///
+/// 只在连接失败或中断时返回。
+pub(super) fn watch(mut on_change: impl FnMut(Vec<MonitorInfo>)) -> Result<(), String> {
+    let (conn, screen_num) = x11rb::connect(None).map_err(|e| e.to_string())?;
+    let root = conn.setup().roots[screen_num].root;
+
+    if conn
+        .extension_information(randr::X11_EXTENSION_NAME)
+        .map_err(|e| e.to_string())?
+        .is_none()
+    {
+        return Err("X server 不支持 RandR".into());
+    }
+    // 需要先协商版本，服务器才会按 1.2+ 的格式发送通知
+    conn.randr_query_version(1, 5)
+        .map_err(|e| e.to_string())?
+        .reply()
+        .map_err(|e| e.to_string())?;
+    conn.randr_select_input(
+        root,
+        randr::NotifyMask::SCREEN_CHANGE
+            | randr::NotifyMask::OUTPUT_CHANGE
+            | randr::NotifyMask::CRTC_CHANGE,
+    )
+    .map_err(|e| e.to_string())?
+    .check()
+    .map_err(|e| e.to_string())?;
+    conn.change_window_attributes(
+        root,
+        &ChangeWindowAttributesAux::new().event_mask(EventMask::PROPERTY_CHANGE),
+    )
+    .map_err(|e| e.to_string())?
+    .check()
+    .map_err(|e| e.to_string())?;
+
+    // 属性可能尚未被设置过，这里要创建 atom 而不是只查已有的
+    let watched_atoms: Vec<u32> = [
+        &b"_NET_WORKAREA"[..],
+        b"_NET_CURRENT_DESKTOP",
+        b"RESOURCE_MANAGER",
+    ]
+    .into_iter()
+    .filter_map(|name| {
+        conn.intern_atom(false, name)
+            .ok()?
+            .reply()
+            .ok()
+            .map(|r| r.atom)
+    })
+    .collect();
+    let relevant = |event: &Event| match event {
+        Event::RandrScreenChangeNotify(_) | Event::RandrNotify(_) => true,
+        Event::PropertyNotify(e) => watched_atoms.contains(&e.atom),
+        _ => false,
+    };
+
+    loop {
+        let event = conn.wait_for_event().map_err(|e| e.to_string())?;
+        let mut changed = relevant(&event);
+        // 一次改动通常伴随一串通知，取完已到达的事件后只枚举一次
+        while let Some(event) = conn.poll_for_event().map_err(|e| e.to_string())? {
+            changed |= relevant(&event);
+        }
+        if changed {
+            on_change(enumerate_on(&conn, screen_num)?);
+        }
+    }
+}
+
+fn enumerate_on(conn: &RustConnection, screen_num: usize) -> Result<Vec<MonitorInfo>, String> {
+    let screen = &conn.setup().roots[screen_num];
+    let root = screen.root;
+
+    let scale = read_scale(conn, root);
+    let work_area = read_work_area(conn, root);
+
+    let monitors = match conn.randr_get_monitors(root, true).map(|c| c.reply()) {
+        Ok(Ok(reply)) if !reply.monitors.is_empty() => reply
+            .monitors
+            .iter()
+            .map(|m| {
+                let bounds =
+                    PhysicalRect::new(m.x as i32, m.y as i32, m.width as u32, m.height as u32);
+                let id = conn
+                    .get_atom_name(m.name)
+                    .ok()
+                    .and_then(|c| c.reply().ok())
+                    .map(|r| String::from_utf8_lossy(&r.name).into_owned())
+                    .unwrap_or_else(|| format!("monitor-{}", m.name));
+                MonitorInfo {
+                    id,
+                    bounds,
+                    work_area: clip_work_area(bounds, work_area),
+                    scale,
+                    primary: m.primary,
+                }
+            })
+            .collect(),
+        _ => {
+            // 没有 RandR 1.5：把整个根窗口视为一个显示器
+            // （连接建立后根窗口可能被 RandR 改过尺寸，setup 里的值只作后备）
+            let (width, height) = conn
+                .get_geometry(root)
+                .ok()
+                .and_then(|c| c.reply().ok())
+                .map_or((screen.width_in_pixels, screen.height_in_pixels), |g| {
+                    (g.width, g.height)
+                });
+            let bounds = PhysicalRect::new(0, 0, width as u32, height as u32);
+            vec![MonitorInfo {
+                id: format!("screen-{}", screen_num),
+                bounds,
+                work_area: clip_work_area(bounds, work_area),
+                scale,
+                primary: true,
+            }]
+        }
+    };
+
+    Ok(monitors)
+}
+
+/// 工作区与显示器矩形求交集；没有 _NET_WORKAREA 或不相交时使用整个显示器
+fn clip_work_area(bounds: PhysicalRect, work_area: Option<PhysicalRect>) -> PhysicalRect {
+    work_area
+        .and_then(|wa| wa.intersect(&bounds))
+        .unwrap_or(bounds)
+}
+
+/// 读取当前桌面的 `_NET_WORKAREA`（窗口管理器未设置时返回 None）
+fn read_work_area(conn: &RustConnection, root: Window) -> Option<PhysicalRect> {
+    let workarea_atom = intern(conn, b"_NET_WORKAREA")?;
+    let current_atom = intern(conn, b"_NET_CURRENT_DESKTOP")?;
+
+    let desktop = conn
+        .get_property(false, root, current_atom, AtomEnum::CARDINAL, 0, 1)
+        .ok()?
+        .reply()
+        .ok()
+        .and_then(|r| r.value32().and_then(|mut v| v.next()))
+        .unwrap_or(0);
+
+    let reply = conn
+        .get_property(false, root, workarea_atom, AtomEnum::CARDINAL, 0, u32::MAX)
+        .ok()?
+        .reply()
+        .ok()?;
+    let values: Vec<u32> = reply.value32()?.collect();
+    let base = desktop as usize * 4;
+    let rect = values.get(base..base + 4)?;
+    Some(PhysicalRect::new(
+        rect[0] as i32,
+        rect[1] as i32,
+        rect[2],
+        rect[3],
+    ))
+}
+
+/// 缩放因子：GDK_SCALE > Xft.dpi / 96 > 1.0
+fn read_scale(conn: &RustConnection, root: Window) -> f64 {
+    if let Some(scale) = std::env::var("GDK_SCALE")
+        .ok()
+        .and_then(|s| s.trim().parse::<f64>().ok())
+        .filter(|s| *s >= 1.0)
+    {
+        return scale.floor();
+    }
+
+    read_xft_dpi(conn, root)
+        .map(|dpi| (dpi / 96.0).floor().max(1.0))
+        .unwrap_or(1.0)
+}
+
+/// 从根窗口的 RESOURCE_MANAGER 属性中解析 `Xft.dpi`
+fn read_xft_dpi(conn: &RustConnection, root: Window) -> Option<f64> {
+    let reply = conn
+        .get_property(
+            false,
+            root,
+            AtomEnum::RESOURCE_MANAGER,
+            AtomEnum::STRING,
+            0,
+            u32::MAX,
+        )
+        .ok()?
+        .reply()
+        .ok()?;
+    let resources = String::from_utf8_lossy(&reply.value).into_owned();
+    resources.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        (key.trim() == "Xft.dpi")
+            .then(|| value.trim().parse::<f64>().ok())
+            .flatten()
+    })
+}
+
+fn intern(conn: &RustConnection, name: &[u8]) -> Option<u32> {
+    conn.intern_atom(true, name)
+        .ok()?
+        .reply()
+        .ok()
+        .map(|r| r.atom)
+        .filter(|&a| a != 0)
+}
@@ -0,0 +1,209 @@
+//! 手势激活策略 —— 决定桌面上的左键拖动何时算作"画手势"
+//!
+//! 默认每次不在宠物上按下左键都会进入绘制模式，选中文字、框选文件时也可能误触发手势。
+//! 前端可以通过 `set_gesture_activation` 选择策略：
+//!
+//! ```json
+//! { "mode": "always" }
+//! { "mode": "modifier", "key": "alt" }
+//! { "mode": "dwell", "hold_ms": 300, "tolerance": 6 }
+//! { "mode": "apps", "allow": [], "deny": ["explorer", "code"] }
+//! ```
+//!
+//! 策略在每次按下时解析为 `Activation` 交给状态机：
+//! 修饰键 / 长按在状态机内判定，前台应用在工作线程查询（见 foreground.rs）。
+//! 未激活的拖动不收集任何轨迹点，原样交给下层窗口。
+
+use super::input::ModifierKey;
+use super::recognizers::check_range;
+
+/// 长按激活的默认按住时长（毫秒）
+const DEFAULT_HOLD_MS: u32 = 300;
+/// 长按期间允许的默认抖动距离（逻辑像素）
+const DEFAULT_TOLERANCE: f64 = 6.0;
+
+/// 前端配置的激活策略
+#[derive(serde::Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(tag = "mode", rename_all = "snake_case", deny_unknown_fields)]
+pub enum ActivationPolicy {
+    /// 总是激活（默认）
+    #[default]
+    Always,
+    /// 按下左键时需要按住指定修饰键
+    Modifier { key: ModifierKey },
+    /// 按住不动 hold_ms 毫秒后才开始绘制；此前移动超过 tolerance（逻辑像素）视为普通拖动
+    Dwell {
+        #[serde(default = "default_hold_ms")]
+        hold_ms: u32,
+        #[serde(default = "default_tolerance")]
+        tolerance: f64,
+    },
+    /// 按前台应用的进程名过滤（不区分大小写，可省略 ".exe"）：
+    /// allow 非空时只在其中的应用上激活，deny 中的应用总是不激活
+    Apps {
+        #[serde(default)]
+        allow: Vec<String>,
+        #[serde(default)]
+        deny: Vec<String>,
+    },
+}
+
+fn default_hold_ms() -> u32 {
+    DEFAULT_HOLD_MS
+}
+
+fn default_tolerance() -> f64 {
+    DEFAULT_TOLERANCE
+}
+
+/// 一次按下的激活方式（策略按当次按下解析后的结果，物理像素）
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Activation {
+    /// 立即开始绘制
+    Immediate,
+    /// 按下时按住了该修饰键才开始绘制
+    Modifier(ModifierKey),
+    /// 按住 hold_ms 毫秒后开始绘制，此前移动超过 tolerance 则放弃
+    Dwell { hold_ms: u32, tolerance: f64 },
+    /// 本次按下不激活
+    Disabled,
+}
+
+impl ActivationPolicy {
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            Self::Always | Self::Modifier { .. } => Ok(()),
+            Self::Dwell { hold_ms, tolerance } => {
+                check_range("hold_ms", *hold_ms as f64, 50.0, 5000.0)?;
+                check_range("tolerance", *tolerance, 0.0, 100.0)
+            }
+            Self::Apps { allow, deny } => {
+                if allow.is_empty() && deny.is_empty() {
+                    return Err("allow 与 deny 不能同时为空".to_string());
+                }
+                if allow
+                    .iter()
+                    .chain(deny)
+                    .any(|name| normalize_app(name).is_empty())
+                {
+                    return Err("应用名不能为空".to_string());
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// 是否需要查询前台应用
+    pub fn needs_foreground_app(&self) -> bool {
+        matches!(self, Self::Apps { .. })
+    }
+
+    /// 解析本次按下的激活方式
+    ///
+    /// `scale` 为按下位置所在显示器的缩放因子；`app` 为前台应用的进程名（查询失败为 None）。
+    pub fn resolve(&self, scale: f64, app: Option<&str>) -> Activation {
+        match self {
+            Self::Always => Activation::Immediate,
+            Self::Modifier { key } => Activation::Modifier(*key),
+            Self::Dwell { hold_ms, tolerance } => Activation::Dwell {
+                hold_ms: *hold_ms,
+                tolerance: tolerance * scale,
+            },
+            Self::Apps { allow, deny } => {
+                let matches = |list: &[String], app: &str| {
+                    list.iter()
+                        .any(|name| normalize_app(name) == normalize_app(app))
+                };
+                let allowed = match app {
+                    Some(app) => (allow.is_empty() || matches(allow, app)) && !matches(deny, app),
+                    // 查不到前台应用时：有白名单则保守地不激活
+                    None => allow.is_empty(),
+                };
+                if allowed {
+                    Activation::Immediate
+                } else {
+                    Activation::Disabled
+                }
+            }
+        }
+    }
+}
+
+/// 统一进程名写法：小写、去掉 ".exe"
+pub fn normalize_app(name: &str) -> String {
+    let name = name.trim().to_lowercase();
+    match name.strip_suffix(".exe") {
+        Some(stem) => stem.to_string(),
+        None => name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(json: &str) -> ActivationPolicy {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn always_and_modifier() {
+        assert_eq!(
+            ActivationPolicy::Always.resolve(1.0, None),
+            Activation::Immediate
+        );
+        let modifier = policy(r#"{ "mode": "modifier", "key": "alt" }"#);
+        assert_eq!(
+            modifier.resolve(2.0, None),
+            Activation::Modifier(ModifierKey::Alt)
+        );
+    }
+
+    #[test]
+    fn dwell_tolerance_scales_to_physical() {
+        let dwell = policy(r#"{ "mode": "dwell" }"#);
+        assert_eq!(
+            dwell.resolve(1.5, None),
+            Activation::Dwell {
+                hold_ms: DEFAULT_HOLD_MS,
+                tolerance: DEFAULT_TOLERANCE * 1.5
+            }
+        );
+        assert!(policy(r#"{ "mode": "dwell", "hold_ms": 10 }"#)
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn deny_list_matches_normalized_names() {
+        let apps = policy(r#"{ "mode": "apps", "deny": ["Explorer.EXE", "code"] }"#);
+        assert_eq!(apps.resolve(1.0, Some("explorer")), Activation::Disabled);
+        assert_eq!(apps.resolve(1.0, Some("Code.exe")), Activation::Disabled);
+        assert_eq!(apps.resolve(1.0, Some("firefox")), Activation::Immediate);
+        // 查不到前台应用、又没有白名单时照常激活
+        assert_eq!(apps.resolve(1.0, None), Activation::Immediate);
+    }
+
+    #[test]
+    fn allow_list_is_exclusive() {
+        let apps = policy(r#"{ "mode": "apps", "allow": ["mspaint"], "deny": ["MSPaint"] }"#);
+        assert_eq!(apps.resolve(1.0, Some("mspaint.exe")), Activation::Disabled);
+        let apps = policy(r#"{ "mode": "apps", "allow": ["mspaint"] }"#);
+        assert_eq!(
+            apps.resolve(1.0, Some("mspaint.exe")),
+            Activation::Immediate
+        );
+        assert_eq!(apps.resolve(1.0, Some("notepad")), Activation::Disabled);
+        assert_eq!(apps.resolve(1.0, None), Activation::Disabled);
+    }
+
+    #[test]
+    fn rejects_empty_or_unknown_fields() {
+        assert!(policy(r#"{ "mode": "apps" }"#).validate().is_err());
+        assert!(policy(r#"{ "mode": "apps", "deny": [" .exe"] }"#)
+            .validate()
+            .is_err());
+        let unknown = r#"{ "mode": "dwell", "hold": 300 }"#;
+        assert!(serde_json::from_str::<ActivationPolicy>(unknown).is_err());
+    }
+}
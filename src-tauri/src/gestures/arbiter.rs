@@ -0,0 +1,257 @@
+//! 识别结果仲裁
+//!
+//! 所有已注册的识别器都会分析同一笔画，各自给出带置信度的候选，
+//! 注册顺序不再影响结果：
+//!   1. 低于识别器自身阈值（`min_score`）的候选淘汰
+//!   2. 剩余候选中分数最高的胜出
+//!   3. 最高分与次高分相差不到 `TIE_MARGIN` 时判为"有歧义"，不触发任何手势
+//!
+//! 落选的候选连同原因一起返回，由 global.rs 通过 "gesture-debug" 事件发给前端。
+
+use super::recognizers::{Candidate, GestureResult};
+
+/// 判定为歧义的分差
+pub const TIE_MARGIN: f64 = 0.05;
+
+/// 某个识别器给出的候选及其阈值
+#[derive(Clone, Debug)]
+pub struct Scored {
+    pub recognizer: &'static str,
+    pub candidate: Candidate,
+    pub threshold: f64,
+}
+
+impl Scored {
+    pub fn new(recognizer: &'static str, candidate: Candidate, threshold: f64) -> Self {
+        Self {
+            recognizer,
+            candidate,
+            threshold,
+        }
+    }
+
+    fn summary(&self) -> CandidateSummary {
+        CandidateSummary {
+            recognizer: self.recognizer,
+            gesture: self.candidate.result.kind(),
+            score: self.candidate.score,
+            threshold: self.threshold,
+        }
+    }
+}
+
+/// 候选摘要（发给前端）
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct CandidateSummary {
+    pub recognizer: &'static str,
+    /// 手势类型，如 "circle"
+    pub gesture: &'static str,
+    pub score: f64,
+    pub threshold: f64,
+}
+
+/// 候选落选的原因
+#[derive(serde::Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RejectReason {
+    /// 低于识别器阈值
+    BelowThreshold,
+    /// 分数低于胜出者
+    Outscored,
+    /// 与其它候选分数太接近
+    Ambiguous,
+}
+
+/// 落选的候选
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct RejectedCandidate {
+    #[serde(flatten)]
+    pub candidate: CandidateSummary,
+    pub reason: RejectReason,
+}
+
+/// 仲裁结论
+#[derive(Debug)]
+pub enum Verdict {
+    /// 有唯一胜出者
+    Accepted {
+        recognizer: &'static str,
+        result: GestureResult,
+        score: f64,
+    },
+    /// 多个候选分数太接近，按分数从高到低排列
+    Ambiguous(Vec<CandidateSummary>),
+    /// 没有达到阈值的候选
+    Unrecognized,
+}
+
+/// 仲裁结果：结论 + 所有落选的候选
+#[derive(Debug)]
+pub struct Arbitration {
+    pub verdict: Verdict,
+    pub rejected: Vec<RejectedCandidate>,
+}
+
+impl Arbitration {
+    /// 是否有结论（识别成功或有歧义）
+    pub fn concluded(&self) -> bool {
+        !matches!(self.verdict, Verdict::Unrecognized)
+    }
+}
+
+/// 在所有候选中挑出最佳结果
+pub fn arbitrate(candidates: Vec<Scored>) -> Arbitration {
+    let mut rejected = Vec::new();
+    let mut passed = Vec::new();
+    for scored in candidates {
+        if scored.candidate.score >= scored.threshold {
+            passed.push(scored);
+        } else {
+            rejected.push(reject(&scored, RejectReason::BelowThreshold));
+        }
+    }
+    passed.sort_by(|a, b| b.candidate.score.total_cmp(&a.candidate.score));
+
+    let mut passed = passed.into_iter();
+    let Some(best) = passed.next() else {
+        return Arbitration {
+            verdict: Verdict::Unrecognized,
+            rejected,
+        };
+    };
+    let (tied, outscored): (Vec<Scored>, Vec<Scored>) =
+        passed.partition(|s| best.candidate.score - s.candidate.score < TIE_MARGIN);
+    rejected.extend(outscored.iter().map(|s| reject(s, RejectReason::Outscored)));
+
+    if tied.is_empty() {
+        return Arbitration {
+            verdict: Verdict::Accepted {
+                recognizer: best.recognizer,
+                result: best.candidate.result,
+                score: best.candidate.score,
+            },
+            rejected,
+        };
+    }
+
+    let contenders: Vec<Scored> = std::iter::once(best).chain(tied).collect();
+    rejected.extend(
+        contenders
+            .iter()
+            .map(|s| reject(s, RejectReason::Ambiguous)),
+    );
+    Arbitration {
+        verdict: Verdict::Ambiguous(contenders.iter().map(Scored::summary).collect()),
+        rejected,
+    }
+}
+
+fn reject(scored: &Scored, reason: RejectReason) -> RejectedCandidate {
+    RejectedCandidate {
+        candidate: scored.summary(),
+        reason,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scored(recognizer: &'static str, score: f64, threshold: f64) -> Scored {
+        let result = GestureResult::Heart {
+            center_x: 0.0,
+            center_y: 0.0,
+            size: 100.0,
+        };
+        Scored::new(recognizer, Candidate::new(result, score), threshold)
+    }
+
+    fn reasons(arbitration: &Arbitration) -> Vec<(&'static str, RejectReason)> {
+        arbitration
+            .rejected
+            .iter()
+            .map(|r| (r.candidate.recognizer, r.reason))
+            .collect()
+    }
+
+    #[test]
+    fn empty_is_unrecognized() {
+        let arbitration = arbitrate(Vec::new());
+        assert!(matches!(arbitration.verdict, Verdict::Unrecognized));
+        assert!(arbitration.rejected.is_empty());
+    }
+
+    #[test]
+    fn below_threshold_is_rejected() {
+        let arbitration = arbitrate(vec![scored("a", 0.6, 0.7), scored("b", 0.4, 0.5)]);
+        assert!(matches!(arbitration.verdict, Verdict::Unrecognized));
+        assert_eq!(
+            reasons(&arbitration),
+            [
+                ("a", RejectReason::BelowThreshold),
+                ("b", RejectReason::BelowThreshold)
+            ]
+        );
+    }
+
+    #[test]
+    fn highest_score_wins_regardless_of_order() {
+        let arbitration = arbitrate(vec![
+            scored("a", 0.6, 0.5),
+            scored("b", 0.9, 0.5),
+            scored("c", 0.3, 0.5),
+        ]);
+        assert!(matches!(
+            arbitration.verdict,
+            Verdict::Accepted {
+                recognizer: "b",
+                ..
+            }
+        ));
+        assert_eq!(
+            reasons(&arbitration),
+            [
+                ("c", RejectReason::BelowThreshold),
+                ("a", RejectReason::Outscored)
+            ]
+        );
+    }
+
+    #[test]
+    fn scores_within_tie_margin_are_ambiguous() {
+        let close = 0.8 - TIE_MARGIN / 2.0;
+        let arbitration = arbitrate(vec![
+            scored("a", close, 0.5),
+            scored("b", 0.8, 0.5),
+            scored("c", 0.6, 0.5),
+        ]);
+        let Verdict::Ambiguous(contenders) = &arbitration.verdict else {
+            panic!("{:?}", arbitration.verdict);
+        };
+        let names: Vec<_> = contenders.iter().map(|c| c.recognizer).collect();
+        assert_eq!(names, ["b", "a"]);
+        assert_eq!(
+            reasons(&arbitration),
+            [
+                ("c", RejectReason::Outscored),
+                ("b", RejectReason::Ambiguous),
+                ("a", RejectReason::Ambiguous)
+            ]
+        );
+    }
+
+    #[test]
+    fn margin_just_outside_tie_is_accepted() {
+        let arbitration = arbitrate(vec![
+            scored("a", 0.8, 0.5),
+            scored("b", 0.8 - TIE_MARGIN - 0.01, 0.5),
+        ]);
+        assert!(matches!(
+            arbitration.verdict,
+            Verdict::Accepted {
+                recognizer: "a",
+                ..
+            }
+        ));
+    }
+}
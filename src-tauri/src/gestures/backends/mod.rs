@@ -0,0 +1,35 @@
+//! 输入后端实现
+//!
+//! - `windows`：WH_MOUSE_LL 低级鼠标钩子（支持吞没）
+//! - `x11`：XInput2 原始指针事件（Linux / X11，不支持吞没）
+//! - `synthetic`：内存中的脚本化事件源，用于在任意平台上驱动手势状态机
+
+pub mod synthetic;
+#[cfg(windows)]
+pub mod windows;
+#[cfg(target_os = "linux")]
+pub mod x11;
+
+use super::input::InputBackend;
+
+/// 返回当前平台默认的全局输入后端，没有可用实现时返回 None
+pub fn platform_backend() -> Option<Box<dyn InputBackend>> {
+    #[cfg(windows)]
+    {
+        Some(Box::new(windows::WindowsHookBackend))
+    }
+    #[cfg(target_os = "linux")]
+    {
+        match x11::X11Backend::connect() {
+            Ok(backend) => Some(Box::new(backend)),
+            Err(e) => {
+                eprintln!("[gesture] ❌ X11 输入后端初始化失败：{}", e);
+                None
+            }
+        }
+    }
+    #[cfg(not(any(windows, target_os = "linux")))]
+    {
+        None
+    }
+}
@@ -0,0 +1,166 @@
+//! 合成输入后端
+//!
+//! 按顺序回放一段预先写好的 `MouseEvent` 脚本，并记录每个事件的吞没判定。
+//! 不依赖任何系统 API，可在 Linux CI 上驱动完整的拖拽 / 绘制 / 悬停 / 右键流程。
+
+use std::sync::{Arc, Mutex};
+
+use crate::gestures::input::{EventSink, InputBackend, MouseEvent};
+
+pub struct SyntheticBackend {
+    script: Vec<MouseEvent>,
+    swallowed: Arc<Mutex<Vec<bool>>>,
+}
+
+impl SyntheticBackend {
+    pub fn new(script: impl IntoIterator<Item = MouseEvent>) -> Self {
+        Self {
+            script: script.into_iter().collect(),
+            swallowed: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// 每个脚本事件对应的吞没判定（与脚本一一对应，run 结束后读取）
+    pub fn swallow_log(&self) -> Arc<Mutex<Vec<bool>>> {
+        Arc::clone(&self.swallowed)
+    }
+}
+
+impl InputBackend for SyntheticBackend {
+    fn name(&self) -> &'static str {
+        "synthetic"
+    }
+
+    fn can_swallow(&self) -> bool {
+        true
+    }
+
+    fn run(self: Box<Self>, mut sink: EventSink) {
+        for event in self.script {
+            let swallow = sink(event);
+            self.swallowed.lock().unwrap().push(swallow);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::TAU;
+
+    use super::*;
+    use crate::gestures::input::MouseButton;
+    use crate::gestures::preprocess;
+    use crate::gestures::recognizers::circle::CircleRecognizer;
+    use crate::gestures::recognizers::{GestureRecognizer, GestureResult};
+    use crate::gestures::state::{GestureOutput, GestureState};
+
+    const PET: (f64, f64) = (100.0, 100.0);
+    const HIT_RADIUS: f64 = 65.0;
+
+    /// 用合成后端回放脚本驱动状态机，返回全部输出与吞没记录
+    fn drive(script: Vec<MouseEvent>) -> (Vec<GestureOutput>, Vec<bool>) {
+        let backend = SyntheticBackend::new(script);
+        let log = backend.swallow_log();
+        let outputs = Arc::new(Mutex::new(Vec::new()));
+
+        let mut state = GestureState::default();
+        state.set_pet(PET.0, PET.1, HIT_RADIUS);
+        let sink_outputs = Arc::clone(&outputs);
+        let sink: EventSink = Box::new(move |event| {
+            let reaction = state.handle(&event);
+            sink_outputs.lock().unwrap().extend(reaction.outputs);
+            reaction.swallow
+        });
+        Box::new(backend).run(sink);
+
+        let outputs = std::mem::take(&mut *outputs.lock().unwrap());
+        let swallowed = log.lock().unwrap().clone();
+        (outputs, swallowed)
+    }
+
+    #[test]
+    fn hover_enter_and_leave() {
+        let (outputs, swallowed) = drive(vec![
+            MouseEvent::moved(400.0, 400.0),
+            MouseEvent::moved(110.0, 100.0),
+            MouseEvent::moved(120.0, 100.0),
+            MouseEvent::moved(400.0, 100.0),
+        ]);
+        assert_eq!(
+            outputs,
+            vec![GestureOutput::HoverEnter, GestureOutput::HoverLeave]
+        );
+        assert_eq!(swallowed, vec![false; 4]);
+    }
+
+    #[test]
+    fn drag_start_move_end() {
+        let (outputs, swallowed) = drive(vec![
+            MouseEvent::down(MouseButton::Left, 110.0, 100.0).at(0),
+            MouseEvent::moved(160.0, 100.0).at(20),
+            MouseEvent::moved(210.0, 120.0).at(40),
+            MouseEvent::up(MouseButton::Left, 210.0, 120.0).at(60),
+        ]);
+        assert_eq!(outputs[0], GestureOutput::DragStart { x: 100.0, y: 100.0 });
+        assert_eq!(outputs[1], GestureOutput::DragMove { x: 150.0, y: 100.0 });
+        assert_eq!(outputs[2], GestureOutput::DragMove { x: 200.0, y: 120.0 });
+        assert!(matches!(outputs[3], GestureOutput::HoverEnter));
+        assert!(matches!(&outputs[4], GestureOutput::DragPath(path) if path.len() == 3));
+        assert!(matches!(
+            outputs[5],
+            GestureOutput::DragEnd { x, y, .. } if (x, y) == (200.0, 120.0)
+        ));
+        // 左键拖拽不吞没（宠物窗口自己接收）
+        assert_eq!(swallowed, vec![false; 4]);
+    }
+
+    #[test]
+    fn draw_then_recognize_circle() {
+        let (cx, cy, r) = (600.0, 500.0, 120.0);
+        let mut script = vec![MouseEvent::down(MouseButton::Left, cx + r, cy).at(0)];
+        for i in 1..=64 {
+            let angle = TAU * i as f64 / 64.0;
+            script.push(MouseEvent::moved(cx + r * angle.cos(), cy + r * angle.sin()).at(i * 10));
+        }
+        script.push(MouseEvent::up(MouseButton::Left, cx + r, cy).at(650));
+        let (outputs, swallowed) = drive(script);
+
+        assert_eq!(outputs[0], GestureOutput::StrokeBegin { x: cx + r, y: cy });
+        let Some(GestureOutput::Stroke(stroke)) = outputs.last() else {
+            panic!("抬笔后应输出整笔轨迹：{:?}", outputs.last());
+        };
+        assert_eq!(stroke.duration_ms(), 650.0);
+        assert!(swallowed.iter().all(|&s| !s));
+
+        let recognizer = CircleRecognizer::default();
+        let stroke = preprocess::apply(stroke, recognizer.stages(), 1.0);
+        let candidate = recognizer.analyze(&stroke, 1.0).expect("应识别为圆");
+        let GestureResult::Circle {
+            center_x,
+            center_y,
+            radius,
+            ..
+        } = candidate.result
+        else {
+            panic!("{:?}", candidate.result);
+        };
+        assert!((center_x - cx).abs() < 3.0 && (center_y - cy).abs() < 3.0);
+        assert!((radius - r).abs() < 5.0);
+    }
+
+    #[test]
+    fn right_click_over_pet_is_swallowed() {
+        let (outputs, swallowed) = drive(vec![
+            MouseEvent::down(MouseButton::Right, 110.0, 100.0),
+            MouseEvent::up(MouseButton::Right, 400.0, 100.0),
+            // 宠物外的右键原样放行
+            MouseEvent::down(MouseButton::Right, 400.0, 400.0),
+            MouseEvent::up(MouseButton::Right, 400.0, 400.0),
+        ]);
+        assert_eq!(
+            outputs,
+            vec![GestureOutput::RightClick { x: PET.0, y: PET.1 }]
+        );
+        assert_eq!(swallowed, vec![true, true, false, false]);
+    }
+}
@@ -0,0 +1,190 @@
+//! Windows 输入后端 —— 直接调用 Windows API（WH_MOUSE_LL）
+//!
+//! 钩子回调中 MSLLHOOKSTRUCT.pt 返回的是 **物理像素** 坐标，
+//! 原样归一化为 `MouseEvent` 交给 sink；sink 返回 true 时吞没该消息。
+//! 滚轮增量取 mouseData 的高位字（有符号），竖直方向取反成"向下为正"；
+//! 侧键消息的 mouseData 高位字为 XBUTTON1 / XBUTTON2。
+//!
+//! 另装一个 WH_KEYBOARD_LL 钩子，只上报 Esc 按下（取消绘制），其他按键一概不看、不吞没。
+
+use std::sync::Mutex;
+
+use windows::{
+    Win32::Foundation::*,
+    Win32::System::LibraryLoader::GetModuleHandleW,
+    Win32::UI::Input::KeyboardAndMouse::{
+        GetAsyncKeyState, VIRTUAL_KEY, VK_CONTROL, VK_ESCAPE, VK_LWIN, VK_MENU, VK_RWIN, VK_SHIFT,
+    },
+    Win32::UI::WindowsAndMessaging::*,
+};
+
+use crate::gestures::input::{
+    EventSink, InputBackend, Modifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+
+/// 钩子回调是无上下文的 extern fn，只能通过静态变量拿到 sink
+static HOOK_SINK: Mutex<Option<EventSink>> = Mutex::new(None);
+
+pub struct WindowsHookBackend;
+
+impl InputBackend for WindowsHookBackend {
+    fn name(&self) -> &'static str {
+        "windows-ll-hook"
+    }
+
+    fn can_swallow(&self) -> bool {
+        true
+    }
+
+    fn run(self: Box<Self>, sink: EventSink) {
+        *HOOK_SINK.lock().unwrap() = Some(sink);
+
+        unsafe {
+            let hmod = GetModuleHandleW(None).expect("[gesture] GetModuleHandleW 失败");
+
+            let hook = SetWindowsHookExW(WH_MOUSE_LL, Some(mouse_hook_proc), hmod, 0);
+
+            match hook {
+                Ok(h) => {
+                    eprintln!("[gesture] ✅ SetWindowsHookExW 成功，句柄={:?}", h);
+                    let keyboard_hook =
+                        SetWindowsHookExW(WH_KEYBOARD_LL, Some(keyboard_hook_proc), hmod, 0)
+                            .inspect_err(|e| {
+                                eprintln!(
+                                    "[gesture] ⚠️ 键盘钩子安装失败，Esc 无法取消绘制：{:?}",
+                                    e
+                                );
+                            })
+                            .ok();
+                    let mut msg = MSG::default();
+                    eprintln!("[gesture] 开始消息泵，等待鼠标事件...");
+                    loop {
+                        let ret = GetMessageW(&mut msg, None, 0, 0);
+                        match ret.0 {
+                            -1 | 0 => break,
+                            _ => {
+                                let _ = TranslateMessage(&msg);
+                                DispatchMessageW(&msg);
+                            }
+                        }
+                    }
+                    if let Some(keyboard_hook) = keyboard_hook {
+                        let _ = UnhookWindowsHookEx(keyboard_hook);
+                    }
+                    let _ = UnhookWindowsHookEx(h);
+                }
+                Err(e) => {
+                    eprintln!("[gesture] ❌ SetWindowsHookExW 失败：{:?}", e);
+                }
+            }
+        }
+
+        *HOOK_SINK.lock().unwrap() = None;
+    }
+}
+
+// ── 钩子回调 ────────────────────────────────────────────────────────────────
+
+unsafe extern "system" fn mouse_hook_proc(
+    n_code: i32,
+    w_param: WPARAM,
+    l_param: LPARAM,
+) -> LRESULT {
+    if n_code >= 0 {
+        let mouse_data = &*(l_param.0 as *const MSLLHOOKSTRUCT);
+        let x = mouse_data.pt.x as f64;
+        let y = mouse_data.pt.y as f64;
+
+        let kind = match w_param.0 as u32 {
+            WM_MOUSEMOVE => Some(MouseEventKind::Move),
+            WM_LBUTTONDOWN => Some(MouseEventKind::Down(MouseButton::Left)),
+            WM_LBUTTONUP => Some(MouseEventKind::Up(MouseButton::Left)),
+            WM_RBUTTONDOWN => Some(MouseEventKind::Down(MouseButton::Right)),
+            WM_RBUTTONUP => Some(MouseEventKind::Up(MouseButton::Right)),
+            WM_MBUTTONDOWN => Some(MouseEventKind::Down(MouseButton::Middle)),
+            WM_MBUTTONUP => Some(MouseEventKind::Up(MouseButton::Middle)),
+            WM_XBUTTONDOWN => x_button(mouse_data).map(MouseEventKind::Down),
+            WM_XBUTTONUP => x_button(mouse_data).map(MouseEventKind::Up),
+            WM_MOUSEWHEEL => Some(MouseEventKind::Wheel {
+                delta_x: 0,
+                delta_y: -wheel_delta(mouse_data),
+            }),
+            WM_MOUSEHWHEEL => Some(MouseEventKind::Wheel {
+                delta_x: wheel_delta(mouse_data),
+                delta_y: 0,
+            }),
+            _ => None,
+        };
+
+        if let Some(kind) = kind {
+            let mut event = MouseEvent::new(kind, x, y).at(mouse_data.time);
+            // 修饰键只在按键事件上读取，移动事件保持钩子回调尽量轻
+            if kind != MouseEventKind::Move {
+                event = event.with_modifiers(current_modifiers());
+            }
+            // 钩子回调总在同一线程上串行执行，这里的锁不会发生竞争
+            let swallow = match HOOK_SINK.lock().unwrap().as_mut() {
+                Some(sink) => sink(event),
+                None => false,
+            };
+
+            // ── 吞没判定：不传递给下层窗口 ──
+            if swallow {
+                return LRESULT(1);
+            }
+        }
+    }
+
+    CallNextHookEx(None, n_code, w_param, l_param)
+}
+
+/// 键盘钩子：只把 Esc 按下转成 `MouseEventKind::Escape`，绘制中按 sink 的结论吞没
+unsafe extern "system" fn keyboard_hook_proc(
+    n_code: i32,
+    w_param: WPARAM,
+    l_param: LPARAM,
+) -> LRESULT {
+    if n_code >= 0 && matches!(w_param.0 as u32, WM_KEYDOWN | WM_SYSKEYDOWN) {
+        let key_data = &*(l_param.0 as *const KBDLLHOOKSTRUCT);
+        if key_data.vkCode == VK_ESCAPE.0 as u32 {
+            let mut pt = POINT::default();
+            let _ = GetCursorPos(&mut pt);
+            let event =
+                MouseEvent::new(MouseEventKind::Escape, pt.x as f64, pt.y as f64).at(key_data.time);
+            let swallow = match HOOK_SINK.lock().unwrap().as_mut() {
+                Some(sink) => sink(event),
+                None => false,
+            };
+            if swallow {
+                return LRESULT(1);
+            }
+        }
+    }
+
+    CallNextHookEx(None, n_code, w_param, l_param)
+}
+
+/// 滚轮增量：mouseData 高位字（向前 / 向右为正）
+fn wheel_delta(mouse_data: &MSLLHOOKSTRUCT) -> i32 {
+    (mouse_data.mouseData >> 16) as u16 as i16 as i32
+}
+
+/// 侧键：mouseData 高位字
+fn x_button(mouse_data: &MSLLHOOKSTRUCT) -> Option<MouseButton> {
+    match (mouse_data.mouseData >> 16) as u16 {
+        XBUTTON1 => Some(MouseButton::X1),
+        XBUTTON2 => Some(MouseButton::X2),
+        _ => None,
+    }
+}
+
+/// 当前按住的修饰键
+fn current_modifiers() -> Modifiers {
+    let pressed = |key: VIRTUAL_KEY| unsafe { GetAsyncKeyState(key.0 as i32) } < 0;
+    Modifiers {
+        ctrl: pressed(VK_CONTROL),
+        shift: pressed(VK_SHIFT),
+        alt: pressed(VK_MENU),
+        meta: pressed(VK_LWIN) || pressed(VK_RWIN),
+    }
+}
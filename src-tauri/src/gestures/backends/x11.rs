@@ -0,0 +1,316 @@
+//! X11 输入后端 —— XInput2 原始事件（XI_RawMotion / XI_RawButtonPress / XI_RawButtonRelease）
+//!
+//! 在根窗口上订阅所有主设备的原始指针事件，不需要任何 grab，
+//! 因此不会干扰其他程序的正常输入。原始事件本身不带屏幕坐标：
+//!   - 按键 / Esc 事件通过 QueryPointer 读取指针在根窗口上的位置（物理像素），
+//!     顺带读取同一应答里的修饰键状态
+//!   - 相对设备（鼠标、触摸板）的移动事件累加加速后的位移，不产生往返请求；
+//!     每隔 `RESYNC_INTERVAL_MS` 用 QueryPointer 校准一次，消除指针屏障、屏幕边缘造成的累计误差；
+//!     累加结果钳制在虚拟桌面内，桌面范围在每次校准时从 `display::topology()` 重新读取（随 RandR 变化更新）
+//!   - 绝对设备（数位板、XTEST 虚拟设备）和查不到模式的设备仍逐个事件 QueryPointer
+//!
+//! 滚轮在核心协议里是按键 4 ~ 7，每次按下算一格（`WHEEL_DELTA`），释放忽略。
+//!
+//! 另订阅 XI_RawKeyPress，只上报 Esc（取消绘制），其他按键一概忽略。
+//!
+//! 吞没说明：X11 无法在不 grab 的前提下拦截事件，本后端忽略 sink 的返回值。
+//! 鼠标悬停在宠物上时前端会关闭主窗口的鼠标穿透，宠物上的右键 / 滚轮 / 中键 / 侧键
+//! 由覆盖窗口自己接收；但离开宠物的事件照常送达下层窗口：
+//! 绘制中用右键取消时下层应用仍会弹出右键菜单，Esc 也会传给前台应用。
+//! 因此 `can_swallow` 返回 false，用中键画手势的配置会被拒绝（中键会在下层窗口粘贴 PRIMARY 选区）。
+//!
+//! 可在 Xvfb 下配合 `xdotool mousemove / mousedown / mouseup` 驱动调试。
+
+use std::collections::HashMap;
+
+use x11rb::connection::{Connection, RequestConnection};
+use x11rb::protocol::xinput::{self, ConnectionExt as _};
+use x11rb::protocol::xproto::{ConnectionExt as _, KeyButMask, Window};
+use x11rb::protocol::Event;
+use x11rb::rust_connection::RustConnection;
+
+use crate::display::{self, PhysicalRect};
+use crate::gestures::input::{
+    EventSink, InputBackend, Modifiers, MouseButton, MouseEvent, MouseEventKind, WHEEL_DELTA,
+};
+
+/// X11 核心协议的按键编号
+const BUTTON_LEFT: u32 = 1;
+const BUTTON_MIDDLE: u32 = 2;
+const BUTTON_RIGHT: u32 = 3;
+const BUTTON_WHEEL_UP: u32 = 4;
+const BUTTON_WHEEL_DOWN: u32 = 5;
+const BUTTON_WHEEL_LEFT: u32 = 6;
+const BUTTON_WHEEL_RIGHT: u32 = 7;
+/// 侧键（后退 / 前进）
+const BUTTON_X1: u32 = 8;
+const BUTTON_X2: u32 = 9;
+
+/// Escape 的 keysym（XK_Escape）
+const KEYSYM_ESCAPE: u32 = 0xff1b;
+
+/// 累加位移时用 QueryPointer 校准位置的间隔（毫秒，按事件时间戳）
+const RESYNC_INTERVAL_MS: u32 = 250;
+
+pub struct X11Backend {
+    conn: RustConnection,
+    root: Window,
+    /// Esc 对应的 keycode；查不到时不上报 Esc
+    escape_keycode: Option<u32>,
+}
+
+impl X11Backend {
+    /// 连接 $DISPLAY，确认 XInput 2.2 可用并订阅根窗口的原始指针事件
+    pub fn connect() -> Result<Self, String> {
+        let (conn, screen_num) = x11rb::connect(None).map_err(|e| e.to_string())?;
+        let screen = &conn.setup().roots[screen_num];
+        let root = screen.root;
+
+        if conn
+            .extension_information(xinput::X11_EXTENSION_NAME)
+            .map_err(|e| e.to_string())?
+            .is_none()
+        {
+            return Err("X server 不支持 XInputExtension".into());
+        }
+
+        let version = conn
+            .xinput_xi_query_version(2, 2)
+            .map_err(|e| e.to_string())?
+            .reply()
+            .map_err(|e| e.to_string())?;
+        if (version.major_version, version.minor_version) < (2, 2) {
+            return Err(format!(
+                "XInput 版本过低 ({}.{})，需要 2.2+",
+                version.major_version, version.minor_version
+            ));
+        }
+
+        let mask = xinput::XIEventMask::RAW_MOTION
+            | xinput::XIEventMask::RAW_BUTTON_PRESS
+            | xinput::XIEventMask::RAW_BUTTON_RELEASE
+            | xinput::XIEventMask::RAW_KEY_PRESS
+            | xinput::XIEventMask::HIERARCHY;
+        conn.xinput_xi_select_events(
+            root,
+            &[xinput::EventMask {
+                deviceid: xinput::Device::ALL_MASTER.into(),
+                mask: vec![mask],
+            }],
+        )
+        .map_err(|e| e.to_string())?
+        .check()
+        .map_err(|e| e.to_string())?;
+
+        let escape_keycode = find_keycode(&conn, KEYSYM_ESCAPE);
+        if escape_keycode.is_none() {
+            eprintln!("[gesture] ⚠️ 找不到 Esc 的 keycode，Esc 无法取消绘制");
+        }
+
+        Ok(Self {
+            conn,
+            root,
+            escape_keycode,
+        })
+    }
+
+    /// 读取指针当前在根窗口上的位置（物理像素）与按住的修饰键
+    fn pointer_state(&self) -> Option<(f64, f64, Modifiers)> {
+        let reply = self.conn.query_pointer(self.root).ok()?.reply().ok()?;
+        let mask = reply.mask;
+        let modifiers = Modifiers {
+            ctrl: mask.contains(KeyButMask::CONTROL),
+            shift: mask.contains(KeyButMask::SHIFT),
+            // 常见键位映射下 Mod1 = Alt，Mod4 = Super
+            alt: mask.contains(KeyButMask::MOD1),
+            meta: mask.contains(KeyButMask::MOD4),
+        };
+        Some((reply.root_x as f64, reply.root_y as f64, modifiers))
+    }
+
+    /// 查询从设备的第 0 号轴是否为相对模式；XTEST 虚拟设备按绝对设备处理
+    fn is_relative(&self, deviceid: xinput::DeviceId) -> Option<bool> {
+        let reply = self
+            .conn
+            .xinput_xi_query_device(deviceid)
+            .ok()?
+            .reply()
+            .ok()?;
+        let info = reply
+            .infos
+            .into_iter()
+            .find(|info| info.deviceid == deviceid)?;
+        if String::from_utf8_lossy(&info.name).contains("XTEST") {
+            return Some(false);
+        }
+        info.classes.iter().find_map(|class| match &class.data {
+            xinput::DeviceClassData::Valuator(v) if v.number == 0 => {
+                Some(v.mode == xinput::ValuatorMode::RELATIVE)
+            }
+            _ => None,
+        })
+    }
+}
+
+/// 由原始移动事件推算出的指针位置
+#[derive(Default)]
+struct Cursor {
+    /// 最近一次已知的位置（物理像素）；尚未同步时为 None
+    position: Option<(f64, f64)>,
+    /// 上次用 QueryPointer 校准的事件时间
+    synced_at: u32,
+    /// 虚拟桌面矩形（物理像素），校准时刷新，累加位移时用于钳制
+    bounds: PhysicalRect,
+    /// 从设备 id → 是否为相对设备（None 表示查询失败）
+    relative: HashMap<xinput::DeviceId, Option<bool>>,
+}
+
+impl Cursor {
+    fn sync(&mut self, x: f64, y: f64, time: u32) {
+        self.position = Some((x, y));
+        self.synced_at = time;
+        self.bounds = display::topology().virtual_bounds;
+    }
+
+    /// 累加一次相对位移；需要校准时返回 None
+    fn advance(&mut self, delta: (f64, f64), time: u32) -> Option<(f64, f64)> {
+        let (x, y) = self.position?;
+        if time.wrapping_sub(self.synced_at) >= RESYNC_INTERVAL_MS {
+            return None;
+        }
+        let bounds = &self.bounds;
+        let x = (x + delta.0).clamp(bounds.x as f64, (bounds.right() - 1) as f64);
+        let y = (y + delta.1).clamp(bounds.y as f64, (bounds.bottom() - 1) as f64);
+        self.position = Some((x, y));
+        Some((x, y))
+    }
+}
+
+impl InputBackend for X11Backend {
+    fn name(&self) -> &'static str {
+        "x11-xinput2"
+    }
+
+    fn can_swallow(&self) -> bool {
+        false
+    }
+
+    fn run(self: Box<Self>, mut sink: EventSink) {
+        eprintln!("[gesture] ✅ XInput2 原始事件订阅成功，等待鼠标事件...");
+        let mut cursor = Cursor::default();
+        loop {
+            let event = match self.conn.wait_for_event() {
+                Ok(event) => event,
+                Err(e) => {
+                    eprintln!("[gesture] ❌ X11 连接中断：{:?}", e);
+                    break;
+                }
+            };
+
+            let (kind, time) = match event {
+                Event::XinputRawMotion(e) => {
+                    let relative = *cursor
+                        .relative
+                        .entry(e.sourceid)
+                        .or_insert_with(|| self.is_relative(e.sourceid));
+                    let advanced = match relative {
+                        Some(true) => cursor.advance(motion_delta(&e), e.time),
+                        _ => None,
+                    };
+                    if let Some((x, y)) = advanced {
+                        // X11 下无法吞没，忽略返回值（见模块说明）
+                        let _ = sink(MouseEvent::new(MouseEventKind::Move, x, y).at(e.time));
+                        continue;
+                    }
+                    (MouseEventKind::Move, e.time)
+                }
+                Event::XinputRawButtonPress(e) => match map_button(e.detail) {
+                    Some(button) => (MouseEventKind::Down(button), e.time),
+                    None => match map_wheel(e.detail) {
+                        Some(kind) => (kind, e.time),
+                        None => continue,
+                    },
+                },
+                Event::XinputRawButtonRelease(e) => match map_button(e.detail) {
+                    Some(button) => (MouseEventKind::Up(button), e.time),
+                    None => continue,
+                },
+                Event::XinputRawKeyPress(e) if Some(e.detail) == self.escape_keycode => {
+                    (MouseEventKind::Escape, e.time)
+                }
+                Event::XinputHierarchy(_) => {
+                    // 设备插拔后 id 可能被复用，重新查询模式
+                    cursor.relative.clear();
+                    continue;
+                }
+                _ => continue,
+            };
+
+            if let Some((x, y, modifiers)) = self.pointer_state() {
+                cursor.sync(x, y, time);
+                let mut event = MouseEvent::new(kind, x, y).at(time);
+                if kind != MouseEventKind::Move {
+                    event = event.with_modifiers(modifiers);
+                }
+                // X11 下无法吞没，忽略返回值（见模块说明）
+                let _ = sink(event);
+            }
+        }
+    }
+}
+
+/// 原始移动事件里第 0 / 1 号轴加速后的位移；没有对应轴时为 0
+fn motion_delta(event: &xinput::RawMotionEvent) -> (f64, f64) {
+    let mask = event.valuator_mask.first().copied().unwrap_or(0);
+    let axis = |number: u32| {
+        if mask & (1 << number) == 0 {
+            return 0.0;
+        }
+        // axisvalues 只包含掩码中置位的轴，按轴号顺序排列
+        let index = (mask & ((1 << number) - 1)).count_ones() as usize;
+        event
+            .axisvalues
+            .get(index)
+            .map_or(0.0, |v| v.integral as f64 + v.frac as f64 / 4_294_967_296.0)
+    };
+    (axis(0), axis(1))
+}
+
+fn map_button(detail: u32) -> Option<MouseButton> {
+    match detail {
+        BUTTON_LEFT => Some(MouseButton::Left),
+        BUTTON_MIDDLE => Some(MouseButton::Middle),
+        BUTTON_RIGHT => Some(MouseButton::Right),
+        BUTTON_X1 => Some(MouseButton::X1),
+        BUTTON_X2 => Some(MouseButton::X2),
+        _ => None,
+    }
+}
+
+fn map_wheel(detail: u32) -> Option<MouseEventKind> {
+    let (delta_x, delta_y) = match detail {
+        BUTTON_WHEEL_UP => (0, -WHEEL_DELTA),
+        BUTTON_WHEEL_DOWN => (0, WHEEL_DELTA),
+        BUTTON_WHEEL_LEFT => (-WHEEL_DELTA, 0),
+        BUTTON_WHEEL_RIGHT => (WHEEL_DELTA, 0),
+        _ => return None,
+    };
+    Some(MouseEventKind::Wheel { delta_x, delta_y })
+}
+
+/// 在键盘映射中查找 keysym 对应的 keycode（只看每个 keycode 的第一个 keysym）
+fn find_keycode(conn: &RustConnection, keysym: u32) -> Option<u32> {
+    let setup = conn.setup();
+    let (min, max) = (setup.min_keycode, setup.max_keycode);
+    let reply = conn
+        .get_keyboard_mapping(min, max - min + 1)
+        .ok()?
+        .reply()
+        .ok()?;
+    let per_keycode = (reply.keysyms_per_keycode as usize).max(1);
+    reply
+        .keysyms
+        .chunks(per_keycode)
+        .position(|syms| syms.first() == Some(&keysym))
+        .map(|index| min as u32 + index as u32)
+}
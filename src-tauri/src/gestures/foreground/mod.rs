@@ -0,0 +1,27 @@
+//! 前台应用查询 —— 供按应用过滤的激活策略使用
+//!
+//! 只在工作线程处理左键按下时调用（不在钩子线程里做系统调用），
+//! 返回统一写法的进程名（小写、不带 ".exe"，见 `activation::normalize_app`）。
+//!
+//! - Windows：GetForegroundWindow → 所属进程的映像路径
+//! - Linux：EWMH `_NET_ACTIVE_WINDOW` → `_NET_WM_PID` → /proc/<pid>/comm，取不到 PID 时回退为 WM_CLASS
+//! - 其他平台：None
+
+#[cfg(windows)]
+mod windows;
+#[cfg(target_os = "linux")]
+mod x11;
+
+use super::activation::normalize_app;
+
+/// 当前前台应用的进程名；查询失败时返回 None
+pub fn foreground_app() -> Option<String> {
+    #[cfg(windows)]
+    let name = windows::foreground_process_name();
+    #[cfg(target_os = "linux")]
+    let name = x11::foreground_process_name();
+    #[cfg(not(any(windows, target_os = "linux")))]
+    let name: Option<String> = None;
+
+    name.map(|name| normalize_app(&name))
+}
@@ -0,0 +1,73 @@
+//! Linux / X11 前台应用查询
+//!
+//! 读取根窗口的 EWMH `_NET_ACTIVE_WINDOW`，再读该窗口的 `_NET_WM_PID`
+//! 并从 /proc/<pid>/comm 取进程名；窗口没有 PID（远程客户端等）时回退为 WM_CLASS 的类名。
+//! 连接在第一次查询时建立并复用，出错后丢弃，下次查询重新连接。
+
+use std::sync::Mutex;
+
+use x11rb::connection::Connection;
+use x11rb::errors::ReplyError;
+use x11rb::protocol::xproto::{AtomEnum, ConnectionExt as _, Window};
+use x11rb::rust_connection::RustConnection;
+
+static CONNECTION: Mutex<Option<(RustConnection, Window)>> = Mutex::new(None);
+
+/// 前台窗口所属进程名；窗口管理器不支持 EWMH 时返回 None
+pub(super) fn foreground_process_name() -> Option<String> {
+    let mut guard = CONNECTION.lock().unwrap();
+    if guard.is_none() {
+        let (conn, screen_num) = x11rb::connect(None).ok()?;
+        let root = conn.setup().roots[screen_num].root;
+        *guard = Some((conn, root));
+    }
+    let (conn, root) = guard.as_ref()?;
+    match query(conn, *root) {
+        Ok(name) => name,
+        Err(e) => {
+            eprintln!("[gesture] ⚠️ 查询前台应用失败：{}", e);
+            *guard = None;
+            None
+        }
+    }
+}
+
+fn query(conn: &RustConnection, root: Window) -> Result<Option<String>, ReplyError> {
+    let Some(window) = read_window_property(conn, root, b"_NET_ACTIVE_WINDOW")? else {
+        return Ok(None);
+    };
+    if window == 0 {
+        return Ok(None);
+    }
+    if let Some(pid) = read_window_property(conn, window, b"_NET_WM_PID")? {
+        if let Ok(comm) = std::fs::read_to_string(format!("/proc/{}/comm", pid)) {
+            return Ok(Some(comm.trim().to_string()));
+        }
+    }
+    // WM_CLASS 为 "实例名\0类名\0"，取类名
+    let reply = conn
+        .get_property(false, window, AtomEnum::WM_CLASS, AtomEnum::STRING, 0, 256)?
+        .reply()?;
+    let class = reply
+        .value
+        .split(|&b| b == 0)
+        .rfind(|part| !part.is_empty())
+        .map(|part| String::from_utf8_lossy(part).into_owned());
+    Ok(class)
+}
+
+/// 读取窗口上的单个 32 位属性（窗口 ID / CARDINAL）
+fn read_window_property(
+    conn: &RustConnection,
+    window: Window,
+    name: &[u8],
+) -> Result<Option<u32>, ReplyError> {
+    let atom = conn.intern_atom(true, name)?.reply()?.atom;
+    if atom == 0 {
+        return Ok(None);
+    }
+    let reply = conn
+        .get_property(false, window, atom, AtomEnum::ANY, 0, 1)?
+        .reply()?;
+    Ok(reply.value32().and_then(|mut values| values.next()))
+}
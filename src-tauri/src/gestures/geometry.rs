@@ -0,0 +1,80 @@
+//! 点集几何工具 —— 预处理与各识别器共用
+//!
+//! 只处理 `(x, y)` 坐标，单位由调用方决定（识别器内部均为物理像素）。
+
+/// 两点间的欧氏距离
+pub fn distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    (a.0 - b.0).hypot(a.1 - b.1)
+}
+
+/// 包围盒 `(min_x, min_y, max_x, max_y)`；点集为空时 min 为 `f64::MAX`、max 为 `f64::MIN`
+pub fn bounds(points: impl IntoIterator<Item = (f64, f64)>) -> (f64, f64, f64, f64) {
+    points.into_iter().fold(
+        (f64::MAX, f64::MAX, f64::MIN, f64::MIN),
+        |(min_x, min_y, max_x, max_y), (x, y)| {
+            (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y))
+        },
+    )
+}
+
+/// 包围盒的宽和高；点集为空时为 (0, 0)
+pub fn extent(points: &[(f64, f64)]) -> (f64, f64) {
+    if points.is_empty() {
+        return (0.0, 0.0);
+    }
+    let (min_x, min_y, max_x, max_y) = bounds(points.iter().copied());
+    (max_x - min_x, max_y - min_y)
+}
+
+/// 质心；点集为空时为原点
+pub fn centroid(points: &[(f64, f64)]) -> (f64, f64) {
+    let n = points.len().max(1) as f64;
+    let cx = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let cy = points.iter().map(|p| p.1).sum::<f64>() / n;
+    (cx, cy)
+}
+
+/// 协方差矩阵的两个特征向量（主轴, 次轴），均为单位向量
+pub fn principal_axes(points: &[(f64, f64)]) -> ((f64, f64), (f64, f64)) {
+    let (cx, cy) = centroid(points);
+    let (mut sxx, mut syy, mut sxy) = (0.0, 0.0, 0.0);
+    for &(x, y) in points {
+        let (dx, dy) = (x - cx, y - cy);
+        sxx += dx * dx;
+        syy += dy * dy;
+        sxy += dx * dy;
+    }
+    // 2×2 对称矩阵主轴方向的闭式解
+    let angle = 0.5 * (2.0 * sxy).atan2(sxx - syy);
+    let (sin, cos) = angle.sin_cos();
+    ((cos, sin), (-sin, cos))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounds_and_extent() {
+        let points = [(3.0, -1.0), (-2.0, 4.0), (5.0, 2.0)];
+        assert_eq!(bounds(points), (-2.0, -1.0, 5.0, 4.0));
+        assert_eq!(extent(&points), (7.0, 5.0));
+        assert_eq!(extent(&[]), (0.0, 0.0));
+    }
+
+    #[test]
+    fn principal_axes_follow_the_spread() {
+        // 沿 y = x 方向拉长的点集
+        let points: Vec<(f64, f64)> = (0..20)
+            .flat_map(|i| {
+                let t = i as f64 * 10.0;
+                [(t + 2.0, t - 2.0), (t - 2.0, t + 2.0)]
+            })
+            .collect();
+        let (major, minor) = principal_axes(&points);
+        let diagonal = std::f64::consts::FRAC_1_SQRT_2;
+        assert!((major.0.abs() - diagonal).abs() < 1e-9 && (major.1.abs() - diagonal).abs() < 1e-9);
+        assert!((major.0 * minor.0 + major.1 * minor.1).abs() < 1e-12);
+        assert_eq!(centroid(&points), (95.0, 95.0));
+    }
+}
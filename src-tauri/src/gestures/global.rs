@@ -1,31 +1,72 @@
-//! 全局鼠标钩子 —— 直接调用 Windows API（WH_MOUSE_LL）
+//! 全局鼠标监听 —— 输入后端 + 手势状态机 + Tauri 事件
 //!
 //! 通用职责（所有宠物共享）：
-//!   1. 宠物拖拽检测 → emit "pet-drag-start" / "pet-drag-move" / "pet-drag-end"
+//!   1. 宠物拖拽检测 → emit "pet-drag-start" / "pet-drag-move" / "pet-drag-end"（附松手速度），
+//!      拖拽中来回摇晃 → emit "pet-shaken"
+//!      （移动超过阈值才算拖拽；否则 emit "pet-click" / "pet-double-click" / "pet-long-press"）
 //!   2. 宠物悬停检测 → emit "pet-hover-enter" / "pet-hover-leave"
-//!   3. 绘制轨迹收集 → 鼠标抬起时交给已注册的 GestureRecognizer 识别
-//!   4. 右键点击宠物 → emit "pet-right-click"（不穿透到下层窗口）
+//!   3. 绘制轨迹收集（按激活策略决定宠物外的左键 / 中键拖动是否算作绘制，见 activation.rs）→ 鼠标抬起时按各识别器声明的步骤预处理，再交给 GestureRecognizer 识别，
+//!      （提供 StrokeTracker 的识别器在绘制过程中逐点识别，可以提前触发），
+//!      由 arbiter 在各识别器的候选中仲裁（歧义 → "gesture-ambiguous"，
+//!      落选候选 → "gesture-debug"）；开启多笔画模式时，笔画按间隔超时分组，先交给 MultiStrokeRecognizer，没用上的笔画才发出暂缓的单笔结论
+//!   4. 绘制进度 → 节流发出 "gesture-progress"（简化后的当前折线），抬笔时发出 "gesture-path"，
+//!      供前端画墨迹、让宠物沿用户画的形状飞行
+//!   5. 绘制中按 Esc / 按下右键 / 超过最长时长 → 取消这一笔，emit "gesture-cancelled"
+//!      （能吞没的后端上，用于取消的右键不会弹出下层右键菜单）
+//!   6. 右键点击宠物 → emit "pet-right-click"（不穿透到下层窗口）
+//!   7. 宠物上滚动滚轮（绘制中除外）→ emit "pet-wheel"（吞没，下层窗口不会跟着滚动）
+//!   8. 宠物上按下中键 / 侧键 → emit "pet-middle-click" / "pet-x1-click" / "pet-x2-click"
+//!      （按 set_button_config 决定是否吞没；后端能吞没时也可以配置成用中键画手势）
+//!
+//! 分层：
+//!   - `InputBackend`（backends/）：平台相关的事件源，产出归一化的 `MouseEvent`
+//!   - `pipeline`：钩子线程只做无锁的吞没判定 + 入队，立即返回
+//!   - 工作线程（本模块）：独占 `GestureState`，把 `GestureOutput` 转成 Tauri emit，
+//!     并调度手势识别；"pet-drag-move" 合并到显示刷新率再发出
 //!
 //! 手势识别是可插拔的：切换宠物时通过 set_recognizers() 注册不同的识别器组合。
 //!
 //! 坐标说明：
-//!   钩子回调中 MSLLHOOKSTRUCT.pt 返回的是 **物理像素** 坐标。
+//!   后端上报的是 **物理像素** 坐标。
 //!   emit 前会除以 DPI 缩放因子，转换为 **逻辑像素** 坐标。
 //!
-//! ★ 多显示器修复：使用 MonitorFromPoint + GetDpiForMonitor 获取
-//!   鼠标所在显示器的真实 DPI，而非始终使用主窗口的 scale factor。
+//! ★ 多显示器修复：换算使用坐标所在显示器的缩放因子（见 display::MonitorTopology），
+//!   而非始终使用主窗口的 scale factor。
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::thread::{self, Thread};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+use crate::display;
+
+use super::activation::{Activation, ActivationPolicy};
+use super::arbiter::{self, Arbitration, CandidateSummary, RejectedCandidate, Scored, Verdict};
+use super::backends;
+use super::foreground;
+use super::input::{
+    ButtonConfig, DrawButton, InputBackend, Modifiers, MouseButton, MouseEvent, MouseEventKind,
+    WHEEL_DELTA,
+};
+use super::multistroke::{self, GroupedStroke, StrokeBatch, StrokeGroup};
+use super::path::{self, Throttle};
+use super::pipeline::{Command, EventQueue, PetSnapshot};
+use super::preprocess::{self, Preprocessed};
+use super::recognizers::{rectangle, GesturePayload, GestureRecognizer, MultiStrokeRecognizer};
+use super::state::{CancelReason, GestureMode, GestureOutput, GestureState};
+use super::streaming::{StrokeStream, TrackerEntry};
+use super::templates;
+use super::trajectory::{Trajectory, TrajectoryPoint};
 
-use std::sync::Mutex;
-use std::thread;
-use tauri::{AppHandle, Emitter, Manager};
+/// 宠物命中判定半径（逻辑像素）
+const PET_HIT_RADIUS: f64 = 65.0;
 
-use windows::{
-    Win32::Foundation::*,
-    Win32::UI::WindowsAndMessaging::*,
-    Win32::System::LibraryLoader::GetModuleHandleW,
-};
+/// "pet-drag-move" 的最小发送间隔（约 60Hz，与显示刷新率对齐）
+const DRAG_MOVE_INTERVAL: Duration = Duration::from_millis(16);
 
-use super::recognizers::GestureRecognizer;
+/// "gesture-progress" 的最小发送间隔（约 30Hz，墨迹渲染足够流畅）
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(33);
 
 // ── 事件 Payload ────────────────────────────────────────────────────────────
 
@@ -37,87 +78,158 @@ pub struct DragPayload {
     pub y: f64,
 }
 
-// ── 全局状态 ────────────────────────────────────────────────────────────────
+/// "pet-drag-end"：宠物最终位置 + 松手速度，前端据此把宠物抛出去
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct DragEndPayload {
+    /// 宠物最终位置（逻辑像素）
+    pub x: f64,
+    pub y: f64,
+    /// 松手速度（逻辑像素 / 秒）；松手前停住或没有时间信息时为 0
+    pub velocity_x: f64,
+    pub velocity_y: f64,
+}
 
-#[derive(PartialEq)]
-enum GestureMode {
-    Idle,
-    Drawing,
-    Dragging,
-}
-
-struct GestureState {
-    mode: GestureMode,
-    /// 绘制模式：轨迹点（物理像素）
-    points: Vec<(f64, f64)>,
-    /// 拖拽模式：鼠标到宠物中心的初始偏移（物理像素）
-    drag_offset_x: f64,
-    drag_offset_y: f64,
-    /// 宠物当前位置（物理像素，由前端通过 update_pet_position 同步）
-    pet_phys_x: f64,
-    pet_phys_y: f64,
-    /// 宠物命中判定半径（物理像素）
-    pet_hit_radius: f64,
-    /// 当前鼠标是否悬停在宠物上（用于 hover 进入/离开 检测）
-    is_hovering: bool,
-    /// 右键是否按在宠物上（用于吞没 WM_RBUTTONUP，防止右键穿透）
-    right_button_on_pet: bool,
-}
-
-impl Default for GestureState {
-    fn default() -> Self {
-        Self {
-            mode: GestureMode::Idle,
-            points: Vec::new(),
-            drag_offset_x: 0.0,
-            drag_offset_y: 0.0,
-            pet_phys_x: -9999.0,
-            pet_phys_y: -9999.0,
-            pet_hit_radius: 75.0,
-            is_hovering: false,
-            right_button_on_pet: false,
-        }
-    }
+/// "pet-shaken"：拖拽中来回摇晃宠物
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct ShakenPayload {
+    /// 宠物位置（逻辑像素）
+    pub x: f64,
+    pub y: f64,
+    /// 判定窗口内的方向反转次数
+    pub reversals: usize,
 }
 
-impl GestureState {
-    /// 判断指定物理像素坐标是否在宠物命中范围内
-    fn is_cursor_over_pet(&self, x: f64, y: f64) -> bool {
-        let dx = x - self.pet_phys_x;
-        let dy = y - self.pet_phys_y;
-        (dx * dx + dy * dy).sqrt() <= self.pet_hit_radius
-    }
+/// "pet-click" / "pet-double-click" / "pet-long-press"：点按宠物
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct PetPointerPayload {
+    /// 鼠标位置（逻辑像素）
+    pub x: f64,
+    pub y: f64,
+    /// 鼠标相对宠物中心的偏移（逻辑像素），前端据此判断摸的是头还是肚子
+    pub offset_x: f64,
+    pub offset_y: f64,
+}
 
-    /// 根据当前鼠标位置更新悬停状态，状态变化时 emit 事件
-    fn update_hover_state(&mut self, x: f64, y: f64) {
-        let over = self.is_cursor_over_pet(x, y);
-        if over && !self.is_hovering {
-            self.is_hovering = true;
-            emit_hover_event(true);
-        } else if !over && self.is_hovering {
-            self.is_hovering = false;
-            emit_hover_event(false);
-        }
-    }
+/// "pet-wheel"：在宠物上滚动滚轮
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct WheelPayload {
+    /// 鼠标位置（逻辑像素）
+    pub x: f64,
+    pub y: f64,
+    /// 滚动格数（向下 / 向右为正，高精度滚轮 / 触控板可能是小数）
+    pub delta_x: f64,
+    pub delta_y: f64,
+    pub modifiers: Modifiers,
+}
+
+/// 路径上的一点（逻辑像素）
+#[derive(serde::Serialize, Clone, Copy, Debug)]
+pub struct PathPoint {
+    pub x: f64,
+    pub y: f64,
+    /// 相对落笔时刻的毫秒数
+    pub t: f64,
 }
 
-static GLOBAL_STATE: Mutex<Option<GestureState>> = Mutex::new(None);
-static GLOBAL_APP: Mutex<Option<AppHandle>> = Mutex::new(None);
+/// "gesture-progress" / "gesture-path"：简化后的绘制折线
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct PathPayload {
+    pub points: Vec<PathPoint>,
+    /// 落笔到现在（progress）/ 抬笔（path）的毫秒数
+    pub duration_ms: f64,
+}
+
+/// "gesture-cancelled"：绘制中的一笔被取消
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct CancelledPayload {
+    pub reason: CancelReason,
+    /// 落笔到取消的毫秒数
+    pub duration_ms: f64,
+}
+
+/// "gesture-ambiguous"：多个识别器的分数太接近，没有触发任何手势
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct AmbiguousPayload {
+    /// 分数接近的候选（从高到低）
+    pub candidates: Vec<CandidateSummary>,
+    pub duration_ms: f64,
+}
+
+/// "gesture-debug"：一次识别中落选的候选
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct GestureDebugPayload {
+    /// 识别来源："stroke" / "drag" / "multi-stroke"
+    pub source: &'static str,
+    /// 采纳的识别器；没有采纳时为 None
+    pub accepted: Option<&'static str>,
+    pub rejected: Vec<RejectedCandidate>,
+}
+
+// ── 全局状态 ────────────────────────────────────────────────────────────────
+
+/// 钩子线程与工作线程共享的管道（内部全部无锁）
+struct Pipeline {
+    queue: EventQueue,
+    snapshot: PetSnapshot,
+    worker: OnceLock<Thread>,
+}
+
+static PIPELINE: OnceLock<Pipeline> = OnceLock::new();
 /// 当前活跃的手势识别器列表（切换宠物时替换）
 static RECOGNIZERS: Mutex<Vec<Box<dyn GestureRecognizer>>> = Mutex::new(Vec::new());
+/// 当前活跃的多笔画识别器列表
+static MULTI_STROKE_RECOGNIZERS: Mutex<Vec<Box<dyn MultiStrokeRecognizer>>> =
+    Mutex::new(Vec::new());
+/// 多笔画间隔超时（毫秒）；0 表示关闭多笔画模式
+static STROKE_TIMEOUT_MS: AtomicU64 = AtomicU64::new(0);
+/// 一笔的最长时长（毫秒）；0 表示不限
+static STROKE_MAX_MS: AtomicU64 = AtomicU64::new(0);
+/// 手势激活策略
+static ACTIVATION: Mutex<ActivationPolicy> = Mutex::new(ActivationPolicy::Always);
+/// 当前输入后端能否吞没事件（启动监听时由后端决定）
+static CAN_SWALLOW: AtomicBool = AtomicBool::new(true);
+
+fn pipeline() -> &'static Pipeline {
+    PIPELINE.get_or_init(|| Pipeline {
+        queue: EventQueue::default(),
+        snapshot: PetSnapshot::default(),
+        worker: OnceLock::new(),
+    })
+}
+
+impl Pipeline {
+    /// 入队并唤醒工作线程（无锁，可在钩子回调中调用）
+    fn submit(&self, command: Command) {
+        if self.queue.push(command) {
+            if let Some(worker) = self.worker.get() {
+                worker.unpark();
+            }
+        }
+    }
+}
 
 // ── 供外部调用的公共接口 ────────────────────────────────────────────────────
 
 /// 更新宠物的屏幕位置（由前端在宠物位置变化时调用）
-/// 传入逻辑像素，内部转成物理像素存储
-pub fn set_pet_position(x_logical: f64, y_logical: f64, scale_factor: f64) {
-    if let Ok(mut guard) = GLOBAL_STATE.try_lock() {
-        if let Some(state) = guard.as_mut() {
-            state.pet_phys_x = x_logical * scale_factor;
-            state.pet_phys_y = y_logical * scale_factor;
-            state.pet_hit_radius = 65.0 * scale_factor;
-        }
+/// 传入逻辑像素，按宠物所在显示器的缩放因子转成物理像素存储
+pub fn set_pet_position(x_logical: f64, y_logical: f64) {
+    let (x, y) = display::topology().to_physical(x_logical, y_logical);
+    set_pet_physical_position(x, y);
+}
+
+/// 直接设置宠物物理像素位置（显示配置变化后重新钳制时使用）
+pub fn set_pet_physical_position(x: f64, y: f64) {
+    let hit_radius = PET_HIT_RADIUS * display::topology().scale_at(x, y);
+    pipeline().submit(Command::SetPet { x, y, hit_radius });
+}
+
+/// 宠物当前物理像素位置；拖拽中或尚未同步时返回 None
+pub fn pet_physical_position() -> Option<(f64, f64)> {
+    let snapshot = &pipeline().snapshot;
+    if snapshot.is_dragging() {
+        return None;
     }
+    snapshot.position()
 }
 
 /// 注册当前宠物需要的手势识别器（替换旧的）
@@ -133,322 +245,730 @@ pub fn set_recognizers(new_recognizers: Vec<Box<dyn GestureRecognizer>>) {
     *guard = new_recognizers;
 }
 
+/// 注册多笔画识别器（替换旧的）
+pub fn set_multi_stroke_recognizers(new_recognizers: Vec<Box<dyn MultiStrokeRecognizer>>) {
+    let names: Vec<&str> = new_recognizers.iter().map(|r| r.name()).collect();
+    eprintln!("[gesture] 注册多笔画识别器: {:?}", names);
+    *MULTI_STROKE_RECOGNIZERS.lock().unwrap() = new_recognizers;
+}
+
+/// 开启（Some(笔画间隔超时)）或关闭（None）多笔画模式
+pub fn set_multi_stroke_timeout(timeout: Option<Duration>) {
+    let ms = timeout.map_or(0, |t| (t.as_millis() as u64).max(1));
+    STROKE_TIMEOUT_MS.store(ms, Ordering::Relaxed);
+    if ms == 0 {
+        eprintln!("[gesture] 多笔画模式：关闭");
+    } else {
+        eprintln!("[gesture] 多笔画模式：开启（笔画间隔 {}ms）", ms);
+    }
+}
+
+/// 设置画手势的键与宠物上中键 / 侧键的吞没
+///
+/// 后端不能吞没时拒绝用中键画手势：中键会同时落到下层窗口（X11 上会粘贴选区）。
+pub fn set_button_config(config: ButtonConfig) -> Result<(), String> {
+    let can_swallow = CAN_SWALLOW.load(Ordering::Relaxed);
+    if config.draw_button == DrawButton::Middle && !can_swallow {
+        return Err("当前输入后端无法吞没中键，不能用中键画手势".to_string());
+    }
+    if config.swallow_extra_buttons && !can_swallow {
+        eprintln!("[gesture] ⚠️ 当前输入后端无法吞没事件，宠物上的中键 / 侧键仍会传给下层窗口");
+    }
+    eprintln!("[gesture] 按键配置: {:?}", config);
+    let pipeline = pipeline();
+    // 吞没在钩子线程判定，立即生效；画手势的键交给工作线程（下一次按下生效）
+    pipeline.snapshot.set_buttons(config);
+    pipeline.submit(Command::SetButtons(config));
+    Ok(())
+}
+
+/// 设置手势激活策略（下一次按下生效）
+pub fn set_activation_policy(policy: ActivationPolicy) {
+    eprintln!("[gesture] 激活策略: {:?}", policy);
+    *ACTIVATION.lock().unwrap() = policy;
+}
+
+/// 设置一笔的最长时长（Some），超过后取消这一笔；None 表示不限
+pub fn set_stroke_max_duration(max: Option<Duration>) {
+    let ms = max.map_or(0, |t| (t.as_millis() as u64).clamp(1, u32::MAX as u64));
+    STROKE_MAX_MS.store(ms, Ordering::Relaxed);
+    if ms == 0 {
+        eprintln!("[gesture] 绘制超时：关闭");
+    } else {
+        eprintln!("[gesture] 绘制超时：{}ms", ms);
+    }
+}
+
+fn stroke_max_ms() -> Option<u32> {
+    match STROKE_MAX_MS.load(Ordering::Relaxed) {
+        0 => None,
+        ms => Some(ms as u32),
+    }
+}
+
+fn multi_stroke_timeout() -> Option<Duration> {
+    match STROKE_TIMEOUT_MS.load(Ordering::Relaxed) {
+        0 => None,
+        ms => Some(Duration::from_millis(ms)),
+    }
+}
+
+/// 使用当前平台默认的输入后端启动全局监听
 pub fn start_global_listener(app: AppHandle) {
-    {
-        let mut handle = GLOBAL_APP.lock().unwrap();
-        *handle = Some(app);
+    match backends::platform_backend() {
+        Some(backend) => start_with_backend(app, backend),
+        None => eprintln!("[gesture] ⚠️ 当前平台没有可用的全局输入后端，手势监听未启动"),
     }
-    {
-        let mut state = GLOBAL_STATE.lock().unwrap();
-        *state = Some(GestureState::default());
+}
+
+/// 使用指定的输入后端启动全局监听
+///
+/// 启动两个线程：工作线程（状态机 + emit）与输入线程（运行后端）。
+pub fn start_with_backend(app: AppHandle, backend: Box<dyn InputBackend>) {
+    if pipeline().worker.get().is_some() {
+        eprintln!("[gesture] ⚠️ 全局监听已经启动过，忽略重复启动");
+        return;
     }
 
-    thread::spawn(|| {
-        eprintln!("[gesture] 钩子线程启动");
+    CAN_SWALLOW.store(backend.can_swallow(), Ordering::Relaxed);
+    let worker = thread::spawn(move || {
+        eprintln!("[gesture] 工作线程启动");
+        Worker::new(app).run();
+    });
+    let _ = pipeline().worker.set(worker.thread().clone());
+    // 工作线程启动前入队的命令（例如宠物初始位置）需要一次唤醒
+    worker.thread().unpark();
+
+    thread::spawn(move || {
+        eprintln!("[gesture] 输入线程启动（后端：{}）", backend.name());
+        backend.run(Box::new(hook_sink));
+    });
+}
+
+// ── 钩子线程 ────────────────────────────────────────────────────────────────
 
-        unsafe {
-            let hmod = GetModuleHandleW(None)
-                .expect("[gesture] GetModuleHandleW 失败");
+/// 后端回调：无锁判定吞没 + 入队，返回是否吞没
+fn hook_sink(event: MouseEvent) -> bool {
+    let pipeline = pipeline();
+    let swallow = pipeline.snapshot.should_swallow(&event);
+    pipeline.submit(Command::Input(event));
+    swallow
+}
+
+// ── 工作线程 ────────────────────────────────────────────────────────────────
+
+struct Worker {
+    app: AppHandle,
+    state: GestureState,
+    /// 尚未发出的最新拖拽位置（物理像素）
+    pending_move: Option<(f64, f64)>,
+    move_throttle: Throttle,
+    reported_drops: u64,
+    /// 已记录到日志的丢失命令数
+    reported_dropped_commands: u64,
+    /// 多笔画模式下等待后续笔画的分组
+    stroke_group: StrokeGroup,
+    /// 当前笔画的增量识别会话
+    stream: Option<StrokeStream>,
+    /// 有尚未发出的绘制进度
+    progress_pending: bool,
+    progress_throttle: Throttle,
+    /// 最近一个输入事件的后端时间戳及其到达时刻，用于在没有事件时推算当前时间戳
+    clock: Option<(u32, Instant)>,
+}
+
+impl Worker {
+    fn new(app: AppHandle) -> Self {
+        Self {
+            app,
+            state: GestureState::default(),
+            pending_move: None,
+            move_throttle: Throttle::new(DRAG_MOVE_INTERVAL, Instant::now()),
+            reported_drops: 0,
+            reported_dropped_commands: 0,
+            stroke_group: StrokeGroup::default(),
+            stream: None,
+            progress_pending: false,
+            progress_throttle: Throttle::new(PROGRESS_INTERVAL, Instant::now()),
+            clock: None,
+        }
+    }
 
-            let hook = SetWindowsHookExW(
-                WH_MOUSE_LL,
-                Some(mouse_hook_proc),
-                hmod,
-                0,
+    fn run(mut self) {
+        let pipeline = pipeline();
+        loop {
+            while let Some(command) = pipeline.queue.pop() {
+                self.process(command);
+            }
+            pipeline.snapshot.publish(
+                self.state.pet_position(),
+                self.state.pet_hit_radius(),
+                self.state.mode() == GestureMode::Dragging,
+                self.state.mode() == GestureMode::Drawing,
+            );
+            self.report_drops(
+                pipeline.queue.dropped_moves(),
+                pipeline.queue.dropped_commands(),
             );
 
-            match hook {
-                Ok(h) => {
-                    eprintln!("[gesture] ✅ SetWindowsHookExW 成功，句柄={:?}", h);
-                    let mut msg = MSG::default();
-                    eprintln!("[gesture] 开始消息泵，等待鼠标事件...");
-                    loop {
-                        let ret = GetMessageW(&mut msg, None, 0, 0);
-                        match ret.0 {
-                            -1 | 0 => break,
-                            _ => {
-                                let _ = TranslateMessage(&msg);
-                                DispatchMessageW(&msg);
-                            }
-                        }
-                    }
-                    let _ = UnhookWindowsHookEx(h);
-                }
-                Err(e) => {
-                    eprintln!("[gesture] ❌ SetWindowsHookExW 失败：{:?}", e);
-                }
+            let wait = [
+                self.flush_drag_move(),
+                self.flush_progress(),
+                self.flush_stroke_group(),
+                self.flush_long_press(),
+                self.flush_stroke_timeout(),
+            ]
+            .into_iter()
+            .flatten()
+            .min();
+            match wait {
+                Some(wait) => thread::park_timeout(wait),
+                None => thread::park(),
             }
         }
-    });
-}
+    }
 
-// ── 钩子回调 ────────────────────────────────────────────────────────────────
-
-unsafe extern "system" fn mouse_hook_proc(
-    n_code: i32,
-    w_param: WPARAM,
-    l_param: LPARAM,
-) -> LRESULT {
-    if n_code >= 0 {
-        let mouse_data = &*(l_param.0 as *const MSLLHOOKSTRUCT);
-        let x = mouse_data.pt.x as f64;
-        let y = mouse_data.pt.y as f64;
-
-        // 标记是否需要吞没此消息（不传递给下层窗口）
-        let mut swallow = false;
-
-        match w_param.0 as u32 {
-            WM_LBUTTONDOWN => {
-                if let Ok(mut guard) = GLOBAL_STATE.try_lock() {
-                    if let Some(state) = guard.as_mut() {
-                        if state.is_cursor_over_pet(x, y) {
-                            // ── 拖拽模式 ──
-                            state.mode = GestureMode::Dragging;
-                            state.drag_offset_x = state.pet_phys_x - x;
-                            state.drag_offset_y = state.pet_phys_y - y;
-                            eprintln!("[gesture] 🖱️ 拖拽开始 ({}, {})", x as i32, y as i32);
-                            emit_drag_event("pet-drag-start", state.pet_phys_x, state.pet_phys_y);
-                        } else {
-                            // ── 绘制模式 ──
-                            state.mode = GestureMode::Drawing;
-                            state.points.clear();
-                            eprintln!("[gesture] 🖱️ 绘制开始 ({}, {})", x as i32, y as i32);
-                        }
-                    }
+    fn process(&mut self, command: Command) {
+        match command {
+            Command::SetPet { x, y, hit_radius } => self.state.set_pet(x, y, hit_radius),
+            Command::SetButtons(config) => self.state.set_buttons(config),
+            Command::Input(event) => {
+                self.clock = Some((event.time, Instant::now()));
+                // 先用处理该事件之前的状态、按它的时间戳判断分组是否超时：超时后才落下的笔属于新的一组
+                self.flush_stroke_group();
+                if event.kind == MouseEventKind::Down(self.state.draw_button())
+                    && !self.state.is_cursor_over_pet(event.x, event.y)
+                {
+                    self.state
+                        .set_activation(resolve_activation(event.x, event.y));
+                    self.state.set_stroke_timeout(stroke_max_ms());
                 }
-            }
-
-            WM_RBUTTONDOWN => {
-                // ── 右键点击宠物 → 通知前端 toggle 面板，并吞没消息防止穿透 ──
-                if let Ok(mut guard) = GLOBAL_STATE.try_lock() {
-                    if let Some(state) = guard.as_mut() {
-                        if state.is_cursor_over_pet(x, y) {
-                            state.right_button_on_pet = true;
-                            emit_right_click_event(state.pet_phys_x, state.pet_phys_y);
-                            swallow = true; // 不让右键按下事件穿透到下层窗口
-                        }
-                    }
+                // 吞没已经在钩子线程决定，这里只关心输出
+                for output in self.state.handle(&event).outputs {
+                    self.dispatch(output);
                 }
             }
+        }
+    }
 
-            WM_RBUTTONUP => {
-                // ── 右键释放：如果对应的按下发生在宠物上，也吞没，防止触发下层右键菜单 ──
-                if let Ok(mut guard) = GLOBAL_STATE.try_lock() {
-                    if let Some(state) = guard.as_mut() {
-                        if state.right_button_on_pet {
-                            state.right_button_on_pet = false;
-                            swallow = true; // 配套吞没右键释放事件
-                        }
-                    }
+    fn dispatch(&mut self, output: GestureOutput) {
+        match output {
+            GestureOutput::DragStart { x, y } => {
+                self.pending_move = None;
+                emit_position_event(&self.app, "pet-drag-start", x, y);
+            }
+            GestureOutput::DragMove { x, y } => self.pending_move = Some((x, y)),
+            GestureOutput::DragPath(path) => recognize_drag(&self.app, &path),
+            GestureOutput::PetShaken { x, y, reversals } => {
+                eprintln!("[gesture] 🌀 摇晃宠物（{} 次反转）", reversals);
+                let (x, y) = display::topology().to_logical(x, y);
+                let _ = self
+                    .app
+                    .emit("pet-shaken", ShakenPayload { x, y, reversals });
+            }
+            GestureOutput::DragEnd {
+                x,
+                y,
+                velocity_x,
+                velocity_y,
+            } => {
+                // drag-end 携带最终位置，尚未发出的 drag-move 直接作废
+                self.pending_move = None;
+                let topology = display::topology();
+                let scale = topology.scale_at(x, y);
+                let (x, y) = topology.to_logical(x, y);
+                let payload = DragEndPayload {
+                    x,
+                    y,
+                    velocity_x: velocity_x / scale,
+                    velocity_y: velocity_y / scale,
+                };
+                let _ = self.app.emit("pet-drag-end", payload);
+            }
+            GestureOutput::HoverEnter => emit_hover_event(&self.app, true),
+            GestureOutput::HoverLeave => emit_hover_event(&self.app, false),
+            GestureOutput::PetWheel {
+                x,
+                y,
+                delta_x,
+                delta_y,
+                modifiers,
+            } => {
+                let (x, y) = display::topology().to_logical(x, y);
+                let payload = WheelPayload {
+                    x,
+                    y,
+                    delta_x: delta_x as f64 / WHEEL_DELTA as f64,
+                    delta_y: delta_y as f64 / WHEEL_DELTA as f64,
+                    modifiers,
+                };
+                let _ = self.app.emit("pet-wheel", payload);
+            }
+            GestureOutput::RightClick { x, y } => {
+                emit_position_event(&self.app, "pet-right-click", x, y)
+            }
+            GestureOutput::PetButton { button, x, y } => {
+                let event_name = match button {
+                    MouseButton::Middle => "pet-middle-click",
+                    MouseButton::X1 => "pet-x1-click",
+                    MouseButton::X2 => "pet-x2-click",
+                    // 左键 / 右键走各自的事件，状态机不会为它们发出 PetButton
+                    MouseButton::Left | MouseButton::Right => return,
+                };
+                self.emit_pointer_event(event_name, x, y)
+            }
+            GestureOutput::PetClick { x, y } => self.emit_pointer_event("pet-click", x, y),
+            GestureOutput::PetDoubleClick { x, y } => {
+                self.emit_pointer_event("pet-double-click", x, y)
+            }
+            GestureOutput::PetLongPress { x, y } => self.emit_pointer_event("pet-long-press", x, y),
+            GestureOutput::StrokeBegin { x, y } => {
+                self.stream = start_stream(x, y);
+                self.progress_pending = false;
+            }
+            GestureOutput::StrokeSample(point) => {
+                self.progress_pending = true;
+                if let Some((recognizer, candidate)) =
+                    self.stream.as_mut().and_then(|stream| stream.push(point))
+                {
+                    eprintln!(
+                        "[gesture] ✅ {} 提前识别（置信度 {:.2}）！{:?}（用时 {:.0}ms）",
+                        recognizer, candidate.score, candidate.result, point.t
+                    );
+                    let topology = display::topology();
+                    let monitor = topology.monitor_at(point.x, point.y);
+                    let result = rectangle::locate(candidate.result, monitor);
+                    let payload = GesturePayload::early(result, point.t);
+                    let _ = self.app.emit(payload.result.event_name(), payload);
                 }
             }
-
-            WM_MOUSEMOVE => {
-                if let Ok(mut guard) = GLOBAL_STATE.try_lock() {
-                    if let Some(state) = guard.as_mut() {
-                        match state.mode {
-                            GestureMode::Dragging => {
-                                let new_x = x + state.drag_offset_x;
-                                let new_y = y + state.drag_offset_y;
-                                state.pet_phys_x = new_x;
-                                state.pet_phys_y = new_y;
-                                emit_drag_event("pet-drag-move", new_x, new_y);
-                            }
-                            GestureMode::Drawing => {
-                                let should_add = match state.points.last() {
-                                    Some(&(lx, ly)) => {
-                                        ((x - lx).powi(2) + (y - ly).powi(2)).sqrt() >= 5.0
-                                    }
-                                    None => true,
-                                };
-                                if should_add {
-                                    state.points.push((x, y));
-                                }
-                            }
-                            GestureMode::Idle => {
-                                // ── 悬停检测：鼠标是否在宠物命中范围内 ──
-                                state.update_hover_state(x, y);
-                            }
-                        }
+            GestureOutput::Cancelled {
+                reason,
+                duration_ms,
+            } => {
+                self.stream = None;
+                self.progress_pending = false;
+                // 取消也结束了正在凑的多笔画
+                self.abandon_stroke_group();
+                let _ = self.app.emit(
+                    "gesture-cancelled",
+                    CancelledPayload {
+                        reason,
+                        duration_ms,
+                    },
+                );
+            }
+            GestureOutput::Stroke(stroke) => {
+                // 先发路径：识别结果到达时前端已经有完整形状可用
+                self.progress_pending = false;
+                emit_path(&self.app, "gesture-path", &stroke);
+                let fired = self.stream.take().is_some_and(|stream| stream.fired());
+                // 录制模板样本时，这一笔不参与识别
+                match templates::record_stroke(&stroke, scale_at_centroid(stroke.centroid())) {
+                    Some(payload) => {
+                        let _ = self.app.emit("gesture-sample-recorded", payload);
                     }
+                    // 已提前识别：视同单笔识别成功，结束正在凑的多笔画
+                    None if fired => self.abandon_stroke_group(),
+                    None => self.handle_stroke(stroke),
                 }
             }
+        }
+    }
 
-            WM_LBUTTONUP => {
-                // 必须先释放锁再做耗时操作
-                let action = {
-                    if let Ok(mut guard) = GLOBAL_STATE.try_lock() {
-                        if let Some(state) = guard.as_mut() {
-                            match state.mode {
-                                GestureMode::Dragging => {
-                                    state.mode = GestureMode::Idle;
-                                    state.is_hovering = false;
-                                    state.update_hover_state(x, y);
-                                    Some(("drag-end", state.pet_phys_x, state.pet_phys_y, Vec::new()))
-                                }
-                                GestureMode::Drawing => {
-                                    state.mode = GestureMode::Idle;
-                                    let pts = state.points.clone();
-                                    state.points.clear();
-                                    Some(("recognize", 0.0, 0.0, pts))
-                                }
-                                GestureMode::Idle => None,
-                            }
-                        } else { None }
-                    } else { None }
-                };
+    /// 单笔识别；开启多笔画模式时结论暂缓，这一笔放进分组等多笔画识别器先看
+    fn handle_stroke(&mut self, stroke: Trajectory) {
+        let single = recognize_stroke(&stroke);
+        if multi_stroke_timeout().is_none() {
+            settle(&self.app, "stroke", single, stroke.duration_ms());
+            return;
+        }
+        let end_time = self.clock.map_or(0, |(time, _)| time);
+        if let Some(evicted) = self.stroke_group.push(GroupedStroke {
+            stroke,
+            single,
+            end_time,
+        }) {
+            settle_single(&self.app, evicted);
+        }
+    }
 
-                if let Some((action_type, px, py, pts)) = action {
-                    let app_opt = {
-                        let guard = GLOBAL_APP.lock().unwrap();
-                        guard.clone()
-                    };
-                    if let Some(app) = app_opt {
-                        if action_type == "drag-end" {
-                            thread::spawn(move || {
-                                // ★ 修复：使用宠物所在位置的显示器 DPI
-                                let scale = get_scale_for_point(px, py);
-                                let payload = DragPayload { x: px / scale, y: py / scale };
-                                let _ = app.emit("pet-drag-end", payload);
-                            });
-                        } else {
-                            // ── 遍历已注册的识别器 ──
-                            thread::spawn(move || {
-                                // ★ 修复：使用轨迹中心点所在显示器的 DPI
-                                let scale = if pts.is_empty() {
-                                    get_scale(&app)
-                                } else {
-                                    let n = pts.len() as f64;
-                                    let cx = pts.iter().map(|p| p.0).sum::<f64>() / n;
-                                    let cy = pts.iter().map(|p| p.1).sum::<f64>() / n;
-                                    get_scale_for_point(cx, cy)
-                                };
-                                let recognizers = RECOGNIZERS.lock().unwrap();
-                                for recognizer in recognizers.iter() {
-                                    if let Some(result) = recognizer.analyze(&pts, scale) {
-                                        let event_name = result.event_name();
-                                        eprintln!(
-                                            "[gesture] ✅ {} 识别成功！{:?}",
-                                            recognizer.name(),
-                                            result
-                                        );
-                                        let _ = app.emit(event_name, result);
-                                        break; // 第一个匹配的识别器生效
-                                    }
-                                }
-                            });
-                        }
-                    }
-                }
+    /// 分组超时后交给多笔画识别器；返回距超时的等待时间
+    fn flush_stroke_group(&mut self) -> Option<Duration> {
+        if self.stroke_group.is_empty() {
+            return None;
+        }
+        let Some(timeout) = multi_stroke_timeout() else {
+            // 多笔画模式已关闭
+            self.abandon_stroke_group();
+            return None;
+        };
+        let now = self.backend_now()?;
+        let drawing = self.state.mode() == GestureMode::Drawing;
+        match self.stroke_group.poll(now, timeout, drawing) {
+            Ok(batch) => {
+                recognize_strokes(&self.app, batch);
+                None
             }
+            Err(wait) => wait,
+        }
+    }
 
-            _ => {}
+    /// 不做多笔画识别就结束分组：按顺序发出各笔暂缓的单笔结论
+    fn abandon_stroke_group(&mut self) {
+        for grouped in self.stroke_group.take() {
+            settle_single(&self.app, grouped);
         }
+    }
 
-        // ── 吞没判定：如果事件发生在宠物上且需要拦截，不传递给下层窗口 ──
-        if swallow {
-            return LRESULT(1);
+    /// 当前的后端时间戳：最近一个事件的时间戳加上之后经过的时长
+    fn backend_now(&self) -> Option<u32> {
+        let (time, at) = self.clock?;
+        Some(time.wrapping_add(at.elapsed().as_millis() as u32))
+    }
+
+    /// 按显示刷新率发出合并后的 drag-move；返回距下一次可发送的等待时间
+    fn flush_drag_move(&mut self) -> Option<Duration> {
+        let (x, y) = self.pending_move?;
+        if let Some(wait) = self.move_throttle.poll(Instant::now()) {
+            return Some(wait);
+        }
+        self.pending_move = None;
+        emit_position_event(&self.app, "pet-drag-move", x, y);
+        None
+    }
+
+    /// 按节流间隔发出当前这一笔的简化折线；返回距下一次可发送的等待时间
+    fn flush_progress(&mut self) -> Option<Duration> {
+        if !self.progress_pending {
+            return None;
+        }
+        if let Some(wait) = self.progress_throttle.poll(Instant::now()) {
+            return Some(wait);
+        }
+        self.progress_pending = false;
+        let points = self.state.stroke_points()?;
+        let duration_ms = points.last().map_or(0.0, |p| p.t);
+        emit_path(
+            &self.app,
+            "gesture-progress",
+            &Trajectory::new(points.to_vec(), duration_ms),
+        );
+        None
+    }
+
+    /// 按住宠物不动时没有输入事件，按截止时刻定时触发长按；返回距截止的等待时间
+    fn flush_long_press(&mut self) -> Option<Duration> {
+        let deadline = self.state.long_press_deadline()?;
+        let now = self.backend_now()?;
+        match self.state.poll_long_press(now) {
+            Some(output) => {
+                self.dispatch(output);
+                None
+            }
+            None => Some(Duration::from_millis(deadline.wrapping_sub(now) as u64)),
         }
     }
 
-    CallNextHookEx(None, n_code, w_param, l_param)
+    /// 绘制中按住不动时没有输入事件，按截止时刻定时取消超时的一笔；返回距截止的等待时间
+    fn flush_stroke_timeout(&mut self) -> Option<Duration> {
+        let deadline = self.state.stroke_deadline()?;
+        let now = self.backend_now()?;
+        match self.state.poll_stroke_timeout(now) {
+            Some(output) => {
+                self.dispatch(output);
+                None
+            }
+            None => Some(Duration::from_millis(deadline.wrapping_sub(now) as u64)),
+        }
+    }
+
+    /// 发送点按宠物的事件（鼠标位置 + 相对宠物中心的偏移）
+    fn emit_pointer_event(&self, event_name: &str, phys_x: f64, phys_y: f64) {
+        let topology = display::topology();
+        let (x, y) = topology.to_logical(phys_x, phys_y);
+        let (offset_x, offset_y) = match self.state.pet_position() {
+            Some((pet_x, pet_y)) => {
+                let (pet_x, pet_y) = topology.to_logical(pet_x, pet_y);
+                (x - pet_x, y - pet_y)
+            }
+            None => (0.0, 0.0),
+        };
+        let _ = self.app.emit(
+            event_name,
+            PetPointerPayload {
+                x,
+                y,
+                offset_x,
+                offset_y,
+            },
+        );
+    }
+
+    fn report_drops(&mut self, dropped_moves: u64, dropped_commands: u64) {
+        if dropped_moves > self.reported_drops {
+            eprintln!(
+                "[gesture] ⚠️ 事件队列拥堵，已丢弃 {} 个移动事件",
+                dropped_moves - self.reported_drops
+            );
+            self.reported_drops = dropped_moves;
+        }
+        if dropped_commands > self.reported_dropped_commands {
+            eprintln!(
+                "[gesture] ❌ 事件队列已满，丢失 {} 个按键 / 控制命令",
+                dropped_commands - self.reported_dropped_commands
+            );
+            self.reported_dropped_commands = dropped_commands;
+        }
+    }
 }
 
 // ── 辅助函数 ────────────────────────────────────────────────────────────────
 
-fn emit_drag_event(event_name: &str, phys_x: f64, phys_y: f64) {
-    let app_opt = {
-        if let Ok(guard) = GLOBAL_APP.try_lock() {
-            guard.clone()
-        } else {
-            return;
-        }
-    };
-    if let Some(app) = app_opt {
-        // ★ 修复：使用鼠标/宠物所在位置的显示器 DPI
-        let scale = get_scale_for_point(phys_x, phys_y);
-        let payload = DragPayload { x: phys_x / scale, y: phys_y / scale };
-        let _ = app.emit(event_name, payload);
+/// 轨迹中心点所在显示器的缩放因子
+fn scale_at_centroid(centroid: Option<(f64, f64)>) -> f64 {
+    // ★ 修复：使用轨迹中心点所在显示器的 DPI
+    let topology = display::topology();
+    match centroid {
+        Some((cx, cy)) => topology.scale_at(cx, cy),
+        None => topology.primary().scale,
     }
 }
 
-// 发送悬停进入/离开事件，前端据此切换窗口鼠标穿透
-fn emit_hover_event(entering: bool) {
-    let app_opt = {
-        if let Ok(guard) = GLOBAL_APP.try_lock() {
-            guard.clone()
-        } else {
-            return;
-        }
-    };
-    if let Some(app) = app_opt {
-        let event_name = if entering { "pet-hover-enter" } else { "pet-hover-leave" };
-        let _ = app.emit(event_name, ());
+/// 按当前策略解析宠物外这次左键按下的激活方式（在收集任何轨迹点之前）
+fn resolve_activation(x: f64, y: f64) -> Activation {
+    let policy = ACTIVATION.lock().unwrap();
+    let app = policy
+        .needs_foreground_app()
+        .then(foreground::foreground_app)
+        .flatten();
+    let activation = policy.resolve(display::topology().scale_at(x, y), app.as_deref());
+    if activation == Activation::Disabled {
+        eprintln!("[gesture] 前台应用 {:?} 不激活手势", app);
     }
+    activation
 }
 
-// 发送宠物右键点击事件，前端据此 toggle 功能面板
-fn emit_right_click_event(phys_x: f64, phys_y: f64) {
-    let app_opt = {
-        if let Ok(guard) = GLOBAL_APP.try_lock() {
-            guard.clone()
-        } else {
-            return;
-        }
-    };
-    if let Some(app) = app_opt {
-        // ★ 修复：使用宠物所在位置的显示器 DPI
-        let scale = get_scale_for_point(phys_x, phys_y);
-        let payload = DragPayload {
-            x: phys_x / scale,
-            y: phys_y / scale,
-        };
-        let _ = app.emit("pet-right-click", payload);
+/// 落笔时为提供增量识别的识别器创建会话；录制模板或没有增量识别器时返回 None
+fn start_stream(x: f64, y: f64) -> Option<StrokeStream> {
+    if templates::is_recording() {
+        return None;
     }
+    let trackers: Vec<TrackerEntry> = RECOGNIZERS
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|r| {
+            Some(TrackerEntry {
+                recognizer: r.name(),
+                threshold: r.min_score(),
+                tracker: r.tracker()?,
+            })
+        })
+        .collect();
+    (!trackers.is_empty()).then(|| StrokeStream::new(trackers, scale_at_centroid(Some((x, y)))))
+}
+
+/// 让所有已注册的识别器分析笔画并仲裁（结论由调用方发出）
+fn recognize_stroke(stroke: &Trajectory) -> Arbitration {
+    let topology = display::topology();
+    let monitor = match stroke.centroid() {
+        Some((cx, cy)) => topology.monitor_at(cx, cy),
+        None => topology.primary(),
+    };
+    let scale = monitor.scale;
+    let mut preprocessed = Preprocessed::new(stroke, scale);
+    let candidates = RECOGNIZERS
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|r| {
+            let candidate = r.analyze(preprocessed.get(r.stages()), scale)?;
+            Some(Scored::new(r.name(), candidate, r.min_score()))
+        })
+        .collect();
+    let Arbitration { verdict, rejected } = arbiter::arbitrate(candidates);
+    // 矩形要带上所在显示器：与缩放因子取自同一台
+    let verdict = match verdict {
+        Verdict::Accepted {
+            recognizer,
+            result,
+            score,
+        } => Verdict::Accepted {
+            recognizer,
+            result: rectangle::locate(result, monitor),
+            score,
+        },
+        other => other,
+    };
+    Arbitration { verdict, rejected }
 }
 
-/// ★ 修复：获取指定物理像素坐标所在显示器的 DPI 缩放因子
+/// 发出分组中一笔暂缓的单笔结论
+fn settle_single(app: &AppHandle, grouped: GroupedStroke) {
+    settle(app, "stroke", grouped.single, grouped.stroke.duration_ms());
+}
+
+/// 拖拽松手时把鼠标轨迹交给愿意处理拖拽的识别器（例如甩出宠物）
+fn recognize_drag(app: &AppHandle, path: &Trajectory) {
+    let scale = scale_at_centroid(path.points().last().map(|p| (p.x, p.y)));
+    let mut preprocessed = Preprocessed::new(path, scale);
+    let candidates = RECOGNIZERS
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|r| {
+            let candidate = r.analyze_drag(preprocessed.get(r.stages()), scale)?;
+            Some(Scored::new(r.name(), candidate, r.min_score()))
+        })
+        .collect();
+    settle(
+        app,
+        "drag",
+        arbiter::arbitrate(candidates),
+        path.duration_ms(),
+    );
+}
+
+/// 把一组笔画交给多笔画识别器并仲裁
 ///
-/// 多显示器场景下，每个显示器可能有不同的 DPI 设置。
-/// 使用 MonitorFromPoint 确定坐标所在的显示器，
-/// 再用 GetDpiForMonitor 获取该显示器的实际 DPI。
-fn get_scale_for_point(phys_x: f64, phys_y: f64) -> f64 {
-    use windows::Win32::Graphics::Gdi::{MonitorFromPoint, MONITOR_DEFAULTTONEAREST};
-    use windows::Win32::UI::HiDpi::{GetDpiForMonitor, MDT_EFFECTIVE_DPI};
-
-    unsafe {
-        let pt = POINT { x: phys_x as i32, y: phys_y as i32 };
-        let hmon = MonitorFromPoint(pt, MONITOR_DEFAULTTONEAREST);
-        let mut dpi_x = 0u32;
-        let mut dpi_y = 0u32;
-        if GetDpiForMonitor(hmon, MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y).is_ok() && dpi_x > 0
-        {
-            dpi_x as f64 / 96.0
-        } else {
-            // 回退：使用主窗口的 scale factor
-            get_scale_from_app()
+/// 整组没有结论时依次去掉最早的一笔再试（前面几笔可能是各自独立的单笔手势）；
+/// 只有多笔画识别器用上的笔画丢弃单笔结论，其余笔画按顺序发出暂缓的单笔结论。
+fn recognize_strokes(app: &AppHandle, batch: StrokeBatch) {
+    let mut strokes = batch.strokes;
+    let whole = arbitrate_strokes(&strokes);
+    let partial = if whole.concluded() {
+        None
+    } else {
+        (1..strokes.len()).find_map(|skip| {
+            let arbitration = arbitrate_strokes(&strokes[skip..]);
+            arbitration.concluded().then_some((skip, arbitration))
+        })
+    };
+    let (skip, arbitration) = partial.unwrap_or((0, whole));
+    let used = strokes.split_off(skip);
+    for grouped in strokes {
+        settle_single(app, grouped);
+    }
+    if !settle(
+        app,
+        "multi-stroke",
+        arbitration,
+        multistroke::span_ms(&used),
+    ) {
+        for grouped in used {
+            settle_single(app, grouped);
         }
     }
 }
 
-/// 从主窗口获取 scale factor（回退方案）
-fn get_scale_from_app() -> f64 {
-    let app_opt = {
-        if let Ok(guard) = GLOBAL_APP.try_lock() {
-            guard.clone()
-        } else {
-            return 1.0;
+/// 多笔画识别器对连续几笔的仲裁结论
+fn arbitrate_strokes(strokes: &[GroupedStroke]) -> Arbitration {
+    let centroids: Vec<(f64, f64)> = strokes.iter().filter_map(|s| s.stroke.centroid()).collect();
+    let centroid = (!centroids.is_empty()).then(|| {
+        let n = centroids.len() as f64;
+        (
+            centroids.iter().map(|c| c.0).sum::<f64>() / n,
+            centroids.iter().map(|c| c.1).sum::<f64>() / n,
+        )
+    });
+    let scale = scale_at_centroid(centroid);
+    let candidates = MULTI_STROKE_RECOGNIZERS
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|r| {
+            let strokes: Vec<Trajectory> = strokes
+                .iter()
+                .map(|s| preprocess::apply(&s.stroke, r.stages(), scale))
+                .collect();
+            let candidate = r.analyze(&strokes, scale)?;
+            Some(Scored::new(r.name(), candidate, r.min_score()))
+        })
+        .collect();
+    arbiter::arbitrate(candidates)
+}
+
+/// 发送仲裁结论与落选候选；返回是否有结论（识别成功或有歧义）
+fn settle(
+    app: &AppHandle,
+    source: &'static str,
+    arbitration: Arbitration,
+    duration_ms: f64,
+) -> bool {
+    let Arbitration { verdict, rejected } = arbitration;
+    let (accepted, concluded) = match verdict {
+        Verdict::Accepted {
+            recognizer,
+            result,
+            score,
+        } => {
+            eprintln!(
+                "[gesture] ✅ {} 识别成功（{}，置信度 {:.2}）！{:?}（用时 {:.0}ms）",
+                recognizer, source, score, result, duration_ms
+            );
+            let _ = app.emit(
+                result.event_name(),
+                GesturePayload::new(result, duration_ms),
+            );
+            (Some(recognizer), true)
         }
+        Verdict::Ambiguous(candidates) => {
+            let names: Vec<&str> = candidates.iter().map(|c| c.recognizer).collect();
+            eprintln!("[gesture] ⚠️ 识别有歧义（{}）：{:?}", source, names);
+            let _ = app.emit(
+                "gesture-ambiguous",
+                AmbiguousPayload {
+                    candidates,
+                    duration_ms,
+                },
+            );
+            (None, true)
+        }
+        Verdict::Unrecognized => (None, false),
     };
-    if let Some(app) = app_opt {
-        get_scale(&app)
-    } else {
-        1.0
+    if !rejected.is_empty() {
+        let _ = app.emit(
+            "gesture-debug",
+            GestureDebugPayload {
+                source,
+                accepted,
+                rejected,
+            },
+        );
+    }
+    concluded
+}
+
+/// 发送携带宠物位置的事件（拖拽 / 右键），前端据此移动宠物或 toggle 面板
+fn emit_position_event(app: &AppHandle, event_name: &str, phys_x: f64, phys_y: f64) {
+    // ★ 修复：使用宠物所在位置的显示器 DPI
+    let (x, y) = display::topology().to_logical(phys_x, phys_y);
+    let _ = app.emit(event_name, DragPayload { x, y });
+}
+
+/// 简化折线并换算成逻辑像素后发出（进度 / 抬笔路径）
+fn emit_path(app: &AppHandle, event_name: &str, stroke: &Trajectory) {
+    if stroke.is_empty() {
+        return;
     }
+    let points = path::simplify(stroke, &display::topology())
+        .into_iter()
+        .map(|TrajectoryPoint { x, y, t }| PathPoint { x, y, t })
+        .collect();
+    let payload = PathPayload {
+        points,
+        duration_ms: stroke.duration_ms(),
+    };
+    let _ = app.emit(event_name, payload);
 }
 
-fn get_scale(app: &AppHandle) -> f64 {
-    app.get_webview_window("main")
-        .and_then(|w| w.scale_factor().ok())
-        .unwrap_or(1.0)
+// 发送悬停进入/离开事件，前端据此切换窗口鼠标穿透
+fn emit_hover_event(app: &AppHandle, entering: bool) {
+    let event_name = if entering {
+        "pet-hover-enter"
+    } else {
+        "pet-hover-leave"
+    };
+    let _ = app.emit(event_name, ());
 }
@@ -0,0 +1,181 @@
+//! 输入后端抽象
+//!
+//! 各平台的全局鼠标监听（Windows 的 WH_MOUSE_LL、测试用的合成脚本……）
+//! 都实现 `InputBackend`，把系统原始消息归一化成 `MouseEvent`，
+//! 再交给与平台无关的 `GestureState` 状态机处理。
+//!
+//! 坐标约定：`MouseEvent` 中的 x / y 均为 **物理像素**（虚拟桌面坐标系）。
+//! 时间约定：`time` 为后端提供的毫秒时间戳（Windows 为 MSLLHOOKSTRUCT.time，
+//! X11 为服务器时间），32 位回绕，只用于计算差值（`wrapping_sub`）。
+//! 修饰键：后端在按键 / 滚轮事件上附带当时的修饰键状态，供激活策略判定。
+//! 滚轮：增量以 `WHEEL_DELTA` 为一格，向下 / 向右为正（与 DOM WheelEvent 一致）。
+
+/// 滚轮转动一格的增量（与 Windows 的 WHEEL_DELTA 相同，高精度滚轮 / 触控板可能小于一格）
+pub const WHEEL_DELTA: i32 = 120;
+
+/// 鼠标按键
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+    /// 侧键（通常是"后退"）
+    X1,
+    /// 侧键（通常是"前进"）
+    X2,
+}
+
+impl MouseButton {
+    /// 按键位掩码，用于记录哪些按键的按下被吞没
+    pub fn mask(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+/// 用哪个键画手势
+#[derive(serde::Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DrawButton {
+    #[default]
+    Left,
+    /// 左键拖动与工作软件冲突时改用中键画
+    Middle,
+}
+
+impl DrawButton {
+    pub fn button(self) -> MouseButton {
+        match self {
+            Self::Left => MouseButton::Left,
+            Self::Middle => MouseButton::Middle,
+        }
+    }
+}
+
+/// 中键 / 侧键相关的配置（set_button_config 的参数，缺省字段取默认值）
+#[derive(serde::Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ButtonConfig {
+    /// 画手势用的键；为 middle 时宠物外的左键拖动不再收集轨迹，宠物外的中键按下 / 释放被吞没
+    pub draw_button: DrawButton,
+    /// 在宠物上按下中键 / 侧键时是否吞没（下层窗口收不到）
+    pub swallow_extra_buttons: bool,
+}
+
+/// 修饰键
+#[derive(serde::Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ModifierKey {
+    Ctrl,
+    Shift,
+    Alt,
+    /// Windows 键 / Super
+    Meta,
+}
+
+/// 事件发生时按住的修饰键（只在按键 / 滚轮事件上填写，移动事件为空）
+#[derive(serde::Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Modifiers {
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+    pub meta: bool,
+}
+
+impl Modifiers {
+    pub fn contains(&self, key: ModifierKey) -> bool {
+        match key {
+            ModifierKey::Ctrl => self.ctrl,
+            ModifierKey::Shift => self.shift,
+            ModifierKey::Alt => self.alt,
+            ModifierKey::Meta => self.meta,
+        }
+    }
+}
+
+/// 归一化后的鼠标事件类型
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MouseEventKind {
+    Move,
+    Down(MouseButton),
+    Up(MouseButton),
+    /// 滚轮转动（`WHEEL_DELTA` 为一格，向下 / 向右为正）
+    Wheel {
+        delta_x: i32,
+        delta_y: i32,
+    },
+    /// Esc 键按下（用于取消绘制；坐标为当时的指针位置，后端只上报这一个按键）
+    Escape,
+}
+
+/// 归一化后的鼠标事件（物理像素）
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MouseEvent {
+    pub kind: MouseEventKind,
+    pub x: f64,
+    pub y: f64,
+    /// 毫秒时间戳（回绕，只用于求差）
+    pub time: u32,
+    pub modifiers: Modifiers,
+}
+
+impl MouseEvent {
+    pub fn new(kind: MouseEventKind, x: f64, y: f64) -> Self {
+        Self {
+            kind,
+            x,
+            y,
+            time: 0,
+            modifiers: Modifiers::default(),
+        }
+    }
+
+    /// 附加时间戳
+    pub fn at(self, time: u32) -> Self {
+        Self { time, ..self }
+    }
+
+    /// 附加修饰键状态
+    pub fn with_modifiers(self, modifiers: Modifiers) -> Self {
+        Self { modifiers, ..self }
+    }
+
+    /// 与更早事件之间的间隔（毫秒，已处理回绕）
+    pub fn millis_since(&self, earlier: &MouseEvent) -> u32 {
+        self.time.wrapping_sub(earlier.time)
+    }
+
+    pub fn moved(x: f64, y: f64) -> Self {
+        Self::new(MouseEventKind::Move, x, y)
+    }
+
+    pub fn down(button: MouseButton, x: f64, y: f64) -> Self {
+        Self::new(MouseEventKind::Down(button), x, y)
+    }
+
+    pub fn up(button: MouseButton, x: f64, y: f64) -> Self {
+        Self::new(MouseEventKind::Up(button), x, y)
+    }
+
+    pub fn wheel(delta_x: i32, delta_y: i32, x: f64, y: f64) -> Self {
+        Self::new(MouseEventKind::Wheel { delta_x, delta_y }, x, y)
+    }
+}
+
+/// 事件接收端：后端每收到一个事件调用一次。
+/// 返回 `true` 表示该事件需要吞没（不传递给下层窗口）。
+pub type EventSink = Box<dyn FnMut(MouseEvent) -> bool + Send>;
+
+/// 全局鼠标输入后端
+///
+/// `run` 在调用者提供的专用线程上执行，阻塞直到事件源结束。
+/// 不支持吞没的后端 `can_swallow` 返回 false，并忽略 sink 的返回值。
+pub trait InputBackend: Send {
+    /// 后端名称，用于日志
+    fn name(&self) -> &'static str;
+
+    /// 能否吞没事件（让下层窗口收不到）
+    fn can_swallow(&self) -> bool;
+
+    /// 启动事件源，把每个归一化事件交给 sink
+    fn run(self: Box<Self>, sink: EventSink);
+}
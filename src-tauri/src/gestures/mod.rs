@@ -1,12 +1,50 @@
 //! gestures 模块
 //!
-//! 全局鼠标钩子 + 可插拔手势识别器架构。
+//! 全局鼠标监听 + 可插拔手势识别器架构。
 //!
-//! - `global`：钩子生命周期、拖拽、悬停、轨迹收集（所有宠物通用）
+//! - `input`：归一化鼠标事件与 `InputBackend` trait
+//! - `backends`：各平台的输入后端（Windows 钩子、合成脚本）
+//! - `trajectory`：带时间戳的绘制轨迹（速度 / 时长 / 停顿等特征）
+//! - `state`：平台无关的拖拽 / 绘制 / 悬停 / 右键状态机
+//! - `motion`：拖拽宠物时的松手速度与摇晃检测
+//! - `activation`：激活策略（总是 / 修饰键 / 长按 / 按前台应用），避免普通拖动被当成手势
+//! - `foreground`：查询前台应用的进程名（各平台实现）
+//! - `pipeline`：钩子线程 → 工作线程的无锁事件队列与宠物快照
+//! - `swallow`：钩子线程与状态机共用的吞没规则
+//! - `global`：监听线程 / 工作线程生命周期、Tauri 事件分发（所有宠物通用）
+//! - `streaming`：绘制过程中的增量识别会话（提前识别）
+//! - `multistroke`：多笔画模式下按笔画间隔超时分组
+//! - `path`：绘制折线的简化与发送节流（gesture-progress / gesture-path）
+//! - `templates`：用户手势模板库的持久化与样本录制
+//! - `geometry`：点集几何工具（距离 / 包围盒 / 质心 / 主轴），预处理与识别器共用
+//! - `preprocess`：笔画预处理（去抖 / 去离群点 / 平滑 / 重采样 / 简化），识别器按需声明
 //! - `recognizers`：手势识别 trait 与具体实现（按宠物需求注册）
+//! - `registry`：识别器名 → 工厂函数，按 JSON 参数构建识别器
+//! - `arbiter`：在多个识别器的带分候选中挑出最佳结果
 
+pub mod activation;
+pub mod arbiter;
+pub mod backends;
+pub mod foreground;
+pub mod geometry;
 pub mod global;
+pub mod input;
+pub mod motion;
+pub mod multistroke;
+pub mod path;
+pub mod pipeline;
+pub mod preprocess;
 pub mod recognizers;
+pub mod registry;
+pub mod state;
+pub mod streaming;
+pub mod swallow;
+pub mod templates;
+pub mod trajectory;
 
 // 对外 re-export，让 lib.rs 用起来和之前一样方便
-pub use global::{start_global_listener, set_pet_position, set_recognizers};
+pub use global::{
+    pet_physical_position, set_activation_policy, set_button_config, set_multi_stroke_recognizers,
+    set_multi_stroke_timeout, set_pet_physical_position, set_pet_position, set_recognizers,
+    set_stroke_max_duration, start_global_listener,
+};
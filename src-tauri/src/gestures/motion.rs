@@ -0,0 +1,223 @@
+//! 拖拽运动跟踪 —— 松手速度 + 摇晃检测
+//!
+//! 拖拽宠物时每个采样喂给 `DragMotion`：
+//!   - 只保留最近 `VELOCITY_WINDOW_MS` 内的采样，松手时据此算出速度向量（用于抛出）；
+//!     松手前停住超过窗口时长的，速度为 0
+//!   - 每个轴单独记录方向反转：朝反方向移动超过 `amplitude` 才算一次反转，
+//!     `SHAKE_WINDOW_MS` 内累计 `SHAKE_REVERSALS` 次即判定为摇晃
+//!
+//! 坐标为物理像素，时间为相对按下时刻的毫秒数；不做任何 I/O。
+
+use std::collections::VecDeque;
+
+use super::trajectory::TrajectoryPoint;
+
+/// 计算松手速度的时间窗口（毫秒）
+const VELOCITY_WINDOW_MS: f64 = 80.0;
+/// 判定摇晃的时间窗口（毫秒）
+const SHAKE_WINDOW_MS: f64 = 800.0;
+/// 窗口内至少需要的方向反转次数
+const SHAKE_REVERSALS: usize = 4;
+/// 两次摇晃之间的最短间隔（毫秒），避免持续摇晃时刷屏
+const SHAKE_COOLDOWN_MS: f64 = 1000.0;
+
+/// 单个轴上的方向反转跟踪
+#[derive(Default)]
+struct AxisSwing {
+    /// 当前移动方向：1 / -1，尚未确定为 0
+    direction: i8,
+    /// 当前方向上到达的极值
+    extreme: f64,
+}
+
+impl AxisSwing {
+    fn start(value: f64) -> Self {
+        Self {
+            direction: 0,
+            extreme: value,
+        }
+    }
+
+    /// 喂入新坐标；发生一次反转时返回 true
+    fn push(&mut self, value: f64, amplitude: f64) -> bool {
+        let delta = value - self.extreme;
+        match self.direction {
+            0 => {
+                if delta.abs() >= amplitude {
+                    self.direction = delta.signum() as i8;
+                    self.extreme = value;
+                }
+                false
+            }
+            direction => {
+                if delta * direction as f64 >= 0.0 {
+                    // 同方向继续走，更新极值
+                    self.extreme = value;
+                    false
+                } else if delta.abs() >= amplitude {
+                    self.direction = -direction;
+                    self.extreme = value;
+                    true
+                } else {
+                    false
+                }
+            }
+        }
+    }
+}
+
+/// 一次拖拽的运动状态
+#[derive(Default)]
+pub struct DragMotion {
+    /// 最近一段时间的采样（用于松手速度）
+    recent: VecDeque<TrajectoryPoint>,
+    swing_x: AxisSwing,
+    swing_y: AxisSwing,
+    /// 窗口内各次反转的时刻
+    reversals: VecDeque<f64>,
+    /// 算一次反转的最小回摆距离（物理像素）
+    amplitude: f64,
+    last_shake: Option<f64>,
+}
+
+impl DragMotion {
+    /// 按下位置开始跟踪；`amplitude` 为算一次反转的最小回摆距离（物理像素）
+    pub fn start(point: TrajectoryPoint, amplitude: f64) -> Self {
+        Self {
+            recent: VecDeque::from([point]),
+            swing_x: AxisSwing::start(point.x),
+            swing_y: AxisSwing::start(point.y),
+            reversals: VecDeque::new(),
+            amplitude,
+            last_shake: None,
+        }
+    }
+
+    /// 喂入一个拖拽采样；判定为摇晃时返回窗口内的反转次数
+    pub fn push(&mut self, point: TrajectoryPoint) -> Option<usize> {
+        self.recent.push_back(point);
+        // 至少保留两个采样，停顿后再甩也能算出一段速度
+        while self.recent.len() > 2 && point.t - self.recent[0].t > VELOCITY_WINDOW_MS {
+            self.recent.pop_front();
+        }
+
+        let reversed_x = self.swing_x.push(point.x, self.amplitude);
+        let reversed_y = self.swing_y.push(point.y, self.amplitude);
+        if reversed_x || reversed_y {
+            self.reversals.push_back(point.t);
+        }
+        while self
+            .reversals
+            .front()
+            .is_some_and(|&t| point.t - t > SHAKE_WINDOW_MS)
+        {
+            self.reversals.pop_front();
+        }
+
+        let cooled = self
+            .last_shake
+            .map_or(true, |t| point.t - t >= SHAKE_COOLDOWN_MS);
+        if self.reversals.len() < SHAKE_REVERSALS || !cooled {
+            return None;
+        }
+        let reversals = self.reversals.len();
+        self.reversals.clear();
+        self.last_shake = Some(point.t);
+        Some(reversals)
+    }
+
+    /// 在 `release_t` 时刻松手的速度向量（物理像素 / 秒）
+    ///
+    /// 没有时间信息，或松手前已经停住超过窗口时长时为 (0, 0)。
+    pub fn release_velocity(&self, release_t: f64) -> (f64, f64) {
+        let (Some(first), Some(last)) = (self.recent.front(), self.recent.back()) else {
+            return (0.0, 0.0);
+        };
+        let dt = last.t - first.t;
+        if dt <= 0.0 || release_t - last.t >= VELOCITY_WINDOW_MS {
+            return (0.0, 0.0);
+        }
+        (
+            (last.x - first.x) / dt * 1000.0,
+            (last.y - first.y) / dt * 1000.0,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AMPLITUDE: f64 = 10.0;
+
+    /// 沿 x 轴在 0 与 20 之间来回，每 `period` 毫秒到达一端；返回判定为摇晃的时刻与反转次数
+    fn swing(motion: &mut DragMotion, from: f64, to: f64, period: f64) -> Vec<(f64, usize)> {
+        let mut shakes = Vec::new();
+        let mut t = from;
+        let mut step = 1;
+        while t <= to {
+            let x = if step % 2 == 1 { 20.0 } else { 0.0 };
+            if let Some(reversals) = motion.push(TrajectoryPoint::new(x, 0.0, t)) {
+                shakes.push((t, reversals));
+            }
+            t += period;
+            step += 1;
+        }
+        shakes
+    }
+
+    fn motion() -> DragMotion {
+        DragMotion::start(TrajectoryPoint::new(0.0, 0.0, 0.0), AMPLITUDE)
+    }
+
+    #[test]
+    fn shake_needs_enough_reversals() {
+        let mut motion = motion();
+        // 20ms 定方向，之后每 20ms 一次反转，第 4 次反转在 100ms
+        assert_eq!(
+            swing(&mut motion, 20.0, 100.0, 20.0),
+            [(100.0, SHAKE_REVERSALS)]
+        );
+    }
+
+    #[test]
+    fn small_wiggles_are_not_reversals() {
+        let mut motion = DragMotion::start(TrajectoryPoint::new(0.0, 0.0, 0.0), 30.0);
+        assert!(swing(&mut motion, 20.0, 2_000.0, 20.0).is_empty());
+    }
+
+    #[test]
+    fn reversals_outside_window_do_not_count() {
+        let mut motion = motion();
+        // 每 300ms 一次反转，800ms 窗口内最多只有 3 次
+        assert!(swing(&mut motion, 300.0, 5_000.0, 300.0).is_empty());
+    }
+
+    #[test]
+    fn cooldown_between_shakes() {
+        let mut motion = motion();
+        let shakes = swing(&mut motion, 20.0, 1_200.0, 20.0);
+        assert_eq!(shakes[0], (100.0, SHAKE_REVERSALS));
+        // 持续摇晃时，下一次要等冷却结束
+        assert_eq!(shakes[1].0, 100.0 + SHAKE_COOLDOWN_MS);
+        assert_eq!(shakes.len(), 2);
+    }
+
+    #[test]
+    fn release_velocity_uses_recent_window() {
+        let mut motion = motion();
+        // 前 100ms 慢速（0.1 px/ms），之后快速（2 px/ms）
+        let mut x = 0.0;
+        for i in 1..=20 {
+            x += if i <= 10 { 1.0 } else { 20.0 };
+            motion.push(TrajectoryPoint::new(x, 0.0, i as f64 * 10.0));
+        }
+        let (vx, vy) = motion.release_velocity(200.0);
+        assert!((vx - 2_000.0).abs() < 1e-9 && vy == 0.0);
+        // 停住超过窗口时长后松手，不再抛出
+        assert_eq!(
+            motion.release_velocity(200.0 + VELOCITY_WINDOW_MS),
+            (0.0, 0.0)
+        );
+    }
+}
@@ -0,0 +1,176 @@
+//! 多笔画分组
+//!
+//! 开启多笔画模式后，每一笔都会暂存到 `StrokeGroup`，连同它的单笔仲裁结论；
+//! 只要下一笔在笔画间隔超时内落笔，就归入同一组。
+//! 超时（且当前没有正在画的笔画）后，整组先交给 `MultiStrokeRecognizer`，
+//! 只有多笔画识别器用上的笔画丢弃单笔结论，其余笔画按顺序发出暂缓的单笔结论——
+//! 否则 "X" 的第一笔会先被当成一次甩动（flick）发出去。
+//!
+//! 分组本身不做 I/O，时间为后端的事件时间戳（毫秒），由调用方传入。
+
+use std::time::Duration;
+
+use super::arbiter::Arbitration;
+use super::trajectory::Trajectory;
+
+/// 默认笔画间隔超时
+pub const DEFAULT_STROKE_TIMEOUT: Duration = Duration::from_millis(600);
+
+/// 一组最多保留的笔画数（超过后最早的笔画被挤出）
+const MAX_GROUP_STROKES: usize = 8;
+
+/// 分组中的一笔：轨迹 + 暂缓发出的单笔仲裁结论
+#[derive(Debug)]
+pub struct GroupedStroke {
+    pub stroke: Trajectory,
+    pub single: Arbitration,
+    /// 抬笔时的事件时间戳
+    pub end_time: u32,
+}
+
+/// 超时后交出的一组笔画
+#[derive(Debug)]
+pub struct StrokeBatch {
+    pub strokes: Vec<GroupedStroke>,
+}
+
+impl StrokeBatch {
+    /// 首笔落笔到末笔抬笔的时长（毫秒）
+    pub fn duration_ms(&self) -> f64 {
+        span_ms(&self.strokes)
+    }
+}
+
+/// 连续几笔从首笔落笔到末笔抬笔的时长（毫秒）；落笔时刻由抬笔时刻减去该笔时长推算
+pub fn span_ms(strokes: &[GroupedStroke]) -> f64 {
+    let (Some(first), Some(last)) = (strokes.first(), strokes.last()) else {
+        return 0.0;
+    };
+    let started_at = first
+        .end_time
+        .wrapping_sub(first.stroke.duration_ms().max(0.0) as u32);
+    last.end_time.wrapping_sub(started_at) as f64
+}
+
+#[derive(Default)]
+pub struct StrokeGroup {
+    strokes: Vec<GroupedStroke>,
+}
+
+impl StrokeGroup {
+    pub fn is_empty(&self) -> bool {
+        self.strokes.is_empty()
+    }
+
+    /// 追加一笔
+    ///
+    /// 分组已满时返回被挤出的最早一笔，调用方应立即发出它的单笔结论。
+    pub fn push(&mut self, stroke: GroupedStroke) -> Option<GroupedStroke> {
+        let evicted = (self.strokes.len() == MAX_GROUP_STROKES).then(|| self.strokes.remove(0));
+        self.strokes.push(stroke);
+        evicted
+    }
+
+    /// 放弃分组（不做多笔画识别），返回其中的笔画
+    pub fn take(&mut self) -> Vec<GroupedStroke> {
+        std::mem::take(&mut self.strokes)
+    }
+
+    /// 检查分组是否已超时（`now` 为当前的后端时间戳）
+    ///
+    /// - 超时：返回 `Ok(batch)` 并清空分组
+    /// - 尚未超时：返回 `Err(Some(剩余等待时间))`
+    /// - 分组为空或正在画下一笔：返回 `Err(None)`（等新事件唤醒即可）
+    pub fn poll(
+        &mut self,
+        now: u32,
+        timeout: Duration,
+        drawing: bool,
+    ) -> Result<StrokeBatch, Option<Duration>> {
+        let Some(last_end) = self.strokes.last().map(|s| s.end_time) else {
+            return Err(None);
+        };
+        if drawing {
+            return Err(None);
+        }
+        // 回绕安全的 now - last_end；时间戳比抬笔还早时按 0 处理
+        let elapsed = Duration::from_millis((now.wrapping_sub(last_end) as i32).max(0) as u64);
+        if elapsed < timeout {
+            return Err(Some(timeout - elapsed));
+        }
+
+        Ok(StrokeBatch {
+            strokes: self.take(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gestures::arbiter::{self, Verdict};
+    use crate::gestures::trajectory::TrajectoryPoint;
+
+    const TIMEOUT: Duration = DEFAULT_STROKE_TIMEOUT;
+
+    fn stroke(duration_ms: f64, end_time: u32) -> GroupedStroke {
+        let points = vec![
+            TrajectoryPoint::new(0.0, 0.0, 0.0),
+            TrajectoryPoint::new(50.0, 50.0, duration_ms),
+        ];
+        GroupedStroke {
+            stroke: Trajectory::new(points, duration_ms),
+            single: arbiter::arbitrate(Vec::new()),
+            end_time,
+        }
+    }
+
+    #[test]
+    fn waits_for_timeout_after_last_stroke() {
+        let mut group = StrokeGroup::default();
+        assert!(group.push(stroke(200.0, 1_200)).is_none());
+        assert_eq!(
+            group.poll(1_500, TIMEOUT, false).unwrap_err(),
+            Some(Duration::from_millis(300))
+        );
+        group.push(stroke(150.0, 1_700));
+        // 第二笔抬笔后重新计时
+        assert!(group.poll(1_900, TIMEOUT, false).is_err());
+        // 正在画下一笔时不会超时
+        assert_eq!(group.poll(2_400, TIMEOUT, true).unwrap_err(), None);
+
+        let batch = group.poll(2_300, TIMEOUT, false).unwrap();
+        assert_eq!(batch.strokes.len(), 2);
+        assert!(matches!(
+            batch.strokes[0].single.verdict,
+            Verdict::Unrecognized
+        ));
+        // 首笔落笔（1000）到末笔抬笔（1700）
+        assert_eq!(batch.duration_ms(), 700.0);
+        assert!(group.is_empty());
+        assert_eq!(group.poll(5_000, TIMEOUT, false).unwrap_err(), None);
+    }
+
+    #[test]
+    fn timestamps_wrap_around() {
+        let mut group = StrokeGroup::default();
+        group.push(stroke(100.0, u32::MAX - 50));
+        assert!(group.poll(100, TIMEOUT, false).is_err());
+        let batch = group.poll(600, TIMEOUT, false).unwrap();
+        assert_eq!(batch.duration_ms(), 100.0);
+    }
+
+    #[test]
+    fn full_group_evicts_oldest() {
+        let mut group = StrokeGroup::default();
+        for i in 0..MAX_GROUP_STROKES {
+            assert!(group.push(stroke(50.0, i as u32 * 100 + 1_000)).is_none());
+        }
+        let evicted = group.push(stroke(50.0, 1_800)).unwrap();
+        assert_eq!(evicted.end_time, 1_000);
+        // 时长从留下的首笔（1100 抬笔，1050 落笔）算起
+        let batch = group.poll(3_000, TIMEOUT, false).unwrap();
+        assert_eq!(batch.strokes.len(), MAX_GROUP_STROKES);
+        assert_eq!(batch.duration_ms(), 750.0);
+    }
+}
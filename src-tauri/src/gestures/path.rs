@@ -0,0 +1,103 @@
+//! 绘制折线的简化与发送节流 —— "gesture-progress" / "gesture-path" 的纯计算部分
+//!
+//! 不做任何系统调用：显示器拓扑与当前时刻都由调用方（global.rs）传入，便于单元测试。
+
+use std::time::{Duration, Instant};
+
+use super::preprocess::{self, Stage};
+use super::trajectory::{Trajectory, TrajectoryPoint};
+use crate::display::MonitorTopology;
+
+/// 发出前的折线简化（逻辑像素）
+const SIMPLIFY: &[Stage] = &[preprocess::DEJITTER, Stage::Simplify { tolerance: 1.0 }];
+
+/// 简化一笔轨迹（物理像素）并换算为逻辑像素；首尾点总是保留
+pub fn simplify(stroke: &Trajectory, topology: &MonitorTopology) -> Vec<TrajectoryPoint> {
+    let Some((cx, cy)) = stroke.centroid() else {
+        return Vec::new();
+    };
+    let scale = topology.scale_at(cx, cy);
+    preprocess::apply(stroke, SIMPLIFY, scale)
+        .points()
+        .iter()
+        .map(|&TrajectoryPoint { x, y, t }| {
+            let (x, y) = topology.to_logical(x, y);
+            TrajectoryPoint::new(x, y, t)
+        })
+        .collect()
+}
+
+/// 最小发送间隔的节流器
+pub struct Throttle {
+    interval: Duration,
+    last_emit: Instant,
+}
+
+impl Throttle {
+    /// `now` 视为刚发送过一次
+    pub fn new(interval: Duration, now: Instant) -> Self {
+        Self {
+            interval,
+            last_emit: now,
+        }
+    }
+
+    /// 现在可以发送时记下发送时刻并返回 None；否则返回还需等待的时间
+    pub fn poll(&mut self, now: Instant) -> Option<Duration> {
+        let elapsed = now.saturating_duration_since(self.last_emit);
+        if elapsed < self.interval {
+            return Some(self.interval - elapsed);
+        }
+        self.last_emit = now;
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::{MonitorInfo, PhysicalRect};
+
+    fn topology(scale: f64) -> MonitorTopology {
+        let bounds = PhysicalRect::new(0, 0, 3840, 2160);
+        MonitorTopology::new(vec![MonitorInfo {
+            id: "main".into(),
+            bounds,
+            work_area: bounds,
+            scale,
+            primary: true,
+        }])
+    }
+
+    #[test]
+    fn simplify_keeps_endpoints_and_drops_collinear_points() {
+        // 向右 10 段，再向下 10 段（带不到 1 像素的抖动）；200% 缩放下换算为逻辑像素
+        let points: Vec<TrajectoryPoint> = (0..=10)
+            .map(|i| TrajectoryPoint::new(i as f64 * 20.0, 0.0, i as f64 * 10.0))
+            .chain((1..=10).map(|i| {
+                let jitter = if i % 2 == 0 { 0.0 } else { 0.6 };
+                TrajectoryPoint::new(200.0 + jitter, i as f64 * 20.0, 100.0 + i as f64 * 10.0)
+            }))
+            .collect();
+        let stroke = Trajectory::new(points, 200.0);
+        let simplified = simplify(&stroke, &topology(2.0));
+        let positions: Vec<(f64, f64, f64)> = simplified.iter().map(|p| (p.x, p.y, p.t)).collect();
+        assert_eq!(
+            positions,
+            [(0.0, 0.0, 0.0), (100.0, 0.0, 100.0), (100.0, 100.0, 200.0)]
+        );
+        assert!(simplify(&Trajectory::default(), &topology(1.0)).is_empty());
+    }
+
+    #[test]
+    fn throttle_spaces_out_emits() {
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+        let mut throttle = Throttle::new(Duration::from_millis(33), start);
+        assert_eq!(throttle.poll(at(10)), Some(Duration::from_millis(23)));
+        assert_eq!(throttle.poll(at(33)), None);
+        // 刚发送过：从这次重新计时
+        assert_eq!(throttle.poll(at(40)), Some(Duration::from_millis(26)));
+        assert_eq!(throttle.poll(at(100)), None);
+    }
+}
@@ -0,0 +1,256 @@
+//! 钩子线程 → 手势工作线程的事件管道
+//!
+//! 钩子回调必须尽快返回（Windows 超过 LowLevelHooksTimeout 会直接摘掉钩子），
+//! 因此钩子线程只做三件事，全部无锁：
+//!   1. 读取 `PetSnapshot` 的原子变量，按 swallow.rs 的规则决定是否吞没
+//!   2. 把带时间戳的事件推进有界无锁队列 `EventQueue`
+//!   3. 唤醒工作线程
+//!
+//! 状态机、坐标换算、Tauri emit、手势识别全部在工作线程中完成，
+//! 工作线程处理完事件后把宠物位置等信息发布回 `PetSnapshot`。
+
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering};
+use std::thread;
+
+use crossbeam_queue::ArrayQueue;
+
+use super::input::{ButtonConfig, DrawButton, MouseEvent, MouseEventKind};
+use super::swallow::{self, SwallowContext};
+
+/// 队列容量
+const QUEUE_CAPACITY: usize = 1024;
+/// 为按键变化 / 控制命令保留的槽位：队列接近满时只丢弃移动事件
+const RESERVED_SLOTS: usize = 64;
+/// 保留槽位也用完时，非移动命令让出时间片等待工作线程取走的次数
+const PUSH_RETRIES: usize = 16;
+
+/// 工作线程的输入
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Command {
+    /// 后端上报的鼠标事件
+    Input(MouseEvent),
+    /// 前端同步的宠物位置与命中半径（物理像素）
+    SetPet { x: f64, y: f64, hit_radius: f64 },
+    /// 中键 / 侧键配置
+    SetButtons(ButtonConfig),
+}
+
+/// 有界无锁事件队列
+///
+/// 移动事件可以丢（下一次移动会带来新坐标），按键变化不能丢：
+/// 队列剩余空间不足 `RESERVED_SLOTS` 时移动事件被丢弃，
+/// 保留的槽位只给按键变化与控制命令使用。
+/// 工作线程卡住导致保留槽位也用完时，非移动命令短暂让出时间片重试，
+/// 仍然失败才丢弃并计数（钩子回调不能无限等待）。
+pub struct EventQueue {
+    queue: ArrayQueue<Command>,
+    dropped_moves: AtomicU64,
+    dropped_commands: AtomicU64,
+}
+
+impl Default for EventQueue {
+    fn default() -> Self {
+        Self::with_capacity(QUEUE_CAPACITY)
+    }
+}
+
+impl EventQueue {
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            queue: ArrayQueue::new(capacity),
+            dropped_moves: AtomicU64::new(0),
+            dropped_commands: AtomicU64::new(0),
+        }
+    }
+
+    /// 推入一条命令，返回是否入队成功
+    pub fn push(&self, command: Command) -> bool {
+        let droppable = matches!(
+            command,
+            Command::Input(MouseEvent {
+                kind: MouseEventKind::Move,
+                ..
+            })
+        );
+        if droppable {
+            if self.queue.len() + RESERVED_SLOTS >= self.queue.capacity() {
+                self.dropped_moves.fetch_add(1, Ordering::Relaxed);
+                return false;
+            }
+            return self.queue.push(command).is_ok();
+        }
+
+        let mut command = command;
+        for _ in 0..PUSH_RETRIES {
+            match self.queue.push(command) {
+                Ok(()) => return true,
+                Err(rejected) => {
+                    command = rejected;
+                    thread::yield_now();
+                }
+            }
+        }
+        if self.queue.push(command).is_ok() {
+            return true;
+        }
+        self.dropped_commands.fetch_add(1, Ordering::Relaxed);
+        false
+    }
+
+    pub fn pop(&self) -> Option<Command> {
+        self.queue.pop()
+    }
+
+    /// 累计丢弃的移动事件数（用于日志）
+    pub fn dropped_moves(&self) -> u64 {
+        self.dropped_moves.load(Ordering::Relaxed)
+    }
+
+    /// 累计丢弃的按键变化 / 控制命令数（正常情况下始终为 0）
+    pub fn dropped_commands(&self) -> u64 {
+        self.dropped_commands.load(Ordering::Relaxed)
+    }
+}
+
+/// 工作线程发布给钩子线程的只读快照（全部为原子变量，读写都不加锁）
+pub struct PetSnapshot {
+    x: AtomicU64,
+    y: AtomicU64,
+    hit_radius: AtomicU64,
+    synced: AtomicBool,
+    dragging: AtomicBool,
+    /// 正在绘制（此时按下右键用于取消这一笔）
+    drawing: AtomicBool,
+    /// 宠物上的中键 / 侧键是否吞没（前端配置）
+    swallow_extra_buttons: AtomicBool,
+    /// 画手势用的键是否为中键（前端配置）
+    draw_middle: AtomicBool,
+    /// 已被吞没按下的键（`MouseButton::mask`），只由钩子线程读写
+    swallowed_buttons: AtomicU8,
+}
+
+impl Default for PetSnapshot {
+    fn default() -> Self {
+        Self {
+            x: AtomicU64::new((-9999.0f64).to_bits()),
+            y: AtomicU64::new((-9999.0f64).to_bits()),
+            hit_radius: AtomicU64::new(0f64.to_bits()),
+            synced: AtomicBool::new(false),
+            dragging: AtomicBool::new(false),
+            drawing: AtomicBool::new(false),
+            swallow_extra_buttons: AtomicBool::new(false),
+            draw_middle: AtomicBool::new(false),
+            swallowed_buttons: AtomicU8::new(0),
+        }
+    }
+}
+
+impl PetSnapshot {
+    /// 工作线程发布宠物位置与拖拽 / 绘制状态
+    pub fn publish(
+        &self,
+        position: Option<(f64, f64)>,
+        hit_radius: f64,
+        dragging: bool,
+        drawing: bool,
+    ) {
+        if let Some((x, y)) = position {
+            self.x.store(x.to_bits(), Ordering::Relaxed);
+            self.y.store(y.to_bits(), Ordering::Relaxed);
+            self.hit_radius
+                .store(hit_radius.to_bits(), Ordering::Relaxed);
+        }
+        self.dragging.store(dragging, Ordering::Relaxed);
+        self.drawing.store(drawing, Ordering::Relaxed);
+        self.synced.store(position.is_some(), Ordering::Release);
+    }
+
+    /// 宠物位置；尚未同步过时返回 None
+    pub fn position(&self) -> Option<(f64, f64)> {
+        if !self.synced.load(Ordering::Acquire) {
+            return None;
+        }
+        Some((
+            f64::from_bits(self.x.load(Ordering::Relaxed)),
+            f64::from_bits(self.y.load(Ordering::Relaxed)),
+        ))
+    }
+
+    /// 设置画手势的键与宠物上中键 / 侧键的吞没
+    pub fn set_buttons(&self, buttons: ButtonConfig) {
+        self.swallow_extra_buttons
+            .store(buttons.swallow_extra_buttons, Ordering::Relaxed);
+        self.draw_middle
+            .store(buttons.draw_button == DrawButton::Middle, Ordering::Relaxed);
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.dragging.load(Ordering::Relaxed)
+    }
+
+    fn is_over_pet(&self, x: f64, y: f64) -> bool {
+        let Some((px, py)) = self.position() else {
+            return false;
+        };
+        let r = f64::from_bits(self.hit_radius.load(Ordering::Relaxed));
+        ((x - px).powi(2) + (y - py).powi(2)).sqrt() <= r
+    }
+
+    /// 钩子线程调用：决定该事件是否需要吞没（规则见 swallow.rs，与 `GestureState` 共用）
+    pub fn should_swallow(&self, event: &MouseEvent) -> bool {
+        let context = SwallowContext {
+            over_pet: self.is_over_pet(event.x, event.y),
+            drawing: self.drawing.load(Ordering::Relaxed),
+            buttons: ButtonConfig {
+                draw_button: if self.draw_middle.load(Ordering::Relaxed) {
+                    DrawButton::Middle
+                } else {
+                    DrawButton::Left
+                },
+                swallow_extra_buttons: self.swallow_extra_buttons.load(Ordering::Relaxed),
+            },
+        };
+        // 只有钩子线程读写，读-改-写不需要原子操作
+        let mut swallowed = self.swallowed_buttons.load(Ordering::Relaxed);
+        let swallow = swallow::decide(&event.kind, context, &mut swallowed);
+        self.swallowed_buttons.store(swallowed, Ordering::Relaxed);
+        swallow
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gestures::input::MouseButton;
+
+    fn input(kind: MouseEventKind) -> Command {
+        Command::Input(MouseEvent::new(kind, 0.0, 0.0))
+    }
+
+    #[test]
+    fn up_survives_queue_full_of_moves() {
+        let queue = EventQueue::with_capacity(RESERVED_SLOTS + 8);
+        let pushed = (0..100)
+            .filter(|_| queue.push(input(MouseEventKind::Move)))
+            .count();
+        assert_eq!(pushed, 8);
+        assert_eq!(queue.dropped_moves(), 92);
+
+        assert!(queue.push(input(MouseEventKind::Up(MouseButton::Left))));
+        assert_eq!(queue.dropped_commands(), 0);
+        let last = std::iter::from_fn(|| queue.pop()).last();
+        assert_eq!(last, Some(input(MouseEventKind::Up(MouseButton::Left))));
+    }
+
+    #[test]
+    fn lost_commands_are_counted() {
+        let queue = EventQueue::with_capacity(RESERVED_SLOTS + 8);
+        while queue.push(input(MouseEventKind::Down(MouseButton::Middle))) {}
+        assert_eq!(queue.dropped_commands(), 1);
+
+        // 工作线程取走一个后，下一条命令就能入队
+        queue.pop();
+        assert!(queue.push(input(MouseEventKind::Up(MouseButton::Middle))));
+        assert_eq!(queue.dropped_commands(), 1);
+    }
+}
@@ -0,0 +1,476 @@
+//! 笔画预处理
+//!
+//! 识别器通过 `GestureRecognizer::stages()` 声明自己需要的预处理步骤，
+//! global.rs 在识别前按声明处理轨迹（相同步骤的识别器共享一次计算）。
+//!
+//! 所有距离参数都是 **逻辑像素**，按笔画所在显示器的缩放因子换算成物理像素：
+//! 高 DPI 鼠标 / 触摸板上报的密集、抖动的采样点与普通鼠标经过同样的预处理后
+//! 得到相近的轨迹，识别结果不再依赖输入设备。
+//!
+//! 各步骤都保留时间戳（插值点的时间线性插值），首末点始终保留，
+//! 因此时长、松手停顿等时间特征不受影响。
+//!
+//! 另外提供识别器内部使用的点列工具：按点数重采样、归一化到单位包围盒。
+
+use super::geometry::{centroid, distance, extent};
+use super::trajectory::{Trajectory, TrajectoryPoint};
+
+/// 预处理步骤（距离单位：逻辑像素）
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Stage {
+    /// 去抖：丢弃与上一个保留点距离小于 `min_distance` 的点
+    Dejitter { min_distance: f64 },
+    /// 去离群点：跳出去又跳回来、步长超过中位步长 `max_step_ratio` 倍的孤立点
+    RemoveOutliers { max_step_ratio: f64 },
+    /// 平滑
+    Smooth(Smoothing),
+    /// 按固定弧长间隔等距重采样
+    Resample { spacing: f64 },
+    /// Douglas–Peucker 折线简化
+    Simplify { tolerance: f64 },
+}
+
+/// 平滑方式
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Smoothing {
+    /// Savitzky–Golay（二次多项式，窗口 2 × half_window + 1），保留拐角比均值滤波好
+    SavitzkyGolay { half_window: usize },
+    /// 正反两遍指数平滑（零相位，不产生滞后）
+    Exponential { alpha: f64 },
+}
+
+/// 常用的去抖步骤
+pub const DEJITTER: Stage = Stage::Dejitter { min_distance: 1.5 };
+/// 常用的去离群点步骤
+pub const REMOVE_OUTLIERS: Stage = Stage::RemoveOutliers {
+    max_step_ratio: 4.0,
+};
+
+/// 按顺序执行预处理步骤
+pub fn apply(stroke: &Trajectory, stages: &[Stage], scale: f64) -> Trajectory {
+    if stages.is_empty() {
+        return stroke.clone();
+    }
+    let mut points = stroke.points().to_vec();
+    for stage in stages {
+        points = match *stage {
+            Stage::Dejitter { min_distance } => dejitter(&points, min_distance * scale),
+            Stage::RemoveOutliers { max_step_ratio } => remove_outliers(&points, max_step_ratio),
+            Stage::Smooth(Smoothing::SavitzkyGolay { half_window }) => {
+                savitzky_golay(&points, half_window)
+            }
+            Stage::Smooth(Smoothing::Exponential { alpha }) => exponential(&points, alpha),
+            Stage::Resample { spacing } => resample_spacing(&points, spacing * scale),
+            Stage::Simplify { tolerance } => douglas_peucker(&points, tolerance * scale),
+        };
+    }
+    Trajectory::new(points, stroke.duration_ms())
+}
+
+/// 同一笔画按不同步骤组合预处理的结果缓存
+pub struct Preprocessed<'a> {
+    stroke: &'a Trajectory,
+    scale: f64,
+    entries: Vec<(&'static [Stage], Trajectory)>,
+}
+
+impl<'a> Preprocessed<'a> {
+    pub fn new(stroke: &'a Trajectory, scale: f64) -> Self {
+        Self {
+            stroke,
+            scale,
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn get(&mut self, stages: &'static [Stage]) -> &Trajectory {
+        let index = match self.entries.iter().position(|(s, _)| *s == stages) {
+            Some(index) => index,
+            None => {
+                self.entries
+                    .push((stages, apply(self.stroke, stages, self.scale)));
+                self.entries.len() - 1
+            }
+        };
+        &self.entries[index].1
+    }
+}
+
+// ── 轨迹级步骤 ──────────────────────────────────────────────────────────────
+
+fn dejitter(points: &[TrajectoryPoint], min_distance: f64) -> Vec<TrajectoryPoint> {
+    let mut out: Vec<TrajectoryPoint> = Vec::with_capacity(points.len());
+    for (i, &point) in points.iter().enumerate() {
+        let too_close = out
+            .last()
+            .is_some_and(|last| last.distance_to(&point) < min_distance);
+        if !too_close {
+            out.push(point);
+        } else if i == points.len() - 1 {
+            // 末点（松手位置）必须保留：替换掉最后一个保留点（首点除外）
+            if out.len() > 1 {
+                out.pop();
+            }
+            out.push(point);
+        }
+    }
+    out
+}
+
+fn remove_outliers(points: &[TrajectoryPoint], max_step_ratio: f64) -> Vec<TrajectoryPoint> {
+    if points.len() < 5 {
+        return points.to_vec();
+    }
+    let mut steps: Vec<f64> = points.windows(2).map(|w| w[0].distance_to(&w[1])).collect();
+    steps.sort_by(f64::total_cmp);
+    let limit = steps[steps.len() / 2] * max_step_ratio;
+    if limit <= 0.0 {
+        return points.to_vec();
+    }
+
+    let mut out = vec![points[0]];
+    for i in 1..points.len() - 1 {
+        let (prev, point, next) = (out[out.len() - 1], points[i], points[i + 1]);
+        let (jump, back) = (prev.distance_to(&point), point.distance_to(&next));
+        // 跳出去又跳回来：前后两点本身离得很近
+        if jump > limit && back > limit && prev.distance_to(&next) < jump.min(back) / 2.0 {
+            continue;
+        }
+        out.push(point);
+    }
+    out.push(points[points.len() - 1]);
+    out
+}
+
+fn savitzky_golay(points: &[TrajectoryPoint], half_window: usize) -> Vec<TrajectoryPoint> {
+    let n = points.len();
+    // 端点附近窗口收缩，首末点保持不变
+    let weights: Vec<Vec<f64>> = (0..=half_window).map(savitzky_golay_weights).collect();
+    (0..n)
+        .map(|i| {
+            let m = half_window.min(i).min(n - 1 - i);
+            let (mut x, mut y) = (0.0, 0.0);
+            for (k, w) in weights[m].iter().enumerate() {
+                let p = points[i + k - m];
+                x += w * p.x;
+                y += w * p.y;
+            }
+            TrajectoryPoint::new(x, y, points[i].t)
+        })
+        .collect()
+}
+
+/// 二次 Savitzky–Golay 平滑系数（窗口 2m + 1）
+fn savitzky_golay_weights(m: usize) -> Vec<f64> {
+    let mf = m as f64;
+    let norm = (2.0 * mf + 1.0) * (4.0 * mf * mf + 4.0 * mf - 3.0);
+    (0..=2 * m)
+        .map(|k| {
+            let j = k as f64 - mf;
+            (3.0 * (3.0 * mf * mf + 3.0 * mf - 1.0) - 15.0 * j * j) / norm
+        })
+        .collect()
+}
+
+fn exponential(points: &[TrajectoryPoint], alpha: f64) -> Vec<TrajectoryPoint> {
+    let Some((&first, &last)) = points.first().zip(points.last()) else {
+        return Vec::new();
+    };
+    let alpha = alpha.clamp(0.0, 1.0);
+    let mut out = points.to_vec();
+    for i in 1..out.len() {
+        out[i].x = alpha * points[i].x + (1.0 - alpha) * out[i - 1].x;
+        out[i].y = alpha * points[i].y + (1.0 - alpha) * out[i - 1].y;
+    }
+    for i in (0..out.len().saturating_sub(1)).rev() {
+        out[i].x = alpha * out[i].x + (1.0 - alpha) * out[i + 1].x;
+        out[i].y = alpha * out[i].y + (1.0 - alpha) * out[i + 1].y;
+    }
+    let end = out.len() - 1;
+    out[0] = first;
+    out[end] = last;
+    out
+}
+
+fn resample_spacing(points: &[TrajectoryPoint], spacing: f64) -> Vec<TrajectoryPoint> {
+    if points.len() < 2 || spacing <= 0.0 {
+        return points.to_vec();
+    }
+    let mut out = vec![points[0]];
+    let mut accumulated = 0.0;
+    let mut prev = points[0];
+    let mut i = 1;
+    while i < points.len() {
+        let cur = points[i];
+        let d = prev.distance_to(&cur);
+        if d > 0.0 && accumulated + d >= spacing {
+            let k = (spacing - accumulated) / d;
+            let q = TrajectoryPoint::new(
+                prev.x + k * (cur.x - prev.x),
+                prev.y + k * (cur.y - prev.y),
+                prev.t + k * (cur.t - prev.t),
+            );
+            out.push(q);
+            prev = q;
+            accumulated = 0.0;
+        } else {
+            accumulated += d;
+            prev = cur;
+            i += 1;
+        }
+    }
+    // 末点（松手位置 / 时刻）始终保留
+    let last = points[points.len() - 1];
+    if out.last() != Some(&last) {
+        out.push(last);
+    }
+    out
+}
+
+fn douglas_peucker(points: &[TrajectoryPoint], tolerance: f64) -> Vec<TrajectoryPoint> {
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+    let mut stack = vec![(0, points.len() - 1)];
+    while let Some((start, end)) = stack.pop() {
+        let (a, b) = (points[start], points[end]);
+        let farthest = (start + 1..end)
+            .map(|i| (i, segment_distance(points[i], a, b)))
+            .max_by(|x, y| x.1.total_cmp(&y.1));
+        if let Some((index, distance)) = farthest {
+            if distance > tolerance {
+                keep[index] = true;
+                stack.push((start, index));
+                stack.push((index, end));
+            }
+        }
+    }
+    points
+        .iter()
+        .zip(keep)
+        .filter_map(|(&p, keep)| keep.then_some(p))
+        .collect()
+}
+
+/// 点到线段 ab 的距离
+fn segment_distance(p: TrajectoryPoint, a: TrajectoryPoint, b: TrajectoryPoint) -> f64 {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let length_sq = dx * dx + dy * dy;
+    if length_sq == 0.0 {
+        return p.distance_to(&a);
+    }
+    let k = (((p.x - a.x) * dx + (p.y - a.y) * dy) / length_sq).clamp(0.0, 1.0);
+    ((p.x - a.x - k * dx).powi(2) + (p.y - a.y - k * dy).powi(2)).sqrt()
+}
+
+// ── 点列工具 ────────────────────────────────────────────────────────────────
+
+/// 把一笔按等弧长间隔重采样为 n 个点
+pub fn resample_points(points: &[(f64, f64)], n: usize) -> Vec<(f64, f64)> {
+    resample_strokes(std::slice::from_ref(&points.to_vec()), n)
+}
+
+/// 把多笔按总弧长等间隔重采样为 n 个点（不跨笔画插值）
+pub fn resample_strokes(strokes: &[Vec<(f64, f64)>], n: usize) -> Vec<(f64, f64)> {
+    let (Some(&first), Some(&last)) = (
+        strokes.iter().find_map(|s| s.first()),
+        strokes.iter().rev().find_map(|s| s.last()),
+    ) else {
+        return Vec::new();
+    };
+    if n < 2 {
+        return vec![first; n];
+    }
+    let total: f64 = strokes
+        .iter()
+        .flat_map(|s| s.windows(2).map(|w| distance(w[0], w[1])))
+        .sum();
+    let interval = total / (n - 1) as f64;
+    if interval <= 0.0 {
+        return vec![first; n];
+    }
+
+    let mut out = Vec::with_capacity(n);
+    out.push(first);
+    let mut accumulated = 0.0;
+    for stroke in strokes {
+        let Some(&start) = stroke.first() else {
+            continue;
+        };
+        // 每一笔从自己的起点开始累计，笔画之间的空隙不计入弧长
+        let mut prev = start;
+        let mut i = 1;
+        while i < stroke.len() {
+            let cur = stroke[i];
+            let d = distance(prev, cur);
+            if d > 0.0 && accumulated + d >= interval {
+                let t = (interval - accumulated) / d;
+                let q = (prev.0 + t * (cur.0 - prev.0), prev.1 + t * (cur.1 - prev.1));
+                out.push(q);
+                // q 成为新的起点，继续在同一段上切分
+                prev = q;
+                accumulated = 0.0;
+            } else {
+                accumulated += d;
+                prev = cur;
+                i += 1;
+            }
+        }
+    }
+    // 浮点误差可能少一个点：补上最后一笔的终点
+    out.truncate(n);
+    while out.len() < n {
+        out.push(last);
+    }
+    out
+}
+
+/// 平移到质心并等比缩放到包围盒最长边为 1（保持长宽比，直线类手势不会被拉伸变形）
+pub fn unit_box(points: &[(f64, f64)]) -> Vec<(f64, f64)> {
+    let (width, height) = extent(points);
+    let size = width.max(height);
+    let size = if size > 0.0 { size } else { 1.0 };
+    let (cx, cy) = centroid(points);
+    points
+        .iter()
+        .map(|&(x, y)| ((x - cx) / size, (y - cy) / size))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stroke(points: &[(f64, f64)]) -> Trajectory {
+        let points = points
+            .iter()
+            .enumerate()
+            .map(|(i, &(x, y))| TrajectoryPoint::new(x, y, i as f64 * 10.0))
+            .collect::<Vec<_>>();
+        let duration = (points.len() as f64 - 1.0) * 10.0 + 5.0;
+        Trajectory::new(points, duration)
+    }
+
+    fn positions(stroke: &Trajectory) -> Vec<(f64, f64)> {
+        stroke.positions()
+    }
+
+    #[test]
+    fn dejitter_keeps_endpoints_and_scales_distance() {
+        let raw = stroke(&[(0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (3.0, 0.0), (3.5, 0.0)]);
+        let stages = [Stage::Dejitter { min_distance: 1.5 }];
+        assert_eq!(
+            positions(&apply(&raw, &stages, 1.0)),
+            [(0.0, 0.0), (2.0, 0.0), (3.5, 0.0)]
+        );
+        // 缩放 2.0 下阈值为 3 物理像素
+        assert_eq!(
+            positions(&apply(&raw, &stages, 2.0)),
+            [(0.0, 0.0), (3.5, 0.0)]
+        );
+    }
+
+    #[test]
+    fn remove_outliers_drops_isolated_spike() {
+        let mut points: Vec<(f64, f64)> = (0..10).map(|i| (i as f64 * 5.0, 0.0)).collect();
+        points[5] = (25.0, 200.0);
+        let cleaned = apply(&stroke(&points), &[REMOVE_OUTLIERS], 1.0);
+        assert_eq!(cleaned.len(), 9);
+        assert!(cleaned.points().iter().all(|p| p.y == 0.0));
+    }
+
+    #[test]
+    fn smoothing_keeps_lines_and_endpoints() {
+        let line: Vec<(f64, f64)> = (0..12).map(|i| (i as f64 * 4.0, i as f64 * 2.0)).collect();
+        for smoothing in [
+            Smoothing::SavitzkyGolay { half_window: 2 },
+            Smoothing::Exponential { alpha: 0.5 },
+        ] {
+            let smoothed = apply(&stroke(&line), &[Stage::Smooth(smoothing)], 1.0);
+            assert_eq!(smoothed.len(), line.len());
+            assert_eq!(smoothed.points()[0], stroke(&line).points()[0]);
+            assert_eq!(smoothed.points().last(), stroke(&line).points().last());
+            // 直线平滑后仍在直线上
+            for p in smoothed.points() {
+                assert!((p.y - p.x / 2.0).abs() < 1e-9, "{:?}: {:?}", smoothing, p);
+            }
+        }
+    }
+
+    #[test]
+    fn savitzky_golay_preserves_quadratics() {
+        let curve: Vec<(f64, f64)> = (0..15).map(|i| (i as f64, (i as f64).powi(2))).collect();
+        let stages = [Stage::Smooth(Smoothing::SavitzkyGolay { half_window: 3 })];
+        let smoothed = apply(&stroke(&curve), &stages, 1.0);
+        for (p, &(x, y)) in smoothed.points().iter().zip(&curve) {
+            assert!((p.x - x).abs() < 1e-9 && (p.y - y).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn resample_spaces_points_and_interpolates_time() {
+        let raw = stroke(&[(0.0, 0.0), (100.0, 0.0)]);
+        let resampled = apply(&raw, &[Stage::Resample { spacing: 10.0 }], 2.0);
+        // 缩放 2.0 下间距 20 物理像素
+        let xs: Vec<f64> = resampled.points().iter().map(|p| p.x.round()).collect();
+        assert_eq!(xs, [0.0, 20.0, 40.0, 60.0, 80.0, 100.0]);
+        assert!((resampled.points()[1].t - 2.0).abs() < 1e-9);
+        assert_eq!(resampled.duration_ms(), raw.duration_ms());
+    }
+
+    #[test]
+    fn simplify_keeps_corners() {
+        let raw = stroke(&[
+            (0.0, 0.0),
+            (50.0, 1.0),
+            (100.0, 0.0),
+            (100.0, 50.0),
+            (100.0, 100.0),
+        ]);
+        let simplified = apply(&raw, &[Stage::Simplify { tolerance: 2.0 }], 1.0);
+        assert_eq!(
+            positions(&simplified),
+            [(0.0, 0.0), (100.0, 0.0), (100.0, 100.0)]
+        );
+    }
+
+    #[test]
+    fn preprocessed_caches_per_stage_list() {
+        static STAGES: &[Stage] = &[Stage::Resample { spacing: 10.0 }];
+        let raw = stroke(&[(0.0, 0.0), (100.0, 0.0)]);
+        let mut cache = Preprocessed::new(&raw, 1.0);
+        assert_eq!(cache.get(&[]).len(), 2);
+        assert_eq!(cache.get(STAGES).len(), 11);
+        assert_eq!(cache.entries.len(), 2);
+        cache.get(STAGES);
+        assert_eq!(cache.entries.len(), 2);
+    }
+
+    #[test]
+    fn resample_strokes_skips_gaps_between_strokes() {
+        let strokes = vec![
+            vec![(0.0, 0.0), (10.0, 0.0)],
+            vec![(100.0, 0.0), (110.0, 0.0)],
+        ];
+        let points = resample_strokes(&strokes, 5);
+        // 总弧长 20，间隔 5：第一笔的终点与第二笔的起点之间不插值
+        assert_eq!(
+            points,
+            [
+                (0.0, 0.0),
+                (5.0, 0.0),
+                (10.0, 0.0),
+                (105.0, 0.0),
+                (110.0, 0.0)
+            ]
+        );
+
+        let unit = unit_box(&points);
+        let xs = unit.iter().map(|p| p.0);
+        let width = xs.clone().fold(f64::MIN, f64::max) - xs.fold(f64::MAX, f64::min);
+        assert!((width - 1.0).abs() < 1e-9);
+    }
+}
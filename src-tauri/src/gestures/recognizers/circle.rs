@@ -2,72 +2,554 @@
 //!
 //! 从原 gesture.rs 的 analyze_circle() 提取而来。
 //! 海鸥宠物的召唤手势：用户在屏幕上画一个圈。
+//!
+//! 判定流程：
+//!   1. 最小二乘椭圆拟合：先用主成分确定朝向，旋转后拟合
+//!      `A·x² + C·y² + D·x + E·y = 1`，得到中心、长短半轴与旋转角
+//!   2. 拟合残差（点到椭圆的归一化径向偏差）衡量形状规则度
+//!   3. 绕拟合中心累加带符号的角度增量：符号 = 绕行方向，绝对值 / 2π = 圈数
+//!   4. 闭合度：不到一圈时 = 角度覆盖率 × (1 − 首尾缺口 / 直径)，画满一圈为 1；
+//!      只看覆盖率的话，首尾错开的螺旋、绕中心转了 350° 的开口弧线也会被当成圈
+//!
+//! 置信度 = 残差得分 × 闭合度，残差为 0 时残差得分为 1，达到上限时为 0.5。
+//!
+//! 默认参数尽量与原先的扇区覆盖判定（12 个扇区覆盖 10 个、不查速度）一样宽松，
+//! 海鸥直接使用默认值；唯一收紧的是闭合度：开口弧线和没画满一圈的螺旋不再算圈。
+//!
+//! 屏幕坐标 y 轴向下，角度增量为正即视觉上的顺时针。
+//!
+//! 开启 `early_detection` 后提供增量识别：笔尖回到起点附近时，
+//! 对抽稀后的有界缓冲做同样的判定，画满一圈即触发，不必等抬笔。
 
-use std::f64::consts::PI;
-use super::{GestureRecognizer, GestureResult};
+use std::f64::consts::{PI, TAU};
 
-pub struct CircleRecognizer;
+use super::{
+    check_range, Candidate, GestureRecognizer, GestureResult, StrokeTracker, DEFAULT_MIN_SCORE,
+};
+use crate::gestures::geometry::{centroid, distance, principal_axes};
+use crate::gestures::preprocess::{self, Stage};
+use crate::gestures::trajectory::{Trajectory, TrajectoryPoint};
 
-impl GestureRecognizer for CircleRecognizer {
-    fn name(&self) -> &'static str {
-        "circle"
+/// 增量识别缓冲的采样间距（逻辑像素）
+const TRACKER_SPACING: f64 = 4.0;
+/// 增量识别缓冲的容量（满了隔一个丢一个）
+const TRACKER_POINTS: usize = 256;
+/// 两次检测之间至少新增的缓冲点数
+const TRACKER_CHECK_INTERVAL: usize = 8;
+/// 笔尖到起点的距离 / 包围盒最长边 低于该值才检测是否画完一圈
+const TRACKER_CLOSE_RATIO: f64 = 0.25;
+
+/// 可调参数（register_recognizers 的 params，缺省字段取默认值）
+#[derive(serde::Deserialize, Clone, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct CircleParams {
+    /// 最少轨迹点数
+    pub min_points: usize,
+    /// 最小等效半径（逻辑像素）
+    pub min_radius: f64,
+    /// 拟合残差上限
+    pub max_residual: f64,
+    /// 短轴 / 长轴 的下限（再扁就是一条线了）
+    pub min_axis_ratio: f64,
+    /// 最低闭合度
+    pub min_closure: f64,
+    /// 最低平均绘制速度（逻辑像素 / 毫秒）：更慢的绕圈多半是无意的鼠标移动；0 表示不检查
+    pub min_avg_speed: f64,
+    /// 最低置信度
+    pub min_score: f64,
+    /// 画满一圈时立即识别（不等抬笔）
+    pub early_detection: bool,
+}
+
+impl Default for CircleParams {
+    fn default() -> Self {
+        Self {
+            min_points: 12,
+            min_radius: 30.0,
+            max_residual: 0.25,
+            min_axis_ratio: 0.35,
+            min_closure: 0.85,
+            min_avg_speed: 0.0,
+            min_score: DEFAULT_MIN_SCORE,
+            early_detection: false,
+        }
     }
+}
 
-    fn analyze(&self, points: &[(f64, f64)], scale: f64) -> Option<GestureResult> {
-        if points.len() < 12 {
-            eprintln!("[circle] ❌ 点数不足 12");
-            return None;
+impl CircleParams {
+    pub fn validate(&self) -> Result<(), String> {
+        check_range("min_points", self.min_points as f64, 5.0, 1000.0)?;
+        check_range("min_radius", self.min_radius, 0.0, 10000.0)?;
+        check_range("max_residual", self.max_residual, 0.01, 1.0)?;
+        check_range("min_axis_ratio", self.min_axis_ratio, 0.0, 1.0)?;
+        check_range("min_closure", self.min_closure, 0.0, 1.0)?;
+        check_range("min_avg_speed", self.min_avg_speed, 0.0, 100.0)?;
+        check_range("min_score", self.min_score, 0.0, 1.0)
+    }
+}
+
+/// 绕行方向（屏幕视角）
+#[derive(serde::Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Winding {
+    Clockwise,
+    Counterclockwise,
+}
+
+/// 椭圆拟合结果（物理像素）
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EllipseFit {
+    pub center_x: f64,
+    pub center_y: f64,
+    /// 长半轴
+    pub semi_major: f64,
+    /// 短半轴
+    pub semi_minor: f64,
+    /// 长轴方向（弧度）
+    pub rotation: f64,
+}
+
+impl EllipseFit {
+    /// 点到椭圆的归一化径向距离（在椭圆上为 1）
+    fn radial(&self, x: f64, y: f64) -> f64 {
+        let (sin, cos) = self.rotation.sin_cos();
+        let (dx, dy) = (x - self.center_x, y - self.center_y);
+        let u = dx * cos + dy * sin;
+        let v = -dx * sin + dy * cos;
+        ((u / self.semi_major).powi(2) + (v / self.semi_minor).powi(2)).sqrt()
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct CircleRecognizer {
+    params: CircleParams,
+}
+
+impl CircleRecognizer {
+    pub fn new(params: CircleParams) -> Self {
+        Self { params }
+    }
+
+    /// 判定一笔是否为圆圈；不符合时返回原因（不打日志，增量识别逐点调用）
+    fn evaluate(&self, stroke: &Trajectory, scale: f64) -> Result<Candidate, String> {
+        let params = &self.params;
+        let points = stroke.positions();
+        if points.len() < params.min_points {
+            return Err(format!("点数不足 {}", params.min_points));
         }
 
-        let n = points.len() as f64;
-        let cx = points.iter().map(|p| p.0).sum::<f64>() / n;
-        let cy = points.iter().map(|p| p.1).sum::<f64>() / n;
+        let Some(fit) = fit_ellipse(&points) else {
+            return Err("无法拟合椭圆".into());
+        };
 
-        // 平均半径
-        let avg_r = points
-            .iter()
-            .map(|&(x, y)| ((x - cx).powi(2) + (y - cy).powi(2)).sqrt())
-            .sum::<f64>()
-            / n;
+        // 等效半径：面积相同的圆的半径
+        let radius = (fit.semi_major * fit.semi_minor).sqrt();
+        if radius / scale < params.min_radius {
+            return Err(format!(
+                "半径 {:.0} < {}",
+                radius / scale,
+                params.min_radius
+            ));
+        }
 
-        if avg_r < 30.0 {
-            eprintln!("[circle] ❌ 半径 {:.0} < 30", avg_r);
-            return None;
+        let axis_ratio = fit.semi_minor / fit.semi_major;
+        if axis_ratio < params.min_axis_ratio {
+            return Err(format!("太扁 ({:.2})", axis_ratio));
         }
 
-        // 半径标准差 / 平均半径 → 形状规则度
-        let std_r = (points
+        // 形状规则度：归一化径向偏差的均方根
+        let residual = (points
             .iter()
-            .map(|&(x, y)| {
-                let r = ((x - cx).powi(2) + (y - cy).powi(2)).sqrt();
-                (r - avg_r).powi(2)
-            })
+            .map(|&(x, y)| (fit.radial(x, y) - 1.0).powi(2))
             .sum::<f64>()
-            / n)
+            / points.len() as f64)
             .sqrt();
+        if residual > params.max_residual {
+            return Err(format!("形状太不规则 ({:.2})", residual));
+        }
 
-        if std_r / avg_r > 0.55 {
-            eprintln!("[circle] ❌ 形状太不规则 ({:.2})", std_r / avg_r);
-            return None;
+        // 绕行方向与圈数
+        let swept = swept_angle(&points, fit.center_x, fit.center_y);
+        let revolutions = swept.abs() / TAU;
+        let gap = distance(points[0], points[points.len() - 1]);
+        let closure = closure(revolutions, gap, radius);
+        if closure < params.min_closure {
+            return Err(format!(
+                "没有画完一圈 ({:.0}°，首尾缺口 {:.0}px，闭合度 {:.2})",
+                swept.abs().to_degrees(),
+                gap,
+                closure
+            ));
+        }
+        let direction = if swept > 0.0 {
+            Winding::Clockwise
+        } else {
+            Winding::Counterclockwise
+        };
+
+        // 速度检查：没有时间戳的后端跳过
+        if let Some(speed) = stroke.average_speed() {
+            let speed = speed / scale;
+            if speed < params.min_avg_speed {
+                return Err(format!(
+                    "画得太慢 ({:.2} px/ms，用时 {:.0}ms)",
+                    speed,
+                    stroke.duration_ms()
+                ));
+            }
+        }
+
+        let score = (1.0 - residual / (2.0 * params.max_residual)) * closure;
+        let result = GestureResult::Circle {
+            center_x: fit.center_x / scale,
+            center_y: fit.center_y / scale,
+            radius: radius / scale,
+            radius_x: fit.semi_major / scale,
+            radius_y: fit.semi_minor / scale,
+            rotation: fit.rotation.to_degrees(),
+            direction,
+            revolutions,
+            axis_ratio,
+            closure,
+        };
+        Ok(Candidate::new(result, score))
+    }
+}
+
+impl GestureRecognizer for CircleRecognizer {
+    fn name(&self) -> &'static str {
+        "circle"
+    }
+
+    fn min_score(&self) -> f64 {
+        self.params.min_score
+    }
+
+    /// 等距重采样：画得慢的一段不会在椭圆拟合中占更大权重
+    fn stages(&self) -> &'static [Stage] {
+        &[
+            preprocess::DEJITTER,
+            preprocess::REMOVE_OUTLIERS,
+            Stage::Resample { spacing: 3.0 },
+        ]
+    }
+
+    fn tracker(&self) -> Option<Box<dyn StrokeTracker>> {
+        self.params.early_detection.then(|| {
+            Box::new(CircleTracker {
+                recognizer: self.clone(),
+                points: Vec::new(),
+                checked_len: 0,
+            }) as Box<dyn StrokeTracker>
+        })
+    }
+
+    fn analyze(&self, stroke: &Trajectory, scale: f64) -> Option<Candidate> {
+        self.evaluate(stroke, scale)
+            .map_err(|reason| eprintln!("[circle] ❌ {}", reason))
+            .ok()
+    }
+}
+
+// ── 增量识别 ────────────────────────────────────────────────────────────────
+
+struct CircleTracker {
+    recognizer: CircleRecognizer,
+    /// 按间距抽稀后的采样，最多 `TRACKER_POINTS` 个
+    points: Vec<TrajectoryPoint>,
+    /// 上一次检测时的缓冲点数
+    checked_len: usize,
+}
+
+impl StrokeTracker for CircleTracker {
+    fn push(&mut self, point: TrajectoryPoint, scale: f64) -> Option<Candidate> {
+        if let Some(last) = self.points.last() {
+            if last.distance_to(&point) < TRACKER_SPACING * scale {
+                return None;
+            }
         }
+        if self.points.len() >= TRACKER_POINTS {
+            let mut index = 0;
+            self.points.retain(|_| {
+                index += 1;
+                index % 2 == 1
+            });
+            self.checked_len /= 2;
+        }
+        self.points.push(point);
 
-        // 扇区覆盖检查：轨迹是否覆盖了足够的角度范围
-        let mut sectors = [false; 12];
-        for &(x, y) in points {
-            let angle = (y - cy).atan2(x - cx);
-            let s = ((angle + PI) / (2.0 * PI) * 12.0) as usize % 12;
-            sectors[s] = true;
+        let len = self.points.len();
+        if len < self.recognizer.params.min_points
+            || len < self.checked_len + TRACKER_CHECK_INTERVAL
+        {
+            return None;
         }
-        let covered = sectors.iter().filter(|&&v| v).count();
-        if covered < 10 {
-            eprintln!("[circle] ❌ 扇区覆盖不足 ({}/12)", covered);
+        // 只在笔尖回到起点附近时检测
+        let (min_x, min_y, max_x, max_y) = self.points.iter().fold(
+            (f64::MAX, f64::MAX, f64::MIN, f64::MIN),
+            |(a, b, c, d), p| (a.min(p.x), b.min(p.y), c.max(p.x), d.max(p.y)),
+        );
+        let size = (max_x - min_x).max(max_y - min_y);
+        if point.distance_to(&self.points[0]) > size * TRACKER_CLOSE_RATIO {
             return None;
         }
+        self.checked_len = len;
+
+        let stroke = Trajectory::new(self.points.clone(), point.t);
+        let stroke = preprocess::apply(&stroke, self.recognizer.stages(), scale);
+        // 笔尖在起点附近时每隔几个点就检测一次，不符合的原因不打日志
+        self.recognizer.evaluate(&stroke, scale).ok()
+    }
+}
+
+/// 最小二乘椭圆拟合
+///
+/// 先按主成分方向旋转消去 xy 交叉项，再对
+/// `A·u² + C·v² + D·u + E·v = 1` 做线性最小二乘。
+pub fn fit_ellipse(points: &[(f64, f64)]) -> Option<EllipseFit> {
+    let (mx, my) = centroid(points);
+    let ((cos, sin), _) = principal_axes(points);
+    let rotation = sin.atan2(cos);
+
+    // 在以质心为原点、主轴为 u 轴的坐标系中拟合（数值更稳定）
+    let rotated: Vec<(f64, f64)> = points
+        .iter()
+        .map(|&(x, y)| {
+            let (dx, dy) = (x - mx, y - my);
+            (dx * cos + dy * sin, -dx * sin + dy * cos)
+        })
+        .collect();
+    let mut normal = [[0.0; 5]; 4];
+    for &(u, v) in &rotated {
+        let row = [u * u, v * v, u, v];
+        for i in 0..4 {
+            for j in 0..4 {
+                normal[i][j] += row[i] * row[j];
+            }
+            normal[i][4] += row[i];
+        }
+    }
+    let [a, c, d, e] = solve4(normal)?;
+    if a <= 0.0 || c <= 0.0 {
+        return None;
+    }
+
+    // 配方：A(u + D/2A)² + C(v + E/2C)² = 1 + D²/4A + E²/4C
+    let (u0, v0) = (-d / (2.0 * a), -e / (2.0 * c));
+    let rhs = 1.0 + d * d / (4.0 * a) + e * e / (4.0 * c);
+    if rhs <= 0.0 {
+        return None;
+    }
+    let (ru, rv) = ((rhs / a).sqrt(), (rhs / c).sqrt());
+
+    let center_x = mx + u0 * cos - v0 * sin;
+    let center_y = my + u0 * sin + v0 * cos;
+    let (semi_major, semi_minor, rotation) = if ru >= rv {
+        (ru, rv, rotation)
+    } else {
+        (rv, ru, rotation + PI / 2.0)
+    };
+    Some(EllipseFit {
+        center_x,
+        center_y,
+        semi_major,
+        semi_minor,
+        rotation: wrap_half_turn(rotation),
+    })
+}
+
+/// 闭合度（0 ~ 1）：画满一圈为 1；不到一圈时按首尾缺口（相对直径）打折
+///
+/// 超过一圈后笔尖已经越过起点，首尾距离不再反映是否闭合，只看圈数。
+fn closure(revolutions: f64, gap: f64, radius: f64) -> f64 {
+    if revolutions >= 1.0 {
+        return 1.0;
+    }
+    revolutions * (1.0 - gap / (2.0 * radius)).max(0.0)
+}
 
-        Some(GestureResult::Circle {
-            center_x: cx / scale,
-            center_y: cy / scale,
-            radius: avg_r / scale,
+/// 绕中心累加的带符号角度（弧度，正 = 屏幕顺时针）
+fn swept_angle(points: &[(f64, f64)], cx: f64, cy: f64) -> f64 {
+    points
+        .windows(2)
+        .map(|w| {
+            let a0 = (w[0].1 - cy).atan2(w[0].0 - cx);
+            let a1 = (w[1].1 - cy).atan2(w[1].0 - cx);
+            let mut d = a1 - a0;
+            if d > PI {
+                d -= TAU;
+            } else if d < -PI {
+                d += TAU;
+            }
+            d
         })
+        .sum()
+}
+
+/// 把角度归一到 (-π/2, π/2]：椭圆旋转 180° 是同一个椭圆
+fn wrap_half_turn(mut angle: f64) -> f64 {
+    while angle > PI / 2.0 {
+        angle -= PI;
+    }
+    while angle <= -PI / 2.0 {
+        angle += PI;
+    }
+    angle
+}
+
+/// 4 元线性方程组的高斯消元（增广矩阵），奇异时返回 None
+fn solve4(mut m: [[f64; 5]; 4]) -> Option<[f64; 4]> {
+    for col in 0..4 {
+        let pivot = (col..4).max_by(|&a, &b| m[a][col].abs().total_cmp(&m[b][col].abs()))?;
+        if m[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+        m.swap(col, pivot);
+        let pivot_row = m[col];
+        for (row, r) in m.iter_mut().enumerate() {
+            if row != col {
+                let factor = r[col] / pivot_row[col];
+                for (value, p) in r.iter_mut().zip(pivot_row).skip(col) {
+                    *value -= factor * p;
+                }
+            }
+        }
+    }
+    Some([
+        m[0][4] / m[0][0],
+        m[1][4] / m[1][1],
+        m[2][4] / m[2][2],
+        m[3][4] / m[3][3],
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 以 (cx, cy) 为中心、从 0° 开始顺时针画 `degrees` 度，半径由 r0 线性变到 r1
+    fn arc(cx: f64, cy: f64, r0: f64, r1: f64, degrees: f64) -> Trajectory {
+        let n = 72;
+        let points = (0..=n)
+            .map(|i| {
+                let f = i as f64 / n as f64;
+                let angle = (degrees * f).to_radians();
+                let r = r0 + (r1 - r0) * f;
+                TrajectoryPoint::new(cx + r * angle.cos(), cy + r * angle.sin(), f * 600.0)
+            })
+            .collect();
+        Trajectory::new(points, 600.0)
+    }
+
+    fn analyze(stroke: &Trajectory) -> Option<Candidate> {
+        analyze_at(stroke, 1.0)
+    }
+
+    fn analyze_at(stroke: &Trajectory, scale: f64) -> Option<Candidate> {
+        let recognizer = CircleRecognizer::default();
+        recognizer.analyze(
+            &preprocess::apply(stroke, recognizer.stages(), scale),
+            scale,
+        )
+    }
+
+    #[test]
+    fn fit_ellipse_recovers_rotated_ellipse() {
+        let rotation = 30f64.to_radians();
+        let (sin, cos) = rotation.sin_cos();
+        let points: Vec<(f64, f64)> = (0..90)
+            .map(|i| {
+                let t = i as f64 / 90.0 * TAU;
+                let (u, v) = (120.0 * t.cos(), 60.0 * t.sin());
+                (400.0 + u * cos - v * sin, 300.0 + u * sin + v * cos)
+            })
+            .collect();
+        let fit = fit_ellipse(&points).unwrap();
+        assert!((fit.center_x - 400.0).abs() < 1e-6 && (fit.center_y - 300.0).abs() < 1e-6);
+        assert!((fit.semi_major - 120.0).abs() < 1e-6 && (fit.semi_minor - 60.0).abs() < 1e-6);
+        assert!((fit.rotation - rotation).abs() < 1e-6);
+    }
+
+    #[test]
+    fn fit_ellipse_rejects_line() {
+        let points: Vec<(f64, f64)> = (0..20).map(|i| (i as f64 * 10.0, i as f64 * 5.0)).collect();
+        assert!(fit_ellipse(&points).is_none());
+    }
+
+    #[test]
+    fn accepts_full_circle() {
+        let candidate = analyze(&arc(500.0, 500.0, 100.0, 100.0, 365.0)).unwrap();
+        let GestureResult::Circle {
+            closure,
+            direction,
+            radius,
+            ..
+        } = candidate.result
+        else {
+            panic!("{:?}", candidate.result);
+        };
+        assert_eq!(closure, 1.0);
+        assert_eq!(direction, Winding::Clockwise);
+        assert!((radius - 100.0).abs() < 3.0);
+    }
+
+    #[test]
+    fn rejects_open_arc() {
+        assert!(analyze(&arc(500.0, 500.0, 100.0, 100.0, 330.0)).is_none());
+    }
+
+    #[test]
+    fn rejects_spiral_short_of_a_turn() {
+        // 覆盖率 0.97，但终点离起点有半径的 40%
+        let spiral = arc(500.0, 500.0, 100.0, 140.0, 350.0);
+        assert!(analyze(&spiral).is_none());
+        assert!(closure(350.0 / 360.0, 40.0, 120.0) < CircleParams::default().min_closure);
+    }
+
+    #[test]
+    fn min_radius_is_logical() {
+        // 同样 45 逻辑像素的圈：缩放 2.0 下物理半径是 90
+        let small = |scale: f64| {
+            arc(
+                500.0 * scale,
+                500.0 * scale,
+                45.0 * scale,
+                45.0 * scale,
+                365.0,
+            )
+        };
+        for scale in [1.0, 2.0] {
+            let candidate = analyze_at(&small(scale), scale).unwrap();
+            let GestureResult::Circle {
+                center_x, radius, ..
+            } = candidate.result
+            else {
+                panic!("{:?}", candidate.result);
+            };
+            assert!((center_x - 500.0).abs() < 1.0 && (radius - 45.0).abs() < 2.0);
+        }
+        // 物理半径 45 在缩放 2.0 下只有 22.5 逻辑像素，低于默认的 30
+        assert!(analyze_at(&small(1.0), 2.0).is_none());
+    }
+
+    #[test]
+    fn default_params_do_not_check_speed() {
+        // 同一个圈慢慢画了 20 秒（约 0.03 px/ms）
+        let fast = arc(500.0, 500.0, 100.0, 100.0, 365.0);
+        let slow = Trajectory::new(
+            fast.points()
+                .iter()
+                .map(|p| TrajectoryPoint::new(p.x, p.y, p.t * 33.0))
+                .collect(),
+            fast.duration_ms() * 33.0,
+        );
+        assert!(analyze(&slow).is_some());
+        let strict = CircleRecognizer::new(CircleParams {
+            min_avg_speed: 0.15,
+            ..Default::default()
+        });
+        assert!(strict
+            .analyze(&preprocess::apply(&slow, strict.stages(), 1.0), 1.0)
+            .is_none());
     }
 }
@@ -0,0 +1,250 @@
+//! 甩动（flick）手势识别器
+//!
+//! 主要用途：从宠物身上朝屏幕边缘一甩，把宠物派遣到好友的桌面。
+//! 按在宠物上的甩动走的是拖拽流程，因此本识别器同时实现了 `analyze_drag`；
+//! 在空白处的甩动走普通笔画流程。
+//!
+//! 与普通拖拽 / 选中文字的区别（都需要后端时间戳）：
+//!   - 时间短：按下到松手不超过 `max_duration_ms`
+//!   - 够直：弦长 / 轨迹长 不低于 `min_straightness`
+//!   - 松手时还在加速：松手前一小段的速度足够快，且松手前没有停顿
+//!     （普通拖拽和选中文字都会先停下再松手）
+//!
+//! 置信度 = 直线度 × 速度得分，松手速度达到阈值两倍时速度得分为 1。
+
+use super::{check_range, Candidate, GestureRecognizer, GestureResult, DEFAULT_MIN_SCORE};
+use crate::gestures::preprocess::{self, Stage};
+use crate::gestures::trajectory::Trajectory;
+
+/// 计算松手速度的时间窗口（毫秒）
+const RELEASE_WINDOW_MS: f64 = 50.0;
+
+/// 可调参数（register_recognizers 的 params，缺省字段取默认值）
+#[derive(serde::Deserialize, Clone, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct FlickParams {
+    /// 按下到松手的最长时长（毫秒）
+    pub max_duration_ms: f64,
+    /// 最短位移（逻辑像素）
+    pub min_length: f64,
+    /// 弦长 / 轨迹长 的下限
+    pub min_straightness: f64,
+    /// 最低松手速度（逻辑像素 / 秒）
+    pub min_release_speed: f64,
+    /// 最后一次移动到松手之间允许的最长停顿（毫秒）
+    pub max_release_pause_ms: f64,
+    /// 最低置信度
+    pub min_score: f64,
+}
+
+impl Default for FlickParams {
+    fn default() -> Self {
+        Self {
+            max_duration_ms: 400.0,
+            min_length: 80.0,
+            min_straightness: 0.9,
+            min_release_speed: 800.0,
+            max_release_pause_ms: 60.0,
+            min_score: DEFAULT_MIN_SCORE,
+        }
+    }
+}
+
+impl FlickParams {
+    pub fn validate(&self) -> Result<(), String> {
+        check_range("max_duration_ms", self.max_duration_ms, 50.0, 5000.0)?;
+        check_range("min_length", self.min_length, 0.0, 10000.0)?;
+        check_range("min_straightness", self.min_straightness, 0.0, 1.0)?;
+        check_range("min_release_speed", self.min_release_speed, 1.0, 100000.0)?;
+        check_range(
+            "max_release_pause_ms",
+            self.max_release_pause_ms,
+            0.0,
+            5000.0,
+        )?;
+        check_range("min_score", self.min_score, 0.0, 1.0)
+    }
+}
+
+#[derive(Default)]
+pub struct FlickRecognizer {
+    params: FlickParams,
+}
+
+impl FlickRecognizer {
+    pub fn new(params: FlickParams) -> Self {
+        Self { params }
+    }
+}
+
+impl GestureRecognizer for FlickRecognizer {
+    fn name(&self) -> &'static str {
+        "flick"
+    }
+
+    fn min_score(&self) -> f64 {
+        self.params.min_score
+    }
+
+    /// 不做平滑 / 重采样：松手前一小段的速度必须保持原样
+    fn stages(&self) -> &'static [Stage] {
+        &[preprocess::DEJITTER, preprocess::REMOVE_OUTLIERS]
+    }
+
+    fn analyze(&self, stroke: &Trajectory, scale: f64) -> Option<Candidate> {
+        detect_flick(&self.params, stroke, scale, false)
+    }
+
+    fn analyze_drag(&self, path: &Trajectory, scale: f64) -> Option<Candidate> {
+        detect_flick(&self.params, path, scale, true)
+    }
+}
+
+fn detect_flick(
+    params: &FlickParams,
+    stroke: &Trajectory,
+    scale: f64,
+    from_pet: bool,
+) -> Option<Candidate> {
+    if !stroke.has_timing() {
+        eprintln!("[flick] ❌ 后端没有提供时间戳");
+        return None;
+    }
+    let points = stroke.points();
+    if points.len() < 3 {
+        eprintln!("[flick] ❌ 点数不足");
+        return None;
+    }
+    if stroke.duration_ms() > params.max_duration_ms {
+        eprintln!("[flick] ❌ 用时太长 ({:.0}ms)", stroke.duration_ms());
+        return None;
+    }
+
+    let (start, end) = (points[0], points[points.len() - 1]);
+    let chord = start.distance_to(&end);
+    let length = chord / scale;
+    if length < params.min_length {
+        eprintln!("[flick] ❌ 位移太短 ({:.0})", length);
+        return None;
+    }
+    let straightness = chord / stroke.path_length().max(f64::EPSILON);
+    if straightness < params.min_straightness {
+        eprintln!("[flick] ❌ 不够直 ({:.2})", straightness);
+        return None;
+    }
+
+    let pause = stroke.duration_ms() - end.t;
+    if pause > params.max_release_pause_ms {
+        eprintln!("[flick] ❌ 松手前停顿了 {:.0}ms", pause);
+        return None;
+    }
+    let speed = stroke.tail_speed(RELEASE_WINDOW_MS)? * 1000.0 / scale;
+    if speed < params.min_release_speed {
+        eprintln!("[flick] ❌ 松手速度太慢 ({:.0} px/s)", speed);
+        return None;
+    }
+
+    let score = straightness * (0.5 + 0.5 * (speed / (2.0 * params.min_release_speed)).min(1.0));
+    let result = GestureResult::Flick {
+        start_x: start.x / scale,
+        start_y: start.y / scale,
+        end_x: end.x / scale,
+        end_y: end.y / scale,
+        angle: (end.y - start.y).atan2(end.x - start.x).to_degrees(),
+        length,
+        speed,
+        from_pet,
+    };
+    Some(Candidate::new(result, score))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gestures::trajectory::TrajectoryPoint;
+
+    /// 每 10ms 一个采样、沿折线加速前进的一笔；松手发生在最后一个采样之后 `pause_ms`
+    fn stroke(vertices: &[(f64, f64)], pause_ms: f64) -> Trajectory {
+        let samples = 14;
+        let segments = vertices.len() - 1;
+        let points: Vec<TrajectoryPoint> = (0..=samples)
+            .map(|i| {
+                // 行程按时间的平方推进：越来越快
+                let progress = (i as f64 / samples as f64).powi(2) * segments as f64;
+                let segment = (progress.floor() as usize).min(segments - 1);
+                let k = progress - segment as f64;
+                let (a, b) = (vertices[segment], vertices[segment + 1]);
+                TrajectoryPoint::new(
+                    a.0 + (b.0 - a.0) * k,
+                    a.1 + (b.1 - a.1) * k,
+                    i as f64 * 10.0,
+                )
+            })
+            .collect();
+        let duration = points[samples].t + pause_ms;
+        Trajectory::new(points, duration)
+    }
+
+    fn flick(stroke: &Trajectory) -> Option<Candidate> {
+        detect_flick(&FlickParams::default(), stroke, 1.0, false)
+    }
+
+    #[test]
+    fn accepts_fast_straight_flick() {
+        let candidate = flick(&stroke(&[(100.0, 300.0), (400.0, 300.0)], 0.0)).unwrap();
+        let GestureResult::Flick {
+            angle,
+            length,
+            speed,
+            ..
+        } = candidate.result
+        else {
+            panic!("{:?}", candidate.result);
+        };
+        assert_eq!((angle, length), (0.0, 300.0));
+        assert!(speed > FlickParams::default().min_release_speed);
+    }
+
+    #[test]
+    fn pause_before_release_cancels() {
+        let line = [(100.0, 300.0), (400.0, 300.0)];
+        assert!(flick(&stroke(&line, 40.0)).is_some());
+        assert!(flick(&stroke(&line, 100.0)).is_none());
+    }
+
+    #[test]
+    fn rejects_bent_stroke() {
+        // 先向右再向下：弦长 / 轨迹长 ≈ 0.71
+        assert!(flick(&stroke(
+            &[(100.0, 100.0), (250.0, 100.0), (250.0, 250.0)],
+            0.0
+        ))
+        .is_none());
+        // 稍微弯一点仍然算
+        assert!(flick(&stroke(
+            &[(100.0, 100.0), (250.0, 120.0), (400.0, 100.0)],
+            0.0
+        ))
+        .is_some());
+    }
+
+    #[test]
+    fn marks_pet_drag_and_free_stroke() {
+        let recognizer = FlickRecognizer::default();
+        let path = stroke(&[(200.0, 200.0), (800.0, 200.0)], 0.0);
+        let from_pet = |candidate: Option<Candidate>| match candidate.map(|c| c.result) {
+            Some(GestureResult::Flick {
+                from_pet, end_x, ..
+            }) => Some((from_pet, end_x)),
+            _ => None,
+        };
+        assert_eq!(
+            from_pet(recognizer.analyze_drag(&path, 2.0)),
+            Some((true, 400.0))
+        );
+        assert_eq!(
+            from_pet(recognizer.analyze(&path, 2.0)),
+            Some((false, 400.0))
+        );
+    }
+}
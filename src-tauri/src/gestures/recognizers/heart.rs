@@ -0,0 +1,298 @@
+//! 爱心手势识别器
+//!
+//! 情侣场景的"给好友送爱心"手势：用户一笔画出一个闭合的爱心。
+//!
+//! 判定思路（与绘制方向、起笔位置无关）：
+//!   1. 轨迹近似闭合，重采样后按环形处理，起笔点不影响分析
+//!   2. 用有向面积判断绕行方向，之后的曲率都乘以该符号，
+//!      顺时针 / 逆时针画得到同样的正负号
+//!   3. 底部有一个尖锐的凸角（最大正曲率），位于包围盒底部、水平居中
+//!   4. 顶部有一个凹陷的尖点（最小负曲率），位于上半部分、水平居中，
+//!      并且左右两瓣都明显高于它
+//!
+//! 圆形没有凹陷尖点，因此不会被误认成爱心。
+//!
+//! 置信度取闭合程度、底部尖角、顶部凹陷三项得分的平均。
+
+use std::f64::consts::{PI, TAU};
+
+use super::{check_range, Candidate, GestureRecognizer, GestureResult, DEFAULT_MIN_SCORE};
+use crate::gestures::geometry::bounds;
+use crate::gestures::preprocess::{self, resample_points, Stage};
+use crate::gestures::trajectory::Trajectory;
+
+/// 重采样点数
+const SAMPLE_POINTS: usize = 64;
+/// 曲率估计的邻域半径（重采样点数）
+const CURVATURE_SPAN: usize = 3;
+/// 底部凸角的最小转角
+const MIN_TIP_ANGLE: f64 = PI / 3.0;
+/// 顶部凹陷的最小反向转角
+const MIN_DIP_ANGLE: f64 = PI / 5.0;
+
+/// 可调参数（register_recognizers 的 params，缺省字段取默认值）
+#[derive(serde::Deserialize, Clone, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct HeartParams {
+    /// 最少原始轨迹点数
+    pub min_points: usize,
+    /// 最小尺寸（包围盒最长边，逻辑像素）
+    pub min_size: f64,
+    /// 首尾距离 / 尺寸 的上限
+    pub max_closure_gap: f64,
+    /// 围成面积 / 包围盒面积 的下限（排除交叉成 8 字或来回划线）
+    pub min_area_ratio: f64,
+    /// 底部尖 / 顶部凹陷偏离水平中心的上限（相对宽度）
+    pub max_center_offset: f64,
+    /// 最低置信度
+    pub min_score: f64,
+}
+
+impl Default for HeartParams {
+    fn default() -> Self {
+        Self {
+            min_points: 16,
+            min_size: 60.0,
+            max_closure_gap: 0.35,
+            min_area_ratio: 0.3,
+            max_center_offset: 0.25,
+            min_score: DEFAULT_MIN_SCORE,
+        }
+    }
+}
+
+impl HeartParams {
+    pub fn validate(&self) -> Result<(), String> {
+        check_range("min_points", self.min_points as f64, 8.0, 1000.0)?;
+        check_range("min_size", self.min_size, 0.0, 10000.0)?;
+        check_range("max_closure_gap", self.max_closure_gap, 0.0, 1.0)?;
+        check_range("min_area_ratio", self.min_area_ratio, 0.0, 1.0)?;
+        check_range("max_center_offset", self.max_center_offset, 0.0, 0.5)?;
+        check_range("min_score", self.min_score, 0.0, 1.0)
+    }
+}
+
+#[derive(Default)]
+pub struct HeartRecognizer {
+    params: HeartParams,
+}
+
+impl HeartRecognizer {
+    pub fn new(params: HeartParams) -> Self {
+        Self { params }
+    }
+}
+
+impl GestureRecognizer for HeartRecognizer {
+    fn name(&self) -> &'static str {
+        "heart"
+    }
+
+    fn min_score(&self) -> f64 {
+        self.params.min_score
+    }
+
+    fn stages(&self) -> &'static [Stage] {
+        &[preprocess::DEJITTER, preprocess::REMOVE_OUTLIERS]
+    }
+
+    fn analyze(&self, stroke: &Trajectory, scale: f64) -> Option<Candidate> {
+        let params = &self.params;
+        if stroke.len() < params.min_points {
+            eprintln!("[heart] ❌ 点数不足 {}", params.min_points);
+            return None;
+        }
+
+        let raw = stroke.positions();
+        let (min_x, min_y, max_x, max_y) = bounds(raw.iter().copied());
+        let (width, height) = (max_x - min_x, max_y - min_y);
+        let size = width.max(height);
+        if size / scale < params.min_size {
+            eprintln!("[heart] ❌ 尺寸 {:.0} < {}", size / scale, params.min_size);
+            return None;
+        }
+
+        let (first, last) = (raw[0], raw[raw.len() - 1]);
+        let gap = ((first.0 - last.0).powi(2) + (first.1 - last.1).powi(2)).sqrt();
+        if gap / size > params.max_closure_gap {
+            eprintln!("[heart] ❌ 没有闭合 ({:.2})", gap / size);
+            return None;
+        }
+
+        // 末点与首点几乎重合，去掉后按环形处理
+        let mut pts = resample_points(&raw, SAMPLE_POINTS);
+        pts.pop();
+        let n = pts.len();
+
+        // ── 绕行方向：用有向面积判断，顶部凹陷画成回头笔时转角求和会有歧义 ──
+        let area: f64 = (0..n)
+            .map(|i| {
+                let (a, b) = (pts[i], pts[(i + 1) % n]);
+                a.0 * b.1 - b.0 * a.1
+            })
+            .sum::<f64>()
+            / 2.0;
+        if area.abs() < width * height * params.min_area_ratio {
+            eprintln!("[heart] ❌ 围成的面积太小 ({:.0})", area.abs());
+            return None;
+        }
+        let winding = area.signum();
+
+        // ── 曲率：正 = 与绕行方向一致的凸角，负 = 凹陷 ──
+        let curvature: Vec<f64> = (0..n)
+            .map(|i| {
+                let before = pts[(i + n - CURVATURE_SPAN) % n];
+                let after = pts[(i + CURVATURE_SPAN) % n];
+                turn(before, pts[i], after) * winding
+            })
+            .collect();
+        let (tip, tip_angle) = extreme(&curvature, f64::total_cmp);
+        let (dip, dip_angle) = extreme(&curvature, |a, b| b.total_cmp(a));
+        let (tip, dip) = (pts[tip], pts[dip]);
+        let center_x = (min_x + max_x) / 2.0;
+
+        // ── 底部尖角 ──
+        if tip_angle < MIN_TIP_ANGLE {
+            eprintln!("[heart] ❌ 底部不够尖 ({:.0}°)", tip_angle.to_degrees());
+            return None;
+        }
+        if tip.1 < max_y - height * 0.2
+            || (tip.0 - center_x).abs() > width * params.max_center_offset
+        {
+            eprintln!("[heart] ❌ 尖角不在底部中央 ({:.0}, {:.0})", tip.0, tip.1);
+            return None;
+        }
+
+        // ── 顶部凹陷 ──
+        if -dip_angle < MIN_DIP_ANGLE {
+            eprintln!("[heart] ❌ 顶部没有凹陷 ({:.0}°)", dip_angle.to_degrees());
+            return None;
+        }
+        if dip.1 > min_y + height * 0.5
+            || (dip.0 - center_x).abs() > width * params.max_center_offset
+        {
+            eprintln!("[heart] ❌ 凹陷不在顶部中央 ({:.0}, {:.0})", dip.0, dip.1);
+            return None;
+        }
+
+        // ── 左右两瓣都高于凹陷 ──
+        let lobe_top = |left: bool| {
+            pts.iter()
+                .filter(|p| (p.0 < dip.0) == left)
+                .map(|p| p.1)
+                .fold(f64::MAX, f64::min)
+        };
+        let min_rise = height * 0.08;
+        if lobe_top(true) > dip.1 - min_rise || lobe_top(false) > dip.1 - min_rise {
+            eprintln!("[heart] ❌ 两瓣不够饱满");
+            return None;
+        }
+
+        // 尖角 / 凹陷达到阈值的 1.5 倍即满分
+        let score = ((1.0 - gap / size)
+            + (tip_angle / (MIN_TIP_ANGLE * 1.5)).min(1.0)
+            + (-dip_angle / (MIN_DIP_ANGLE * 1.5)).min(1.0))
+            / 3.0;
+        let result = GestureResult::Heart {
+            center_x: center_x / scale,
+            center_y: (min_y + max_y) / 2.0 / scale,
+            size: size / scale,
+        };
+        Some(Candidate::new(result, score))
+    }
+}
+
+/// a → b → c 在 b 处的有向转角（-π ~ π）
+fn turn(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> f64 {
+    let h1 = (b.1 - a.1).atan2(b.0 - a.0);
+    let h2 = (c.1 - b.1).atan2(c.0 - b.0);
+    let mut d = h2 - h1;
+    while d > PI {
+        d -= TAU;
+    }
+    while d < -PI {
+        d += TAU;
+    }
+    d
+}
+
+/// 按比较函数取最大值及其下标
+fn extreme(values: &[f64], cmp: impl Fn(&f64, &f64) -> std::cmp::Ordering) -> (usize, f64) {
+    values
+        .iter()
+        .copied()
+        .enumerate()
+        .max_by(|a, b| cmp(&a.1, &b.1))
+        .unwrap_or((0, 0.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gestures::trajectory::TrajectoryPoint;
+
+    /// 按参数方程画一整圈：t 从 `start` 走 2π，`clockwise` 为屏幕坐标下的顺时针
+    fn closed_curve(start: f64, clockwise: bool, f: impl Fn(f64) -> (f64, f64)) -> Trajectory {
+        let n = 120;
+        let points = (0..=n)
+            .map(|i| {
+                let k = i as f64 / n as f64;
+                let t = if clockwise {
+                    start + k * TAU
+                } else {
+                    start - k * TAU
+                };
+                let (x, y) = f(t);
+                TrajectoryPoint::new(400.0 + x, 400.0 + y, k * 800.0)
+            })
+            .collect();
+        Trajectory::new(points, 800.0)
+    }
+
+    /// 经典爱心曲线（y 向下），t = 0 为顶部凹陷、t = π 为底部尖角；t 增大时在屏幕上顺时针
+    fn heart(t: f64) -> (f64, f64) {
+        let x = 16.0 * t.sin().powi(3);
+        let y = -(13.0 * t.cos() - 5.0 * (2.0 * t).cos() - 2.0 * (3.0 * t).cos() - (4.0 * t).cos());
+        (x * 8.0, y * 8.0)
+    }
+
+    fn analyze(stroke: &Trajectory) -> Option<Candidate> {
+        let recognizer = HeartRecognizer::default();
+        let candidate =
+            recognizer.analyze(&preprocess::apply(stroke, recognizer.stages(), 1.0), 1.0)?;
+        (candidate.score >= recognizer.min_score()).then_some(candidate)
+    }
+
+    #[test]
+    fn accepts_clockwise_and_counter_clockwise_hearts() {
+        for clockwise in [true, false] {
+            let candidate = analyze(&closed_curve(0.0, clockwise, heart))
+                .unwrap_or_else(|| panic!("clockwise = {clockwise}"));
+            let GestureResult::Heart { center_x, size, .. } = candidate.result else {
+                panic!("{:?}", candidate.result);
+            };
+            assert!((center_x - 400.0).abs() < 2.0);
+            assert!((size - 256.0).abs() < 5.0);
+        }
+    }
+
+    #[test]
+    fn accepts_heart_started_away_from_the_cusp() {
+        // 从左瓣外侧、底部尖角附近起笔
+        for start in [-PI / 2.0, 2.5] {
+            assert!(
+                analyze(&closed_curve(start, true, heart)).is_some(),
+                "start = {start}"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_circle_and_teardrop() {
+        let circle = |t: f64| (150.0 * t.sin(), -150.0 * t.cos());
+        assert!(analyze(&closed_curve(0.0, true, circle)).is_none());
+        // 底部有尖角、顶部圆润
+        let teardrop = |t: f64| (120.0 * t.sin() * (t / 2.0).sin(), 150.0 * t.cos());
+        assert!(analyze(&closed_curve(0.0, true, teardrop)).is_none());
+    }
+}
@@ -1,42 +1,272 @@
 //! 手势识别器 trait 与识别结果定义
 //!
 //! 每种宠物可注册自己需要的 recognizer 组合。
-//! global.rs 在鼠标抬起时遍历当前已注册的 recognizer 列表。
+//! global.rs 在鼠标抬起时让每个已注册的 recognizer 给出带置信度的候选，
+//! 再交给 `arbiter` 挑出最佳结果；开启多笔画模式后，单笔结论暂缓，笔画按间隔超时分组后先交给 `MultiStrokeRecognizer`。
 
 pub mod circle;
+pub mod flick;
+pub mod heart;
+pub mod point_cloud;
+pub mod rectangle;
+pub mod template;
+pub mod zigzag;
+
+use super::preprocess::Stage;
+use super::trajectory::{Trajectory, TrajectoryPoint};
+use circle::Winding;
 
 /// 手势识别结果（逻辑像素坐标）
 #[derive(serde::Serialize, Clone, Debug)]
 #[serde(tag = "type")]
 pub enum GestureResult {
-    /// 画圈手势
+    /// 画圈手势（椭圆拟合）
     Circle {
         center_x: f64,
         center_y: f64,
+        /// 等效半径（与拟合椭圆面积相同的圆）
         radius: f64,
+        /// 长半轴
+        radius_x: f64,
+        /// 短半轴
+        radius_y: f64,
+        /// 长轴方向（度）
+        rotation: f64,
+        /// 绕行方向
+        direction: Winding,
+        /// 圈数（螺旋会大于 1）
+        revolutions: f64,
+        /// 短轴 / 长轴（正圆为 1）
+        axis_ratio: f64,
+        /// 闭合度（0 ~ 1，画满一圈为 1，不到一圈时按首尾缺口打折）
+        closure: f64,
+    },
+    /// 用户模板匹配成功
+    Template {
+        /// 模板名
+        name: String,
+        /// 匹配分数（0 ~ 1）
+        score: f64,
+        center_x: f64,
+        center_y: f64,
+        /// 轨迹包围盒最长边
+        size: f64,
+    },
+    /// 多笔画 "X"
+    Cross {
+        center_x: f64,
+        center_y: f64,
+        /// 包围盒最长边
+        size: f64,
+        /// 笔画数
+        strokes: usize,
+        /// 匹配分数（0 ~ 1）
+        score: f64,
+    },
+    /// 多笔画 "+"
+    Plus {
+        center_x: f64,
+        center_y: f64,
+        size: f64,
+        strokes: usize,
+        score: f64,
+    },
+    /// 五角星（一笔或多笔）
+    Star {
+        center_x: f64,
+        center_y: f64,
+        size: f64,
+        strokes: usize,
+        score: f64,
+    },
+    /// 爱心手势
+    Heart {
+        center_x: f64,
+        center_y: f64,
+        /// 包围盒最长边
+        size: f64,
+    },
+    /// 矩形（规整后的包围矩形，可用于框选截图）
+    Rectangle {
+        /// 左上角
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        /// 矩形中心所在显示器的标识（见 get_monitors）
+        monitor: String,
+        /// 相对该显示器左上角的位置（按该显示器的缩放换算的逻辑像素）
+        monitor_x: f64,
+        monitor_y: f64,
+    },
+    /// 之字形 / 来回涂抹
+    Zigzag {
+        start_x: f64,
+        start_y: f64,
+        end_x: f64,
+        end_y: f64,
+        /// 方向反转次数
+        reversals: usize,
+    },
+    /// 直线甩动
+    Flick {
+        start_x: f64,
+        start_y: f64,
+        end_x: f64,
+        end_y: f64,
+        /// 方向角（度，0 = 向右，90 = 向下）
+        angle: f64,
+        /// 起点到终点的距离
+        length: f64,
+        /// 松手速度（逻辑像素 / 秒）
+        speed: f64,
+        /// 是否从宠物身上甩出（拖拽宠物时的甩动）
+        from_pet: bool,
     },
-    // 未来扩展：
-    // Heart { center_x: f64, center_y: f64, size: f64 },
-    // Zigzag { start_x: f64, start_y: f64, end_x: f64, end_y: f64 },
 }
 
 impl GestureResult {
+    /// 手势类型名（与序列化后的 `type` 字段对应的小写形式），用于调试事件
+    pub fn kind(&self) -> &'static str {
+        self.event_name().trim_start_matches("gesture-")
+    }
+
     /// 返回该手势对应的 Tauri 事件名
     pub fn event_name(&self) -> &'static str {
         match self {
             GestureResult::Circle { .. } => "gesture-circle",
-            // GestureResult::Heart { .. } => "gesture-heart",
+            GestureResult::Template { .. } => "gesture-template",
+            GestureResult::Cross { .. } => "gesture-cross",
+            GestureResult::Plus { .. } => "gesture-plus",
+            GestureResult::Star { .. } => "gesture-star",
+            GestureResult::Heart { .. } => "gesture-heart",
+            GestureResult::Rectangle { .. } => "gesture-rectangle",
+            GestureResult::Zigzag { .. } => "gesture-zigzag",
+            GestureResult::Flick { .. } => "gesture-flick",
+        }
+    }
+}
+
+/// 发给前端的手势事件 payload：识别结果字段 + 笔画时长
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct GesturePayload {
+    #[serde(flatten)]
+    pub result: GestureResult,
+    /// 落笔到抬笔的时长（毫秒，多笔画为首笔落笔到末笔抬笔）；后端不提供时间戳时为 0
+    /// 提前识别时为落笔到识别时刻的时长
+    pub duration_ms: f64,
+    /// 是否在抬笔前由增量识别提前确认
+    pub early: bool,
+}
+
+impl GesturePayload {
+    pub fn new(result: GestureResult, duration_ms: f64) -> Self {
+        Self {
+            result,
+            duration_ms,
+            early: false,
+        }
+    }
+
+    /// 增量识别在抬笔前确认的结果
+    pub fn early(result: GestureResult, duration_ms: f64) -> Self {
+        Self {
+            result,
+            duration_ms,
+            early: true,
+        }
+    }
+}
+
+/// 识别器未指定阈值时使用的最低置信度
+pub const DEFAULT_MIN_SCORE: f64 = 0.5;
+
+/// 参数校验：数值必须落在闭区间 [min, max] 内
+pub(crate) fn check_range(field: &str, value: f64, min: f64, max: f64) -> Result<(), String> {
+    if value.is_finite() && (min..=max).contains(&value) {
+        Ok(())
+    } else {
+        Err(format!("{} = {} 超出范围 [{}, {}]", field, value, min, max))
+    }
+}
+
+/// 识别候选：识别结果 + 置信度
+#[derive(Clone, Debug)]
+pub struct Candidate {
+    pub result: GestureResult,
+    /// 置信度（0 ~ 1）
+    pub score: f64,
+}
+
+impl Candidate {
+    pub fn new(result: GestureResult, score: f64) -> Self {
+        Self {
+            result,
+            score: score.clamp(0.0, 1.0),
         }
     }
 }
 
 /// 手势识别器 trait
 ///
-/// 接收一段鼠标轨迹（物理像素），判断是否匹配特定手势。
+/// 接收一段带时间戳的鼠标轨迹（物理像素），判断是否匹配特定手势。
 /// `scale` 为 DPI 缩放因子，用于将结果转换为逻辑像素。
+///
+/// 明显不符合的轨迹直接返回 None；形状沾边的返回带分数的候选，
+/// 是否采纳由仲裁器结合 `min_score` 与其它识别器的候选决定。
 pub trait GestureRecognizer: Send + Sync {
-    /// 分析轨迹点，返回 Some(result) 表示识别成功
-    fn analyze(&self, points: &[(f64, f64)], scale: f64) -> Option<GestureResult>;
+    /// 分析轨迹，返回候选结果
+    fn analyze(&self, stroke: &Trajectory, scale: f64) -> Option<Candidate>;
+
+    /// 分析拖拽宠物时的鼠标轨迹（松手时调用）；默认不参与
+    fn analyze_drag(&self, _path: &Trajectory, _scale: f64) -> Option<Candidate> {
+        None
+    }
+
+    /// 候选被采纳所需的最低置信度
+    fn min_score(&self) -> f64 {
+        DEFAULT_MIN_SCORE
+    }
+
+    /// 识别前需要的预处理步骤；`analyze` / `analyze_drag` 收到的是处理后的轨迹
+    fn stages(&self) -> &'static [Stage] {
+        &[]
+    }
+
+    /// 为新的一笔创建增量识别器；返回 None 表示只在抬笔时识别（默认）
+    fn tracker(&self) -> Option<Box<dyn StrokeTracker>> {
+        None
+    }
+
+    /// 识别器名称，用于日志
+    fn name(&self) -> &'static str;
+}
+
+/// 增量识别器
+///
+/// 绘制过程中逐点接收采样（物理像素），内部状态大小必须有上限；逐点调用，不要在这里打日志。
+/// 返回的候选与其它识别器同一采样点上的候选一起仲裁，唯一胜出时这一笔随即结束识别，
+/// 抬笔时不再交给任何识别器。
+pub trait StrokeTracker: Send {
+    fn push(&mut self, point: TrajectoryPoint, scale: f64) -> Option<Candidate>;
+}
+
+/// 多笔画识别器 trait
+///
+/// 接收一组在笔画间隔超时内连续画出的轨迹（物理像素，按时间顺序）。
+pub trait MultiStrokeRecognizer: Send + Sync {
+    /// 分析一组笔画，返回候选结果
+    fn analyze(&self, strokes: &[Trajectory], scale: f64) -> Option<Candidate>;
+
+    /// 候选被采纳所需的最低置信度
+    fn min_score(&self) -> f64 {
+        DEFAULT_MIN_SCORE
+    }
+
+    /// 每一笔识别前需要的预处理步骤
+    fn stages(&self) -> &'static [Stage] {
+        &[]
+    }
 
     /// 识别器名称，用于日志
     fn name(&self) -> &'static str;
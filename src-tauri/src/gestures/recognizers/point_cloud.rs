@@ -0,0 +1,351 @@
+//! 多笔画点云识别器（$P）
+//!
+//! 把一组笔画看成无序点云，与内置形状的点云做贪心最优匹配，
+//! 因此与笔画数量、顺序、方向都无关："X" 可以先画哪一笔都行，
+//! 五角星可以一笔画完，也可以分成五条线。
+//!
+//! 流程：
+//!   1. 所有笔画按总弧长等间隔重采样为 `CLOUD_POINTS` 个点（不跨笔画插值）
+//!   2. 按包围盒等比缩放、平移到质心
+//!   3. 从若干起点出发做双向贪心匹配，取加权距离最小值
+//!
+//! 置信度随点云距离线性下降，距离达到 `ZERO_SCORE_DISTANCE` 时为 0。
+
+use super::{check_range, Candidate, GestureResult, MultiStrokeRecognizer, DEFAULT_MIN_SCORE};
+use crate::gestures::geometry::{bounds, distance};
+use crate::gestures::preprocess::{self, resample_strokes, unit_box, Stage};
+use crate::gestures::trajectory::Trajectory;
+
+/// 点云点数
+const CLOUD_POINTS: usize = 32;
+/// 置信度降为 0 的点云距离（越小越相似）；默认阈值 0.5 对应距离 1.5
+const ZERO_SCORE_DISTANCE: f64 = 3.0;
+/// 参与识别的最短总轨迹长度（逻辑像素）
+const MIN_TOTAL_LENGTH: f64 = 80.0;
+
+/// 内置的多笔画形状
+#[derive(serde::Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CloudShape {
+    /// "X"：两条交叉的斜线
+    Cross,
+    /// "+"：一横一竖
+    Plus,
+    /// 五角星
+    Star,
+}
+
+impl CloudShape {
+    pub fn name(&self) -> &'static str {
+        match self {
+            CloudShape::Cross => "cross",
+            CloudShape::Plus => "plus",
+            CloudShape::Star => "star",
+        }
+    }
+
+    /// 形状的标准笔画（单位坐标，y 轴向下）
+    fn strokes(&self) -> Vec<Vec<(f64, f64)>> {
+        match self {
+            CloudShape::Cross => vec![vec![(0.0, 0.0), (1.0, 1.0)], vec![(1.0, 0.0), (0.0, 1.0)]],
+            CloudShape::Plus => vec![vec![(0.5, 0.0), (0.5, 1.0)], vec![(0.0, 0.5), (1.0, 0.5)]],
+            CloudShape::Star => {
+                // 五个顶点按隔一个连接
+                let vertex = |k: usize| {
+                    let angle =
+                        -std::f64::consts::FRAC_PI_2 + k as f64 * std::f64::consts::TAU / 5.0;
+                    (0.5 + 0.5 * angle.cos(), 0.5 + 0.5 * angle.sin())
+                };
+                vec![(0..=5).map(|i| vertex(i * 2 % 5)).collect()]
+            }
+        }
+    }
+
+    fn result(
+        &self,
+        center_x: f64,
+        center_y: f64,
+        size: f64,
+        strokes: usize,
+        score: f64,
+    ) -> GestureResult {
+        match self {
+            CloudShape::Cross => GestureResult::Cross {
+                center_x,
+                center_y,
+                size,
+                strokes,
+                score,
+            },
+            CloudShape::Plus => GestureResult::Plus {
+                center_x,
+                center_y,
+                size,
+                strokes,
+                score,
+            },
+            CloudShape::Star => GestureResult::Star {
+                center_x,
+                center_y,
+                size,
+                strokes,
+                score,
+            },
+        }
+    }
+}
+
+/// 可调参数（register_recognizers 的 params，缺省字段取默认值）
+#[derive(serde::Deserialize, Clone, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct PointCloudParams {
+    /// 参与匹配的形状
+    pub shapes: Vec<CloudShape>,
+    /// 最低置信度
+    pub min_score: f64,
+}
+
+impl Default for PointCloudParams {
+    fn default() -> Self {
+        Self {
+            shapes: vec![CloudShape::Cross, CloudShape::Plus, CloudShape::Star],
+            min_score: DEFAULT_MIN_SCORE,
+        }
+    }
+}
+
+impl PointCloudParams {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.shapes.is_empty() {
+            return Err("shapes 不能为空列表".to_string());
+        }
+        check_range("min_score", self.min_score, 0.0, 1.0)
+    }
+}
+
+/// $P 点云识别器，只匹配注册时指定的形状
+pub struct PointCloudRecognizer {
+    templates: Vec<(CloudShape, Vec<(f64, f64)>)>,
+    min_score: f64,
+}
+
+impl PointCloudRecognizer {
+    pub fn new(params: PointCloudParams) -> Self {
+        let templates = params
+            .shapes
+            .iter()
+            .map(|&shape| {
+                (
+                    shape,
+                    unit_box(&resample_strokes(&shape.strokes(), CLOUD_POINTS)),
+                )
+            })
+            .collect();
+        Self {
+            templates,
+            min_score: params.min_score,
+        }
+    }
+}
+
+impl MultiStrokeRecognizer for PointCloudRecognizer {
+    fn name(&self) -> &'static str {
+        "point-cloud"
+    }
+
+    fn min_score(&self) -> f64 {
+        self.min_score
+    }
+
+    fn stages(&self) -> &'static [Stage] {
+        &[preprocess::DEJITTER, preprocess::REMOVE_OUTLIERS]
+    }
+
+    fn analyze(&self, strokes: &[Trajectory], scale: f64) -> Option<Candidate> {
+        let total_length = strokes.iter().map(|s| s.path_length()).sum::<f64>() / scale;
+        if total_length < MIN_TOTAL_LENGTH {
+            eprintln!("[point-cloud] ❌ 轨迹太短 ({:.0})", total_length);
+            return None;
+        }
+
+        let raw: Vec<Vec<(f64, f64)>> = strokes.iter().map(|s| s.positions()).collect();
+        let candidate = unit_box(&resample_strokes(&raw, CLOUD_POINTS));
+
+        let (shape, distance) = self
+            .templates
+            .iter()
+            .map(|(shape, template)| (*shape, greedy_cloud_match(&candidate, template)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))?;
+        let all: Vec<(f64, f64)> = raw.iter().flatten().copied().collect();
+        let (min_x, min_y, max_x, max_y) = bounds(all.iter().copied());
+        let score = (1.0 - distance / ZERO_SCORE_DISTANCE).max(0.0);
+        let result = shape.result(
+            (min_x + max_x) / 2.0 / scale,
+            (min_y + max_y) / 2.0 / scale,
+            (max_x - min_x).max(max_y - min_y) / scale,
+            strokes.len(),
+            score,
+        );
+        Some(Candidate::new(result, score))
+    }
+}
+
+// ── $P 算法 ─────────────────────────────────────────────────────────────────
+
+/// 从 ⌊n^0.5⌋ 间隔的多个起点出发做双向贪心匹配，取最小距离
+fn greedy_cloud_match(points: &[(f64, f64)], template: &[(f64, f64)]) -> f64 {
+    let n = points.len();
+    if n == 0 || n != template.len() {
+        return f64::INFINITY;
+    }
+    let step = ((n as f64).sqrt() as usize).max(1);
+    (0..n)
+        .step_by(step)
+        .map(|start| {
+            cloud_distance(points, template, start).min(cloud_distance(template, points, start))
+        })
+        .fold(f64::INFINITY, f64::min)
+}
+
+/// 从 start 开始依次为每个点找最近的未匹配点，越早匹配的点权重越大
+fn cloud_distance(a: &[(f64, f64)], b: &[(f64, f64)], start: usize) -> f64 {
+    let n = a.len();
+    let mut matched = vec![false; n];
+    let mut sum = 0.0;
+    for offset in 0..n {
+        let i = (start + offset) % n;
+        let (index, d) = b
+            .iter()
+            .enumerate()
+            .filter(|(j, _)| !matched[*j])
+            .map(|(j, &p)| (j, distance(a[i], p)))
+            .min_by(|x, y| x.1.total_cmp(&y.1))
+            .expect("点数一致时总有未匹配的点");
+        matched[index] = true;
+        let weight = 1.0 - offset as f64 / n as f64;
+        sum += weight * d;
+    }
+    sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gestures::trajectory::TrajectoryPoint;
+
+    /// 两点之间的直线笔画（物理像素，每 10ms 一个采样）
+    fn line(from: (f64, f64), to: (f64, f64)) -> Trajectory {
+        polyline(&[from, to])
+    }
+
+    /// 依次连接各顶点的一笔，每段 16 个采样
+    fn polyline(vertices: &[(f64, f64)]) -> Trajectory {
+        let mut points = vec![TrajectoryPoint::new(vertices[0].0, vertices[0].1, 0.0)];
+        for w in vertices.windows(2) {
+            for i in 1..=16 {
+                let k = i as f64 / 16.0;
+                let t = points.len() as f64 * 10.0;
+                points.push(TrajectoryPoint::new(
+                    w[0].0 + (w[1].0 - w[0].0) * k,
+                    w[0].1 + (w[1].1 - w[0].1) * k,
+                    t,
+                ));
+            }
+        }
+        let duration = points.len() as f64 * 10.0;
+        Trajectory::new(points, duration)
+    }
+
+    fn shape_of(strokes: &[Trajectory]) -> Option<&'static str> {
+        let candidate =
+            PointCloudRecognizer::new(PointCloudParams::default()).analyze(strokes, 1.0)?;
+        if candidate.score < DEFAULT_MIN_SCORE {
+            return None;
+        }
+        Some(match candidate.result {
+            GestureResult::Cross { .. } => "cross",
+            GestureResult::Plus { .. } => "plus",
+            GestureResult::Star { .. } => "star",
+            _ => unreachable!(),
+        })
+    }
+
+    /// 五角星顶点（中心 (300, 300)，半径 150，从顶点开始顺时针）
+    fn star_vertex(k: usize) -> (f64, f64) {
+        let angle = -std::f64::consts::FRAC_PI_2 + k as f64 * std::f64::consts::TAU / 5.0;
+        (300.0 + 150.0 * angle.cos(), 300.0 + 150.0 * angle.sin())
+    }
+
+    #[test]
+    fn cross_in_any_order_and_direction() {
+        let a = line((100.0, 100.0), (300.0, 300.0));
+        let b = line((300.0, 100.0), (100.0, 300.0));
+        assert_eq!(shape_of(&[a.clone(), b.clone()]), Some("cross"));
+        let a_back = line((300.0, 300.0), (100.0, 100.0));
+        let b_back = line((100.0, 300.0), (300.0, 100.0));
+        assert_eq!(shape_of(&[b_back, a_back]), Some("cross"));
+        assert_eq!(shape_of(&[b, a]), Some("cross"));
+    }
+
+    #[test]
+    fn plus_in_any_order_and_direction() {
+        let vertical = line((200.0, 100.0), (200.0, 300.0));
+        let horizontal = line((100.0, 200.0), (300.0, 200.0));
+        assert_eq!(
+            shape_of(&[vertical.clone(), horizontal.clone()]),
+            Some("plus")
+        );
+        let horizontal_back = line((300.0, 200.0), (100.0, 200.0));
+        assert_eq!(shape_of(&[horizontal_back, vertical]), Some("plus"));
+    }
+
+    #[test]
+    fn star_in_one_or_five_strokes() {
+        let vertices: Vec<(f64, f64)> = (0..=5).map(|i| star_vertex(i * 2 % 5)).collect();
+        assert_eq!(shape_of(&[polyline(&vertices)]), Some("star"));
+        // 逆序画一笔
+        let reversed: Vec<(f64, f64)> = vertices.iter().rev().copied().collect();
+        assert_eq!(shape_of(&[polyline(&reversed)]), Some("star"));
+        // 五条边打乱顺序、部分反向，分五笔画
+        let edges: Vec<Trajectory> = [3, 0, 4, 1, 2]
+            .iter()
+            .map(|&i| {
+                let (from, to) = (vertices[i], vertices[i + 1]);
+                if i % 2 == 0 {
+                    line(to, from)
+                } else {
+                    line(from, to)
+                }
+            })
+            .collect();
+        assert_eq!(shape_of(&edges), Some("star"));
+    }
+
+    #[test]
+    fn rejects_unrelated_strokes() {
+        // 两条平行横线（"="）
+        let equals = [
+            line((100.0, 150.0), (300.0, 150.0)),
+            line((100.0, 250.0), (300.0, 250.0)),
+        ];
+        assert_eq!(shape_of(&equals), None);
+        // 一个圆
+        let circle: Vec<(f64, f64)> = (0..=36)
+            .map(|i| {
+                let angle = i as f64 / 36.0 * std::f64::consts::TAU;
+                (200.0 + 100.0 * angle.cos(), 200.0 + 100.0 * angle.sin())
+            })
+            .collect();
+        assert_eq!(shape_of(&[polyline(&circle)]), None);
+        // 太短
+        assert!(PointCloudRecognizer::new(PointCloudParams::default())
+            .analyze(
+                &[
+                    line((0.0, 0.0), (20.0, 20.0)),
+                    line((20.0, 0.0), (0.0, 20.0))
+                ],
+                1.0
+            )
+            .is_none());
+    }
+}
@@ -0,0 +1,381 @@
+//! 矩形手势识别器
+//!
+//! "画个框，海鸥叼走一张截图"：用户一笔画出一个近似闭合的矩形，
+//! 识别结果给出规整后的包围矩形（逻辑像素）及其所在显示器，前端据此调用 capture_region。
+//! 识别器本身不读取系统状态；所在显示器由 global.rs 按笔画所在显示器用 `locate` 补上。
+//!
+//! 判定思路（与绘制方向、起笔位置无关）：
+//!   1. 轨迹近似闭合，尺寸足够
+//!   2. Douglas-Peucker 简化成折线后按环形处理，反复去掉转角最小的顶点，
+//!      直到剩下的顶点都是明显的拐角（起笔落在边中间时，起点会在这一步被去掉）
+//!   3. 恰好 4 个拐角，转向一致，每个都接近 90°
+//!   4. 各边接近水平 / 竖直（斜放的菱形不算），围成面积接近包围盒面积
+//!
+//! 置信度取拐角直角程度、闭合程度、填充率三项的平均。
+
+use std::f64::consts::{FRAC_PI_2, PI, TAU};
+
+use super::{check_range, Candidate, GestureRecognizer, GestureResult, DEFAULT_MIN_SCORE};
+use crate::display::MonitorInfo;
+use crate::gestures::geometry::{bounds, distance};
+use crate::gestures::preprocess::{self, resample_points, Stage};
+use crate::gestures::trajectory::Trajectory;
+
+/// 简化折线的容差（相对包围盒最长边）
+const SIMPLIFY_RATIO: f64 = 0.08;
+/// 转角小于该值的顶点不算拐角
+const MIN_CORNER_TURN: f64 = PI / 4.0;
+/// 计算填充率时的重采样点数
+const SAMPLE_POINTS: usize = 64;
+
+/// 可调参数（register_recognizers 的 params，缺省字段取默认值）
+#[derive(serde::Deserialize, Clone, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct RectangleParams {
+    /// 最少原始轨迹点数
+    pub min_points: usize,
+    /// 最短边的下限（逻辑像素）
+    pub min_side: f64,
+    /// 首尾距离 / 包围盒最长边 的上限
+    pub max_closure_gap: f64,
+    /// 拐角偏离 90° 的上限（度）
+    pub corner_tolerance: f64,
+    /// 各边偏离水平 / 竖直的上限（度）
+    pub max_tilt: f64,
+    /// 围成面积 / 包围盒面积 的下限
+    pub min_fill_ratio: f64,
+    /// 最低置信度
+    pub min_score: f64,
+}
+
+impl Default for RectangleParams {
+    fn default() -> Self {
+        Self {
+            min_points: 16,
+            min_side: 40.0,
+            max_closure_gap: 0.25,
+            corner_tolerance: 30.0,
+            max_tilt: 20.0,
+            min_fill_ratio: 0.75,
+            min_score: DEFAULT_MIN_SCORE,
+        }
+    }
+}
+
+impl RectangleParams {
+    pub fn validate(&self) -> Result<(), String> {
+        check_range("min_points", self.min_points as f64, 8.0, 1000.0)?;
+        check_range("min_side", self.min_side, 0.0, 10000.0)?;
+        check_range("max_closure_gap", self.max_closure_gap, 0.0, 1.0)?;
+        check_range("corner_tolerance", self.corner_tolerance, 1.0, 45.0)?;
+        check_range("max_tilt", self.max_tilt, 0.0, 45.0)?;
+        check_range("min_fill_ratio", self.min_fill_ratio, 0.0, 1.0)?;
+        check_range("min_score", self.min_score, 0.0, 1.0)
+    }
+}
+
+#[derive(Default)]
+pub struct RectangleRecognizer {
+    params: RectangleParams,
+}
+
+impl RectangleRecognizer {
+    pub fn new(params: RectangleParams) -> Self {
+        Self { params }
+    }
+}
+
+impl GestureRecognizer for RectangleRecognizer {
+    fn name(&self) -> &'static str {
+        "rectangle"
+    }
+
+    fn min_score(&self) -> f64 {
+        self.params.min_score
+    }
+
+    fn stages(&self) -> &'static [Stage] {
+        &[preprocess::DEJITTER, preprocess::REMOVE_OUTLIERS]
+    }
+
+    fn analyze(&self, stroke: &Trajectory, scale: f64) -> Option<Candidate> {
+        let params = &self.params;
+        if stroke.len() < params.min_points {
+            eprintln!("[rectangle] ❌ 点数不足 {}", params.min_points);
+            return None;
+        }
+
+        let raw = stroke.positions();
+        let (min_x, min_y, max_x, max_y) = bounds(raw.iter().copied());
+        let (width, height) = (max_x - min_x, max_y - min_y);
+        if width.min(height) / scale < params.min_side {
+            eprintln!(
+                "[rectangle] ❌ 最短边 {:.0} < {}",
+                width.min(height) / scale,
+                params.min_side
+            );
+            return None;
+        }
+        let size = width.max(height);
+
+        let (first, last) = (raw[0], raw[raw.len() - 1]);
+        let gap = distance(first, last) / size;
+        if gap > params.max_closure_gap {
+            eprintln!("[rectangle] ❌ 没有闭合 ({:.2})", gap);
+            return None;
+        }
+
+        // ── 拐角：简化后按环形处理，去掉不够尖的顶点 ──
+        let simplified = preprocess::apply(
+            stroke,
+            &[Stage::Simplify {
+                tolerance: size * SIMPLIFY_RATIO,
+            }],
+            1.0,
+        );
+        let mut corners = simplified.positions();
+        let closes = corners.len() > 1
+            && distance(corners[0], corners[corners.len() - 1]) < size * SIMPLIFY_RATIO * 2.0;
+        if closes {
+            // 末点回到起点附近，与首点合并
+            corners.pop();
+        }
+        let mut turns = cyclic_turns(&corners);
+        while corners.len() > 3 {
+            let (index, smallest) = turns
+                .iter()
+                .map(|t| t.abs())
+                .enumerate()
+                .min_by(|a, b| a.1.total_cmp(&b.1))?;
+            if smallest >= MIN_CORNER_TURN {
+                break;
+            }
+            corners.remove(index);
+            turns = cyclic_turns(&corners);
+        }
+        if corners.len() != 4 {
+            eprintln!("[rectangle] ❌ 拐角数 {} ≠ 4", corners.len());
+            return None;
+        }
+        if !(turns.iter().all(|&t| t > 0.0) || turns.iter().all(|&t| t < 0.0)) {
+            eprintln!("[rectangle] ❌ 拐角转向不一致");
+            return None;
+        }
+        let corner_error = turns
+            .iter()
+            .map(|t| (t.abs() - FRAC_PI_2).abs().to_degrees())
+            .fold(0.0, f64::max);
+        if corner_error > params.corner_tolerance {
+            eprintln!("[rectangle] ❌ 拐角不够直 (偏差 {:.0}°)", corner_error);
+            return None;
+        }
+
+        // ── 各边接近水平 / 竖直 ──
+        let tilt = (0..4)
+            .map(|i| {
+                let (a, b) = (corners[i], corners[(i + 1) % 4]);
+                let angle = (b.1 - a.1).atan2(b.0 - a.0).rem_euclid(FRAC_PI_2);
+                angle.min(FRAC_PI_2 - angle).to_degrees()
+            })
+            .fold(0.0, f64::max);
+        if tilt > params.max_tilt {
+            eprintln!("[rectangle] ❌ 边是斜的 ({:.0}°)", tilt);
+            return None;
+        }
+
+        // ── 填充率：围成面积 / 包围盒面积 ──
+        let pts = resample_points(&raw, SAMPLE_POINTS);
+        let n = pts.len();
+        let area = (0..n)
+            .map(|i| {
+                let (a, b) = (pts[i], pts[(i + 1) % n]);
+                a.0 * b.1 - b.0 * a.1
+            })
+            .sum::<f64>()
+            .abs()
+            / 2.0;
+        let fill = area / (width * height);
+        if fill < params.min_fill_ratio {
+            eprintln!(
+                "[rectangle] ❌ 填充率 {:.2} < {}",
+                fill, params.min_fill_ratio
+            );
+            return None;
+        }
+
+        let mean_error = turns
+            .iter()
+            .map(|t| (t.abs() - FRAC_PI_2).abs().to_degrees())
+            .sum::<f64>()
+            / 4.0;
+        let score =
+            ((1.0 - mean_error / params.corner_tolerance) + (1.0 - gap) + fill.min(1.0)) / 3.0;
+
+        let result = GestureResult::Rectangle {
+            x: min_x / scale,
+            y: min_y / scale,
+            width: width / scale,
+            height: height / scale,
+            // 由 locate 补上
+            monitor: String::new(),
+            monitor_x: 0.0,
+            monitor_y: 0.0,
+        };
+        Some(Candidate::new(result, score))
+    }
+}
+
+/// 给矩形结果补上所在显示器及相对该显示器左上角的位置（逻辑像素）；其他结果原样返回
+pub fn locate(result: GestureResult, monitor: &MonitorInfo) -> GestureResult {
+    let GestureResult::Rectangle {
+        x,
+        y,
+        width,
+        height,
+        ..
+    } = result
+    else {
+        return result;
+    };
+    GestureResult::Rectangle {
+        x,
+        y,
+        width,
+        height,
+        monitor: monitor.id.clone(),
+        monitor_x: x - monitor.bounds.x as f64 / monitor.scale,
+        monitor_y: y - monitor.bounds.y as f64 / monitor.scale,
+    }
+}
+
+/// 环形折线每个顶点处的有向转角（-π ~ π）
+fn cyclic_turns(points: &[(f64, f64)]) -> Vec<f64> {
+    let n = points.len();
+    (0..n)
+        .map(|i| {
+            let (a, b, c) = (points[(i + n - 1) % n], points[i], points[(i + 1) % n]);
+            let d = (c.1 - b.1).atan2(c.0 - b.0) - (b.1 - a.1).atan2(b.0 - a.0);
+            (d + PI).rem_euclid(TAU) - PI
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::{MonitorInfo, PhysicalRect};
+    use crate::gestures::trajectory::TrajectoryPoint;
+
+    /// 依次连接各顶点的一笔（物理像素），每段 16 个采样
+    fn polyline(vertices: &[(f64, f64)]) -> Trajectory {
+        let mut points = vec![TrajectoryPoint::new(vertices[0].0, vertices[0].1, 0.0)];
+        for w in vertices.windows(2) {
+            for i in 1..=16 {
+                let k = i as f64 / 16.0;
+                let t = points.len() as f64 * 10.0;
+                points.push(TrajectoryPoint::new(
+                    w[0].0 + (w[1].0 - w[0].0) * k,
+                    w[0].1 + (w[1].1 - w[0].1) * k,
+                    t,
+                ));
+            }
+        }
+        let duration = points.len() as f64 * 10.0;
+        Trajectory::new(points, duration)
+    }
+
+    fn analyze(vertices: &[(f64, f64)]) -> Option<Candidate> {
+        let candidate = RectangleRecognizer::default().analyze(&polyline(vertices), 1.0)?;
+        (candidate.score >= DEFAULT_MIN_SCORE).then_some(candidate)
+    }
+
+    #[test]
+    fn accepts_box_started_mid_edge() {
+        // 从上边中点起笔，逆时针画一圈
+        let vertices = [
+            (300.0, 100.0),
+            (100.0, 100.0),
+            (100.0, 300.0),
+            (500.0, 300.0),
+            (500.0, 100.0),
+            (300.0, 100.0),
+        ];
+        let candidate = analyze(&vertices).unwrap();
+        let GestureResult::Rectangle {
+            x,
+            y,
+            width,
+            height,
+            ..
+        } = candidate.result
+        else {
+            panic!("{:?}", candidate.result);
+        };
+        assert_eq!((x, y, width, height), (100.0, 100.0, 400.0, 200.0));
+    }
+
+    #[test]
+    fn rejects_rhombus_and_circle() {
+        let rhombus = [
+            (300.0, 100.0),
+            (500.0, 300.0),
+            (300.0, 500.0),
+            (100.0, 300.0),
+            (300.0, 100.0),
+        ];
+        assert!(analyze(&rhombus).is_none());
+        let circle: Vec<(f64, f64)> = (0..=36)
+            .map(|i| {
+                let angle = i as f64 / 36.0 * TAU;
+                (300.0 + 150.0 * angle.cos(), 300.0 + 150.0 * angle.sin())
+            })
+            .collect();
+        assert!(analyze(&circle).is_none());
+    }
+
+    #[test]
+    fn rejects_open_three_sided_box() {
+        let open = [
+            (100.0, 100.0),
+            (500.0, 100.0),
+            (500.0, 300.0),
+            (100.0, 300.0),
+        ];
+        assert!(analyze(&open).is_none());
+    }
+
+    #[test]
+    fn locate_uses_the_monitor_scale() {
+        // 150% 的副屏，物理坐标从 (1920, -300) 开始，逻辑坐标从 (1280, -200) 开始
+        let bounds = PhysicalRect::new(1920, -300, 2880, 1620);
+        let monitor = MonitorInfo {
+            id: "right".to_string(),
+            bounds,
+            work_area: bounds,
+            scale: 1.5,
+            primary: false,
+        };
+        let rect = GestureResult::Rectangle {
+            x: 1380.0,
+            y: -100.0,
+            width: 200.0,
+            height: 100.0,
+            monitor: String::new(),
+            monitor_x: 0.0,
+            monitor_y: 0.0,
+        };
+        let GestureResult::Rectangle {
+            monitor,
+            monitor_x,
+            monitor_y,
+            ..
+        } = locate(rect, &monitor)
+        else {
+            unreachable!()
+        };
+        assert_eq!(
+            (monitor.as_str(), monitor_x, monitor_y),
+            ("right", 100.0, 100.0)
+        );
+    }
+}
@@ -0,0 +1,364 @@
+//! 模板手势识别器（$1 / Protractor）
+//!
+//! 不写死阈值，而是把轨迹与用户录制的模板逐一比较：
+//!   1. 重采样为固定点数（等弧长间隔）
+//!   2. 平移到质心、按包围盒等比缩放 → 位置 / 大小无关
+//!   3. 旋转到"指示角"（质心 → 首点方向）→ 方向无关
+//!   4. 展平成单位向量，用 Protractor 的闭式解求最优旋转下的余弦距离
+//!
+//! 模板库由 `gestures::templates` 负责持久化与录制，
+//! 这里只包含纯算法与数据结构，不做任何 I/O。
+
+use std::f64::consts::FRAC_PI_2;
+
+use super::{check_range, Candidate, GestureRecognizer, GestureResult};
+use crate::gestures::geometry::extent;
+use crate::gestures::preprocess::{self, Stage};
+use crate::gestures::templates;
+use crate::gestures::trajectory::Trajectory;
+
+/// 重采样点数
+pub const RESAMPLE_POINTS: usize = 64;
+/// 默认最低匹配分数（0 ~ 1）
+const MIN_SCORE: f64 = 0.85;
+/// 参与匹配 / 录制的最少原始轨迹点数
+const MIN_STROKE_POINTS: usize = 8;
+/// 匹配 / 录制前的预处理（录制与匹配必须一致）
+pub const STAGES: &[Stage] = &[preprocess::DEJITTER, preprocess::REMOVE_OUTLIERS];
+/// 参与匹配 / 录制的最短轨迹长度（逻辑像素）
+const MIN_STROKE_LENGTH: f64 = 60.0;
+
+/// 归一化后的样本点列（质心在原点、包围盒最长边为 1）
+pub type TemplateSample = Vec<(f64, f64)>;
+
+/// 一个命名模板：同一手势的多个样本
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct GestureTemplate {
+    pub name: String,
+    pub samples: Vec<TemplateSample>,
+}
+
+/// 模板库（持久化格式）
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct TemplateLibrary {
+    pub templates: Vec<GestureTemplate>,
+}
+
+/// 模板摘要（list 命令返回给前端）
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct TemplateInfo {
+    pub name: String,
+    pub samples: usize,
+}
+
+/// 匹配结果
+#[derive(Clone, Debug, PartialEq)]
+pub struct TemplateMatch {
+    pub name: String,
+    /// 0 ~ 1，越大越相似
+    pub score: f64,
+}
+
+impl TemplateLibrary {
+    pub fn infos(&self) -> Vec<TemplateInfo> {
+        self.templates
+            .iter()
+            .map(|t| TemplateInfo {
+                name: t.name.clone(),
+                samples: t.samples.len(),
+            })
+            .collect()
+    }
+
+    /// 追加样本；同名模板已存在时合并到该模板
+    pub fn add_samples(&mut self, name: &str, samples: Vec<TemplateSample>) -> TemplateInfo {
+        let index = match self.templates.iter().position(|t| t.name == name) {
+            Some(index) => index,
+            None => {
+                self.templates.push(GestureTemplate {
+                    name: name.to_string(),
+                    samples: Vec::new(),
+                });
+                self.templates.len() - 1
+            }
+        };
+        let template = &mut self.templates[index];
+        template.samples.extend(samples);
+        TemplateInfo {
+            name: template.name.clone(),
+            samples: template.samples.len(),
+        }
+    }
+
+    /// 删除模板，返回是否存在
+    pub fn remove(&mut self, name: &str) -> bool {
+        let before = self.templates.len();
+        self.templates.retain(|t| t.name != name);
+        self.templates.len() != before
+    }
+
+    /// 在指定模板（None 表示全部）中寻找最佳匹配
+    pub fn best_match(
+        &self,
+        sample: &[(f64, f64)],
+        names: Option<&[String]>,
+    ) -> Option<TemplateMatch> {
+        let candidate = vectorize(sample);
+        let mut best: Option<TemplateMatch> = None;
+        for template in &self.templates {
+            if names.is_some_and(|names| !names.contains(&template.name)) {
+                continue;
+            }
+            for stored in &template.samples {
+                let score = similarity(&candidate, &vectorize(stored));
+                if best.as_ref().map_or(true, |b| score > b.score) {
+                    best = Some(TemplateMatch {
+                        name: template.name.clone(),
+                        score,
+                    });
+                }
+            }
+        }
+        best
+    }
+}
+
+// ── 识别器 ──────────────────────────────────────────────────────────────────
+
+/// 可调参数（register_recognizers 的 params，缺省字段取默认值）
+#[derive(serde::Deserialize, Clone, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct TemplateParams {
+    /// 只匹配这些模板（每个宠物可以只认自己的召唤手势）；缺省匹配全部
+    pub templates: Option<Vec<String>>,
+    /// 最低匹配分数
+    pub min_score: f64,
+}
+
+impl Default for TemplateParams {
+    fn default() -> Self {
+        Self {
+            templates: None,
+            min_score: MIN_SCORE,
+        }
+    }
+}
+
+impl TemplateParams {
+    pub fn validate(&self) -> Result<(), String> {
+        if self
+            .templates
+            .as_ref()
+            .is_some_and(|names| names.is_empty())
+        {
+            return Err("templates 不能为空列表".to_string());
+        }
+        check_range("min_score", self.min_score, 0.0, 1.0)
+    }
+}
+
+/// 基于模板库的识别器
+#[derive(Default)]
+pub struct TemplateRecognizer {
+    params: TemplateParams,
+}
+
+impl TemplateRecognizer {
+    pub fn new(params: TemplateParams) -> Self {
+        Self { params }
+    }
+}
+
+impl GestureRecognizer for TemplateRecognizer {
+    fn name(&self) -> &'static str {
+        "template"
+    }
+
+    fn min_score(&self) -> f64 {
+        self.params.min_score
+    }
+
+    fn stages(&self) -> &'static [Stage] {
+        STAGES
+    }
+
+    fn analyze(&self, stroke: &Trajectory, scale: f64) -> Option<Candidate> {
+        let sample = normalize_stroke(stroke, scale)?;
+        let library = templates::library();
+        let Some(found) = library.best_match(&sample, self.params.templates.as_deref()) else {
+            eprintln!("[template] ❌ 没有可用的模板");
+            return None;
+        };
+        let (cx, cy) = stroke.centroid()?;
+        let (w, h) = extent(&stroke.positions());
+        let score = found.score;
+        let result = GestureResult::Template {
+            name: found.name,
+            score,
+            center_x: cx / scale,
+            center_y: cy / scale,
+            size: w.max(h) / scale,
+        };
+        Some(Candidate::new(result, score))
+    }
+}
+
+// ── 归一化 ──────────────────────────────────────────────────────────────────
+
+/// 把一笔轨迹（物理像素）归一化为模板样本；太短的轨迹返回 None
+pub fn normalize_stroke(stroke: &Trajectory, scale: f64) -> Option<TemplateSample> {
+    if stroke.len() < MIN_STROKE_POINTS || stroke.path_length() / scale < MIN_STROKE_LENGTH {
+        return None;
+    }
+    let points = preprocess::resample_points(&stroke.positions(), RESAMPLE_POINTS);
+    Some(preprocess::unit_box(&points))
+}
+
+/// 旋转到指示角并展平为单位向量（Protractor）
+fn vectorize(points: &[(f64, f64)]) -> Vec<f64> {
+    let Some(&(fx, fy)) = points.first() else {
+        return Vec::new();
+    };
+    // 样本已平移到质心，首点方向即指示角
+    let angle = -fy.atan2(fx);
+    let (sin, cos) = angle.sin_cos();
+    let mut vector: Vec<f64> = points
+        .iter()
+        .flat_map(|&(x, y)| [x * cos - y * sin, x * sin + y * cos])
+        .collect();
+    let magnitude = vector.iter().map(|v| v * v).sum::<f64>().sqrt();
+    if magnitude > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= magnitude);
+    }
+    vector
+}
+
+/// Protractor 最优旋转下的相似度，映射到 0 ~ 1
+fn similarity(a: &[f64], b: &[f64]) -> f64 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let (mut dot, mut cross) = (0.0, 0.0);
+    for (pa, pb) in a.chunks_exact(2).zip(b.chunks_exact(2)) {
+        dot += pa[0] * pb[0] + pa[1] * pb[1];
+        cross += pa[0] * pb[1] - pa[1] * pb[0];
+    }
+    let angle = cross.atan2(dot);
+    let cosine = (dot * angle.cos() + cross * angle.sin()).clamp(-1.0, 1.0);
+    (1.0 - cosine.acos() / FRAC_PI_2).max(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gestures::trajectory::TrajectoryPoint;
+    use std::f64::consts::{PI, TAU};
+
+    /// 依次连接各顶点的一笔，每段 16 个采样
+    fn polyline(vertices: &[(f64, f64)]) -> Trajectory {
+        let mut points = vec![TrajectoryPoint::new(vertices[0].0, vertices[0].1, 0.0)];
+        for w in vertices.windows(2) {
+            for i in 1..=16 {
+                let k = i as f64 / 16.0;
+                let t = points.len() as f64 * 10.0;
+                let (x, y) = (
+                    w[0].0 + (w[1].0 - w[0].0) * k,
+                    w[0].1 + (w[1].1 - w[0].1) * k,
+                );
+                points.push(TrajectoryPoint::new(x, y, t));
+            }
+        }
+        let duration = points.len() as f64 * 10.0;
+        Trajectory::new(points, duration)
+    }
+
+    /// 绕原点旋转 `angle`、放大 `factor` 倍后平移到 `offset`
+    fn transform(
+        vertices: &[(f64, f64)],
+        angle: f64,
+        factor: f64,
+        offset: (f64, f64),
+    ) -> Vec<(f64, f64)> {
+        let (sin, cos) = angle.sin_cos();
+        vertices
+            .iter()
+            .map(|&(x, y)| {
+                (
+                    offset.0 + factor * (x * cos - y * sin),
+                    offset.1 + factor * (x * sin + y * cos),
+                )
+            })
+            .collect()
+    }
+
+    const CHECK: [(f64, f64); 3] = [(-60.0, 0.0), (-20.0, 50.0), (80.0, -70.0)];
+    const ZED: [(f64, f64); 4] = [(-60.0, -60.0), (60.0, -60.0), (-60.0, 60.0), (60.0, 60.0)];
+
+    fn sample(vertices: &[(f64, f64)]) -> TemplateSample {
+        normalize_stroke(&polyline(vertices), 1.0).unwrap()
+    }
+
+    fn library() -> TemplateLibrary {
+        let mut library = TemplateLibrary::default();
+        library.add_samples(
+            "check",
+            vec![sample(&transform(&CHECK, 0.0, 1.0, (200.0, 200.0)))],
+        );
+        library.add_samples(
+            "zed",
+            vec![sample(&transform(&ZED, 0.0, 1.0, (200.0, 200.0)))],
+        );
+        library
+    }
+
+    #[test]
+    fn normalize_rejects_short_stroke() {
+        assert!(normalize_stroke(&polyline(&[(0.0, 0.0), (30.0, 0.0)]), 1.0).is_none());
+        // 物理像素够长，但换算到逻辑像素不足
+        assert!(normalize_stroke(&polyline(&[(0.0, 0.0), (100.0, 0.0)]), 2.0).is_none());
+    }
+
+    #[test]
+    fn identical_samples_are_fully_similar() {
+        let vector = vectorize(&sample(&CHECK));
+        assert!((similarity(&vector, &vector) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn matches_rotated_and_scaled_strokes() {
+        let library = library();
+        for (angle, factor) in [(0.0, 3.0), (PI / 6.0, 1.0), (-PI / 4.0, 0.5), (PI, 2.0)] {
+            for (name, shape) in [("check", &CHECK[..]), ("zed", &ZED[..])] {
+                let query = sample(&transform(shape, angle, factor, (900.0, 500.0)));
+                let found = library.best_match(&query, None).unwrap();
+                assert_eq!(found.name, name, "angle = {angle}, factor = {factor}");
+                assert!(found.score > 0.95, "{name}: {}", found.score);
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_unrelated_stroke_below_threshold() {
+        let circle: Vec<(f64, f64)> = (0..=36)
+            .map(|i| {
+                let angle = i as f64 / 36.0 * TAU;
+                (300.0 + 100.0 * angle.cos(), 300.0 + 100.0 * angle.sin())
+            })
+            .collect();
+        let found = library().best_match(&sample(&circle), None).unwrap();
+        assert!(found.score < MIN_SCORE, "{:?}", found);
+    }
+
+    #[test]
+    fn best_match_honours_name_filter() {
+        let query = sample(&CHECK);
+        let only_zed = ["zed".to_string()];
+        assert_eq!(
+            library().best_match(&query, Some(&only_zed)).unwrap().name,
+            "zed"
+        );
+        assert!(TemplateLibrary::default()
+            .best_match(&query, None)
+            .is_none());
+    }
+}
@@ -0,0 +1,294 @@
+//! 之字形 / 来回涂抹手势识别器
+//!
+//! "把宠物赶走" / "擦除" 手势：用户快速来回划动。
+//!
+//! 先对轨迹点做主成分分析，得到主轴与次轴：
+//!   - 来回涂抹（scribble）：沿主轴往返
+//!   - 之字形（W 形前进）：沿主轴前进，沿次轴往返
+//!
+//! 在两条轴上分别统计带滞回的方向反转次数（摆幅必须超过阈值才算一次反转，
+//! 手抖不计数），取反转次数多的那条轴；两条轴都频繁反转的是绕圈，不算。
+//! 再用时间戳检查往返频率，排除慢悠悠的曲线。
+//!
+//! 置信度 = 主导轴得分与次数得分的平均：次轴越安静、反转次数越多越可信。
+
+use super::{check_range, Candidate, GestureRecognizer, GestureResult, DEFAULT_MIN_SCORE};
+use crate::gestures::geometry::principal_axes;
+use crate::gestures::preprocess::{self, Smoothing, Stage};
+use crate::gestures::trajectory::Trajectory;
+
+/// 可调参数（register_recognizers 的 params，缺省字段取默认值）
+#[derive(serde::Deserialize, Clone, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct ZigzagParams {
+    /// 最少原始轨迹点数
+    pub min_points: usize,
+    /// 最少反转次数
+    pub min_reversals: usize,
+    /// 单次摆动的最小幅度（逻辑像素）
+    pub min_amplitude: f64,
+    /// 单次摆动至少达到该轴总跨度的比例
+    pub amplitude_ratio: f64,
+    /// 最低往返频率（次 / 秒），没有时间戳的后端跳过
+    pub min_frequency: f64,
+    /// 最低置信度
+    pub min_score: f64,
+}
+
+impl Default for ZigzagParams {
+    fn default() -> Self {
+        Self {
+            min_points: 10,
+            min_reversals: 3,
+            min_amplitude: 25.0,
+            amplitude_ratio: 0.4,
+            min_frequency: 1.5,
+            min_score: DEFAULT_MIN_SCORE,
+        }
+    }
+}
+
+impl ZigzagParams {
+    pub fn validate(&self) -> Result<(), String> {
+        check_range("min_points", self.min_points as f64, 3.0, 1000.0)?;
+        check_range("min_reversals", self.min_reversals as f64, 1.0, 100.0)?;
+        check_range("min_amplitude", self.min_amplitude, 0.0, 10000.0)?;
+        check_range("amplitude_ratio", self.amplitude_ratio, 0.0, 1.0)?;
+        check_range("min_frequency", self.min_frequency, 0.0, 100.0)?;
+        check_range("min_score", self.min_score, 0.0, 1.0)
+    }
+}
+
+#[derive(Default)]
+pub struct ZigzagRecognizer {
+    params: ZigzagParams,
+}
+
+impl ZigzagRecognizer {
+    pub fn new(params: ZigzagParams) -> Self {
+        Self { params }
+    }
+}
+
+impl GestureRecognizer for ZigzagRecognizer {
+    fn name(&self) -> &'static str {
+        "zigzag"
+    }
+
+    fn min_score(&self) -> f64 {
+        self.params.min_score
+    }
+
+    /// 轻度平滑：触摸板的细碎抖动不会在次轴上累积出假的反转
+    fn stages(&self) -> &'static [Stage] {
+        &[
+            preprocess::DEJITTER,
+            preprocess::REMOVE_OUTLIERS,
+            Stage::Smooth(Smoothing::SavitzkyGolay { half_window: 2 }),
+        ]
+    }
+
+    fn analyze(&self, stroke: &Trajectory, scale: f64) -> Option<Candidate> {
+        let params = &self.params;
+        if stroke.len() < params.min_points {
+            eprintln!("[zigzag] ❌ 点数不足 {}", params.min_points);
+            return None;
+        }
+
+        let points = stroke.positions();
+        let (major, minor) = principal_axes(&points);
+        let (on_major, on_minor) = (
+            count_reversals(&points, major, params, scale),
+            count_reversals(&points, minor, params, scale),
+        );
+        let (reversals, other) = (on_major.max(on_minor), on_major.min(on_minor));
+        if reversals < params.min_reversals {
+            eprintln!(
+                "[zigzag] ❌ 反转次数不足 ({}/{})",
+                reversals, params.min_reversals
+            );
+            return None;
+        }
+        // 两条轴上都频繁反转的是绕圈，不是往返
+        if other * 2 > reversals {
+            eprintln!("[zigzag] ❌ 像是在绕圈 ({} / {})", reversals, other);
+            return None;
+        }
+
+        if stroke.has_timing() {
+            let frequency = reversals as f64 / (stroke.duration_ms() / 1000.0);
+            if frequency < params.min_frequency {
+                eprintln!("[zigzag] ❌ 往返太慢 ({:.1} 次/秒)", frequency);
+                return None;
+            }
+        }
+
+        // 反转次数比最少次数多 2 次即满分
+        let dominance = 1.0 - other as f64 / reversals as f64;
+        let count = (reversals as f64 / (params.min_reversals + 2) as f64).min(1.0);
+        let score = (dominance + count) / 2.0;
+
+        let (start, end) = (points[0], points[points.len() - 1]);
+        let result = GestureResult::Zigzag {
+            start_x: start.0 / scale,
+            start_y: start.1 / scale,
+            end_x: end.0 / scale,
+            end_y: end.1 / scale,
+            reversals,
+        };
+        Some(Candidate::new(result, score))
+    }
+}
+
+/// 沿指定轴统计方向反转次数（带滞回）；`points` 为物理像素，`min_amplitude` 按 `scale` 换算
+fn count_reversals(
+    points: &[(f64, f64)],
+    axis: (f64, f64),
+    params: &ZigzagParams,
+    scale: f64,
+) -> usize {
+    let projected: Vec<f64> = points.iter().map(|p| p.0 * axis.0 + p.1 * axis.1).collect();
+    let (min, max) = projected
+        .iter()
+        .fold((f64::MAX, f64::MIN), |(lo, hi), &v| (lo.min(v), hi.max(v)));
+    let threshold = (params.min_amplitude * scale).max((max - min) * params.amplitude_ratio);
+
+    let mut reversals = 0;
+    // 当前运动方向：1 = 正向，-1 = 反向，0 = 尚未确定
+    let mut direction = 0.0;
+    // 方向确定前：起笔以来的最小 / 最大投影；确定后：当前方向上的极值
+    let (mut low, mut high) = (projected[0], projected[0]);
+    let mut extreme = projected[0];
+    for &v in &projected[1..] {
+        if direction == 0.0 {
+            low = low.min(v);
+            high = high.max(v);
+            if v - low >= threshold {
+                direction = 1.0;
+                extreme = v;
+            } else if high - v >= threshold {
+                direction = -1.0;
+                extreme = v;
+            }
+        } else if (v - extreme) * direction > 0.0 {
+            extreme = v;
+        } else if (extreme - v) * direction >= threshold {
+            reversals += 1;
+            direction = -direction;
+            extreme = v;
+        }
+    }
+    reversals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gestures::trajectory::TrajectoryPoint;
+    use std::f64::consts::TAU;
+
+    /// 依次连接各顶点的一笔，每段 10 个采样，时间戳均匀分布在 `duration_ms` 内
+    fn polyline(vertices: &[(f64, f64)], duration_ms: f64) -> Trajectory {
+        let mut positions = vec![vertices[0]];
+        for w in vertices.windows(2) {
+            for i in 1..=10 {
+                let k = i as f64 / 10.0;
+                positions.push((
+                    w[0].0 + (w[1].0 - w[0].0) * k,
+                    w[0].1 + (w[1].1 - w[0].1) * k,
+                ));
+            }
+        }
+        let step = duration_ms / (positions.len() - 1) as f64;
+        let points = positions
+            .iter()
+            .enumerate()
+            .map(|(i, p)| TrajectoryPoint::new(p.0, p.1, i as f64 * step));
+        Trajectory::new(points.collect(), duration_ms)
+    }
+
+    /// 沿 x 轴在 100 ~ 300 之间来回 `legs` 段
+    fn scribble(legs: usize) -> Vec<(f64, f64)> {
+        (0..=legs)
+            .map(|i| {
+                (
+                    if i % 2 == 0 { 100.0 } else { 300.0 },
+                    200.0 + i as f64 * 2.0,
+                )
+            })
+            .collect()
+    }
+
+    fn reversals_along_x(positions: &[(f64, f64)]) -> usize {
+        count_reversals(positions, (1.0, 0.0), &ZigzagParams::default(), 1.0)
+    }
+
+    fn analyze(stroke: &Trajectory) -> Option<Candidate> {
+        let recognizer = ZigzagRecognizer::default();
+        let candidate =
+            recognizer.analyze(&preprocess::apply(stroke, recognizer.stages(), 1.0), 1.0)?;
+        (candidate.score >= recognizer.min_score()).then_some(candidate)
+    }
+
+    #[test]
+    fn counts_each_swing_once() {
+        assert_eq!(
+            reversals_along_x(&polyline(&scribble(5), 1000.0).positions()),
+            4
+        );
+    }
+
+    #[test]
+    fn jitter_does_not_count_as_reversal() {
+        // 每段上叠加 ±8 像素的抖动，远低于摆幅阈值（跨度的 40%）
+        let jittery: Vec<(f64, f64)> = polyline(&scribble(5), 1000.0)
+            .positions()
+            .into_iter()
+            .enumerate()
+            .map(|(i, (x, y))| (x + if i % 2 == 0 { 8.0 } else { -8.0 }, y))
+            .collect();
+        assert_eq!(reversals_along_x(&jittery), 4);
+        // 整笔都只是小幅晃动：跨度太小，按 25 像素的最小摆幅计
+        let small: Vec<(f64, f64)> = (0..40)
+            .map(|i| (200.0 + (i % 2) as f64 * 15.0, 200.0))
+            .collect();
+        assert_eq!(reversals_along_x(&small), 0);
+    }
+
+    #[test]
+    fn accepts_scribble_and_zigzag() {
+        let candidate = analyze(&polyline(&scribble(6), 1200.0)).unwrap();
+        let GestureResult::Zigzag { reversals, .. } = candidate.result else {
+            panic!("{:?}", candidate.result);
+        };
+        assert_eq!(reversals, 5);
+        // W 形前进：沿 x 前进，沿 y 往返
+        let zigzag: Vec<(f64, f64)> = (0..=6)
+            .map(|i| {
+                (
+                    100.0 + i as f64 * 60.0,
+                    if i % 2 == 0 { 100.0 } else { 250.0 },
+                )
+            })
+            .collect();
+        assert!(analyze(&polyline(&zigzag, 1000.0)).is_some());
+    }
+
+    #[test]
+    fn rejects_circling() {
+        let circle: Vec<(f64, f64)> = (0..=48)
+            .map(|i| {
+                let angle = i as f64 / 16.0 * TAU;
+                (300.0 + 100.0 * angle.cos(), 300.0 + 100.0 * angle.sin())
+            })
+            .collect();
+        assert!(analyze(&polyline(&circle, 1500.0)).is_none());
+    }
+
+    #[test]
+    fn rejects_slow_back_and_forth() {
+        // 5 次反转用了 5 秒，低于每秒 1.5 次
+        assert!(analyze(&polyline(&scribble(6), 5000.0)).is_none());
+        assert!(analyze(&polyline(&scribble(6), 2000.0)).is_some());
+    }
+}
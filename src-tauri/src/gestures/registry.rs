@@ -0,0 +1,203 @@
+//! 识别器注册表
+//!
+//! 识别器名 → 工厂函数。工厂接收前端传来的 JSON 参数对象，
+//! 反序列化为各识别器自己的 `*Params`（缺省字段取默认值）并校验取值范围。
+//! 每个宠物因此可以在 JS 侧调整灵敏度，无需重新编译 Rust。
+//!
+//! ```json
+//! [
+//!   { "name": "circle", "params": { "min_radius": 40, "min_score": 0.6 } },
+//!   { "name": "template", "params": { "templates": ["loop"] } },
+//!   { "name": "point-cloud", "params": { "shapes": ["cross", "star"] } }
+//! ]
+//! ```
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use super::recognizers::circle::{CircleParams, CircleRecognizer};
+use super::recognizers::flick::{FlickParams, FlickRecognizer};
+use super::recognizers::heart::{HeartParams, HeartRecognizer};
+use super::recognizers::point_cloud::{PointCloudParams, PointCloudRecognizer};
+use super::recognizers::rectangle::{RectangleParams, RectangleRecognizer};
+use super::recognizers::template::{TemplateParams, TemplateRecognizer};
+use super::recognizers::zigzag::{ZigzagParams, ZigzagRecognizer};
+use super::recognizers::{GestureRecognizer, MultiStrokeRecognizer};
+
+/// register_recognizers 的单个条目
+#[derive(serde::Deserialize, Clone, Debug)]
+pub struct RecognizerSpec {
+    pub name: String,
+    /// 参数对象；缺省或 null 时全部取默认值
+    #[serde(default)]
+    pub params: Value,
+}
+
+/// 注册失败的原因（发给前端）
+#[derive(serde::Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RegistryError {
+    /// 没有这个名字的识别器
+    UnknownRecognizer {
+        index: usize,
+        name: String,
+        /// 可注册的识别器名称
+        known: Vec<&'static str>,
+    },
+    /// 参数无法解析或超出范围
+    InvalidParams {
+        index: usize,
+        name: String,
+        message: String,
+    },
+}
+
+/// 构建好的识别器组合
+pub struct Registered {
+    pub single: Vec<Box<dyn GestureRecognizer>>,
+    pub multi: Vec<Box<dyn MultiStrokeRecognizer>>,
+}
+
+/// 工厂产出的识别器
+enum Built {
+    Single(Box<dyn GestureRecognizer>),
+    Multi(Box<dyn MultiStrokeRecognizer>),
+}
+
+type Factory = fn(&Value) -> Result<Built, String>;
+
+/// 所有可注册的识别器
+const FACTORIES: &[(&str, Factory)] = &[
+    ("circle", |p| {
+        let params: CircleParams = parse(p)?;
+        params.validate()?;
+        Ok(Built::Single(Box::new(CircleRecognizer::new(params))))
+    }),
+    ("heart", |p| {
+        let params: HeartParams = parse(p)?;
+        params.validate()?;
+        Ok(Built::Single(Box::new(HeartRecognizer::new(params))))
+    }),
+    ("rectangle", |p| {
+        let params: RectangleParams = parse(p)?;
+        params.validate()?;
+        Ok(Built::Single(Box::new(RectangleRecognizer::new(params))))
+    }),
+    ("zigzag", |p| {
+        let params: ZigzagParams = parse(p)?;
+        params.validate()?;
+        Ok(Built::Single(Box::new(ZigzagRecognizer::new(params))))
+    }),
+    ("flick", |p| {
+        let params: FlickParams = parse(p)?;
+        params.validate()?;
+        Ok(Built::Single(Box::new(FlickRecognizer::new(params))))
+    }),
+    ("template", |p| {
+        let params: TemplateParams = parse(p)?;
+        params.validate()?;
+        Ok(Built::Single(Box::new(TemplateRecognizer::new(params))))
+    }),
+    ("point-cloud", |p| {
+        let params: PointCloudParams = parse(p)?;
+        params.validate()?;
+        Ok(Built::Multi(Box::new(PointCloudRecognizer::new(params))))
+    }),
+];
+
+/// 按条目构建识别器；任一条目出错时返回全部错误，不构建任何识别器
+pub fn build(specs: &[RecognizerSpec]) -> Result<Registered, Vec<RegistryError>> {
+    let mut single = Vec::new();
+    let mut multi = Vec::new();
+    let mut errors = Vec::new();
+    for (index, spec) in specs.iter().enumerate() {
+        let Some((_, factory)) = FACTORIES.iter().find(|(name, _)| *name == spec.name) else {
+            errors.push(RegistryError::UnknownRecognizer {
+                index,
+                name: spec.name.clone(),
+                known: FACTORIES.iter().map(|(name, _)| *name).collect(),
+            });
+            continue;
+        };
+        match factory(&spec.params) {
+            Ok(Built::Single(recognizer)) => single.push(recognizer),
+            Ok(Built::Multi(recognizer)) => multi.push(recognizer),
+            Err(message) => errors.push(RegistryError::InvalidParams {
+                index,
+                name: spec.name.clone(),
+                message,
+            }),
+        }
+    }
+    if errors.is_empty() {
+        Ok(Registered { single, multi })
+    } else {
+        Err(errors)
+    }
+}
+
+/// null 视为空对象，其余交给 serde（未知字段报错）
+fn parse<P: DeserializeOwned + Default>(params: &Value) -> Result<P, String> {
+    if params.is_null() {
+        return Ok(P::default());
+    }
+    serde_json::from_value(params.clone()).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn specs(json: Value) -> Vec<RecognizerSpec> {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn builds_with_defaults_and_params() {
+        let registered = build(&specs(serde_json::json!([
+            { "name": "circle" },
+            { "name": "heart", "params": null },
+            { "name": "zigzag", "params": { "min_reversals": 4 } },
+            { "name": "point-cloud", "params": { "shapes": ["cross"] } }
+        ])))
+        .unwrap_or_else(|errors| panic!("{:?}", errors));
+        let names: Vec<_> = registered.single.iter().map(|r| r.name()).collect();
+        assert_eq!(names, ["circle", "heart", "zigzag"]);
+        assert_eq!(registered.multi.len(), 1);
+    }
+
+    #[test]
+    fn rejects_bad_specs_with_their_index() {
+        let Err(errors) = build(&specs(serde_json::json!([
+            { "name": "circle", "params": { "min_radius": 40 } },
+            { "name": "triangle" },
+            { "name": "circle", "params": { "min_raduis": 40 } },
+            { "name": "circle", "params": { "min_score": 1.5 } },
+            { "name": "heart", "params": { "min_size": "big" } },
+            { "name": "zigzag", "params": [1, 2] }
+        ]))) else {
+            panic!("错误的参数应当被拒绝");
+        };
+        let failed: Vec<(usize, &str)> = errors
+            .iter()
+            .map(|e| match e {
+                RegistryError::UnknownRecognizer { index, .. } => (*index, "unknown"),
+                RegistryError::InvalidParams { index, .. } => (*index, "invalid"),
+            })
+            .collect();
+        assert_eq!(
+            failed,
+            [
+                (1, "unknown"),
+                (2, "invalid"),
+                (3, "invalid"),
+                (4, "invalid"),
+                (5, "invalid")
+            ]
+        );
+        let RegistryError::UnknownRecognizer { known, .. } = &errors[0] else {
+            unreachable!()
+        };
+        assert!(known.contains(&"circle"));
+    }
+}
@@ -0,0 +1,881 @@
+//! 手势状态机 —— 与平台无关的拖拽 / 绘制 / 悬停 / 右键逻辑
+//!
+//! `GestureState::handle` 接收一个归一化的 `MouseEvent`，返回 `Reaction`：
+//!   - `swallow`：该事件是否需要吞没（不传递给下层窗口）
+//!   - `outputs`：需要对外发出的事件（由 global.rs 转成 Tauri emit）
+//!
+//! 宠物上按下左键先进入 `Pressing`：移动超过阈值才开始拖拽，
+//! 否则松开为点击 / 双击，按住不动为长按。
+//! 宠物外按下画手势的键（默认左键，可配置为中键）时按 `Activation` 决定是否进入绘制模式
+//! （见 activation.rs），未激活的拖动进入 `Passthrough`，不收集轨迹。
+//! 宠物上按下中键 / 侧键只发出 `PetButton`，按配置决定是否吞没。
+//!
+//! 状态机本身不做任何 I/O，可以直接用脚本化的事件序列驱动。
+//! 所有坐标均为 **物理像素**，转换逻辑像素由调用方负责。
+
+use super::activation::Activation;
+use super::input::{ButtonConfig, Modifiers, MouseButton, MouseEvent, MouseEventKind};
+use super::motion::DragMotion;
+use super::swallow::{self, SwallowContext};
+use super::trajectory::{Trajectory, TrajectoryPoint};
+
+/// 开始拖拽的移动距离（相对命中半径，命中半径已按显示器缩放，约 5 逻辑像素）
+const DRAG_START_RATIO: f64 = 0.08;
+/// 拖拽中算一次摇晃反转的回摆距离（相对命中半径）
+const SHAKE_AMPLITUDE_RATIO: f64 = 0.3;
+/// 按住不动超过该时长算长按（毫秒）
+pub const LONG_PRESS_MS: u32 = 500;
+/// 两次点击间隔不超过该时长算双击（毫秒）
+const DOUBLE_CLICK_MS: u32 = 400;
+
+/// 拖拽轨迹最多保留的采样数（超过后丢弃较早的一半，只有松手前的一段有意义）
+const MAX_DRAG_POINTS: usize = 256;
+
+/// 绘制轨迹最多保留的采样数：在桌面上长时间按住左键（选中文字、拖动文件）时，
+/// 超过后隔一个丢一个，整笔形状保留、分辨率减半，内存有上限
+const MAX_STROKE_POINTS: usize = 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GestureMode {
+    Idle,
+    /// 左键按在宠物上，还没移动到拖拽阈值（松开为点击，按住不动为长按）
+    Pressing,
+    /// 画手势的键已按下，等待长按激活绘制
+    Holding,
+    Drawing,
+    Dragging,
+    /// 本次拖动未激活手势，不收集轨迹，原样交给下层窗口
+    Passthrough,
+}
+
+/// 取消绘制的原因
+#[derive(serde::Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CancelReason {
+    /// 按下 Esc
+    Escape,
+    /// 按住左键绘制时按下右键
+    RightClick,
+    /// 这一笔超过了最长时长
+    Timeout,
+}
+
+/// 状态机产生的对外事件（物理像素）
+#[derive(Clone, Debug, PartialEq)]
+pub enum GestureOutput {
+    /// 点击宠物（按下后没有拖动就松开），携带鼠标位置
+    PetClick {
+        x: f64,
+        y: f64,
+    },
+    /// 双击宠物（第二次点击发出这个而不是 PetClick），携带鼠标位置
+    PetDoubleClick {
+        x: f64,
+        y: f64,
+    },
+    /// 在宠物上按住不动超过 `LONG_PRESS_MS`（之后仍可拖动），携带鼠标位置
+    PetLongPress {
+        x: f64,
+        y: f64,
+    },
+    /// 宠物拖拽开始，携带宠物当前位置
+    DragStart {
+        x: f64,
+        y: f64,
+    },
+    /// 宠物拖拽中，携带宠物新位置
+    DragMove {
+        x: f64,
+        y: f64,
+    },
+    /// 宠物拖拽结束时的鼠标轨迹（带时间戳，在 DragEnd 之前发出，用于甩出判定）
+    DragPath(Trajectory),
+    /// 拖拽中来回摇晃宠物，携带宠物位置与窗口内的方向反转次数
+    PetShaken {
+        x: f64,
+        y: f64,
+        reversals: usize,
+    },
+    /// 宠物拖拽结束，携带宠物最终位置与松手速度（物理像素 / 秒）
+    DragEnd {
+        x: f64,
+        y: f64,
+        velocity_x: f64,
+        velocity_y: f64,
+    },
+    HoverEnter,
+    HoverLeave,
+    /// 在宠物上滚动滚轮，携带鼠标位置、增量（`WHEEL_DELTA` 为一格）与修饰键
+    PetWheel {
+        x: f64,
+        y: f64,
+        delta_x: i32,
+        delta_y: i32,
+        modifiers: Modifiers,
+    },
+    /// 在宠物上按下中键 / 侧键，携带鼠标位置
+    PetButton {
+        button: MouseButton,
+        x: f64,
+        y: f64,
+    },
+    /// 右键点击宠物，携带宠物位置
+    RightClick {
+        x: f64,
+        y: f64,
+    },
+    /// 开始绘制一笔（落笔位置）
+    StrokeBegin {
+        x: f64,
+        y: f64,
+    },
+    /// 绘制中记录的新采样点，供增量识别使用
+    StrokeSample(TrajectoryPoint),
+    /// 一笔绘制完成，交给识别器处理的轨迹（带时间戳）
+    Stroke(Trajectory),
+    /// 绘制中的一笔被取消（轨迹丢弃，松开左键前不再收集）
+    Cancelled {
+        reason: CancelReason,
+        duration_ms: f64,
+    },
+}
+
+/// 单个事件的处理结果
+#[derive(Debug, Default, PartialEq)]
+pub struct Reaction {
+    pub swallow: bool,
+    pub outputs: Vec<GestureOutput>,
+}
+
+pub struct GestureState {
+    mode: GestureMode,
+    /// 绘制 / 拖拽模式：轨迹点
+    points: Vec<TrajectoryPoint>,
+    /// 绘制 / 拖拽模式：按下时刻（后端毫秒时间戳）
+    stroke_start: u32,
+    /// 拖拽模式：鼠标到宠物中心的初始偏移
+    drag_offset_x: f64,
+    drag_offset_y: f64,
+    /// 宠物当前位置（由前端通过 update_pet_position 同步）
+    pet_phys_x: f64,
+    pet_phys_y: f64,
+    /// 宠物命中判定半径
+    pet_hit_radius: f64,
+    /// 前端是否已同步过宠物位置
+    pet_synced: bool,
+    /// 当前鼠标是否悬停在宠物上（用于 hover 进入/离开 检测）
+    is_hovering: bool,
+    /// 已被吞没按下的键（`MouseButton::mask`），用于配套吞没释放（见 swallow.rs）
+    swallowed_buttons: u8,
+    /// 画手势的键与中键 / 侧键的吞没配置
+    buttons: ButtonConfig,
+    /// 绘制 / 长按激活 / 透传模式：按下时用的键（松开同一个键才结束）
+    stroke_button: MouseButton,
+    /// 本次按下的激活方式（工作线程在每次左键按下前按策略设置）
+    activation: Activation,
+    /// 一笔的最长时长（毫秒），超过后取消；None 表示不限
+    stroke_timeout_ms: Option<u32>,
+    /// 长按激活 / 点按宠物：按下位置
+    press_x: f64,
+    press_y: f64,
+    /// 点按宠物：本次按下是否已经触发过长按
+    long_press_fired: bool,
+    /// 上一次点击宠物的时间与位置（用于双击判定）
+    last_click: Option<(u32, f64, f64)>,
+    /// 拖拽模式：松手速度与摇晃检测
+    motion: DragMotion,
+}
+
+impl Default for GestureState {
+    fn default() -> Self {
+        Self {
+            mode: GestureMode::Idle,
+            points: Vec::new(),
+            stroke_start: 0,
+            drag_offset_x: 0.0,
+            drag_offset_y: 0.0,
+            pet_phys_x: -9999.0,
+            pet_phys_y: -9999.0,
+            pet_hit_radius: 75.0,
+            pet_synced: false,
+            is_hovering: false,
+            swallowed_buttons: 0,
+            buttons: ButtonConfig::default(),
+            stroke_button: MouseButton::Left,
+            activation: Activation::Immediate,
+            stroke_timeout_ms: None,
+            press_x: 0.0,
+            press_y: 0.0,
+            long_press_fired: false,
+            last_click: None,
+            motion: DragMotion::default(),
+        }
+    }
+}
+
+impl GestureState {
+    pub fn mode(&self) -> GestureMode {
+        self.mode
+    }
+
+    /// 宠物当前位置；前端尚未同步过时返回 None
+    pub fn pet_position(&self) -> Option<(f64, f64)> {
+        self.pet_synced
+            .then_some((self.pet_phys_x, self.pet_phys_y))
+    }
+
+    /// 绘制中这一笔已记录的轨迹点；不在绘制模式时返回 None
+    pub fn stroke_points(&self) -> Option<&[TrajectoryPoint]> {
+        (self.mode == GestureMode::Drawing).then_some(self.points.as_slice())
+    }
+
+    pub fn pet_hit_radius(&self) -> f64 {
+        self.pet_hit_radius
+    }
+
+    /// 同步宠物位置与命中半径（物理像素）
+    pub fn set_pet(&mut self, x: f64, y: f64, hit_radius: f64) {
+        self.pet_phys_x = x;
+        self.pet_phys_y = y;
+        self.pet_hit_radius = hit_radius;
+        self.pet_synced = true;
+    }
+
+    /// 设置下一次左键按下的激活方式
+    pub fn set_activation(&mut self, activation: Activation) {
+        self.activation = activation;
+    }
+
+    /// 设置画手势的键与中键 / 侧键的吞没配置
+    pub fn set_buttons(&mut self, buttons: ButtonConfig) {
+        self.buttons = buttons;
+    }
+
+    /// 画手势用的键
+    pub fn draw_button(&self) -> MouseButton {
+        self.buttons.draw_button.button()
+    }
+
+    /// 设置一笔的最长时长（毫秒），None 表示不限
+    pub fn set_stroke_timeout(&mut self, timeout_ms: Option<u32>) {
+        self.stroke_timeout_ms = timeout_ms;
+    }
+
+    /// 判断指定坐标是否在宠物命中范围内
+    pub fn is_cursor_over_pet(&self, x: f64, y: f64) -> bool {
+        let dx = x - self.pet_phys_x;
+        let dy = y - self.pet_phys_y;
+        (dx * dx + dy * dy).sqrt() <= self.pet_hit_radius
+    }
+
+    /// 处理一个鼠标事件
+    pub fn handle(&mut self, event: &MouseEvent) -> Reaction {
+        let (x, y) = (event.x, event.y);
+        // 吞没规则与钩子线程共用，按处理该事件之前的状态判定
+        let context = SwallowContext {
+            over_pet: self.is_cursor_over_pet(x, y),
+            drawing: self.mode == GestureMode::Drawing,
+            buttons: self.buttons,
+        };
+        let mut reaction = Reaction {
+            swallow: swallow::decide(&event.kind, context, &mut self.swallowed_buttons),
+            outputs: Vec::new(),
+        };
+
+        match event.kind {
+            // ── 中键正在画手势 / 长按激活 / 透传时按下左键：不打断这一笔，原样交给下层窗口 ──
+            MouseEventKind::Down(MouseButton::Left) if self.mode != GestureMode::Idle => {}
+
+            MouseEventKind::Down(MouseButton::Left) if self.is_cursor_over_pet(x, y) => {
+                // ── 点按宠物：移动超过阈值才开始拖拽 ──
+                self.mode = GestureMode::Pressing;
+                self.drag_offset_x = self.pet_phys_x - x;
+                self.drag_offset_y = self.pet_phys_y - y;
+                self.press_x = x;
+                self.press_y = y;
+                self.long_press_fired = false;
+                self.points.clear();
+                self.points.push(TrajectoryPoint::new(x, y, 0.0));
+                self.motion = DragMotion::start(
+                    TrajectoryPoint::new(x, y, 0.0),
+                    self.pet_hit_radius * SHAKE_AMPLITUDE_RATIO,
+                );
+                self.stroke_start = event.time;
+            }
+
+            MouseEventKind::Down(MouseButton::Right) => {
+                if self.mode == GestureMode::Drawing {
+                    // ── 绘制中按下右键 → 取消这一笔（能吞没的后端上不会弹出下层右键菜单）──
+                    let output = self.cancel_stroke(CancelReason::RightClick, event.time);
+                    reaction.outputs.push(output);
+                } else if self.is_cursor_over_pet(x, y) {
+                    // ── 右键点击宠物 → 通知前端 toggle 面板（右键被吞没，不会穿透）──
+                    reaction.outputs.push(GestureOutput::RightClick {
+                        x: self.pet_phys_x,
+                        y: self.pet_phys_y,
+                    });
+                }
+            }
+
+            MouseEventKind::Down(button) if self.is_cursor_over_pet(x, y) => {
+                // ── 宠物上按下中键 / 侧键 → 通知前端（是否吞没由配置决定）──
+                reaction
+                    .outputs
+                    .push(GestureOutput::PetButton { button, x, y });
+            }
+
+            MouseEventKind::Down(button)
+                if button == self.draw_button() && self.mode == GestureMode::Idle =>
+            {
+                // ── 绘制模式（按激活策略）──
+                self.stroke_button = button;
+                self.stroke_start = event.time;
+                match self.activation {
+                    Activation::Immediate => self.begin_stroke(event, &mut reaction.outputs),
+                    Activation::Modifier(key) if event.modifiers.contains(key) => {
+                        self.begin_stroke(event, &mut reaction.outputs)
+                    }
+                    Activation::Dwell { .. } => {
+                        self.mode = GestureMode::Holding;
+                        self.press_x = x;
+                        self.press_y = y;
+                    }
+                    Activation::Modifier(_) | Activation::Disabled => {
+                        self.mode = GestureMode::Passthrough;
+                    }
+                }
+            }
+
+            MouseEventKind::Down(_) => {}
+
+            MouseEventKind::Wheel { delta_x, delta_y } => {
+                // 绘制中的滚轮不归宠物，照常交给下层窗口
+                if self.mode != GestureMode::Drawing && self.is_cursor_over_pet(x, y) {
+                    // ── 宠物上滚动滚轮 → 通知前端（滚轮被吞没，下层窗口不会跟着滚动）──
+                    reaction.outputs.push(GestureOutput::PetWheel {
+                        x,
+                        y,
+                        delta_x,
+                        delta_y,
+                        modifiers: event.modifiers,
+                    });
+                }
+            }
+
+            MouseEventKind::Escape => {
+                if self.mode == GestureMode::Drawing {
+                    let output = self.cancel_stroke(CancelReason::Escape, event.time);
+                    reaction.outputs.push(output);
+                }
+            }
+
+            MouseEventKind::Move => match self.mode {
+                GestureMode::Pressing => {
+                    let threshold = self.pet_hit_radius * DRAG_START_RATIO;
+                    if (x - self.press_x).hypot(y - self.press_y) > threshold {
+                        self.mode = GestureMode::Dragging;
+                        eprintln!("[gesture] 🖱️ 拖拽开始 ({}, {})", x as i32, y as i32);
+                        reaction.outputs.push(GestureOutput::DragStart {
+                            x: self.pet_phys_x,
+                            y: self.pet_phys_y,
+                        });
+                        self.drag_to(event, &mut reaction.outputs);
+                    } else if let Some(output) = self.poll_long_press(event.time) {
+                        reaction.outputs.push(output);
+                    }
+                }
+                GestureMode::Dragging => self.drag_to(event, &mut reaction.outputs),
+                GestureMode::Holding => {
+                    // 只有长按激活会进入 Holding
+                    let (hold_ms, tolerance) = match self.activation {
+                        Activation::Dwell { hold_ms, tolerance } => (hold_ms, tolerance),
+                        _ => (0, 0.0),
+                    };
+                    if self.stroke_elapsed(event) >= hold_ms as f64 {
+                        // 按住足够久：从当前位置开始绘制，轨迹时间从激活时刻算起
+                        self.begin_stroke(event, &mut reaction.outputs);
+                        self.record_stroke_point(event, &mut reaction.outputs);
+                    } else if (x - self.press_x).hypot(y - self.press_y) > tolerance {
+                        // 没按够就移动：普通拖动（选中文字、框选文件）
+                        self.mode = GestureMode::Passthrough;
+                    }
+                }
+                GestureMode::Drawing if self.is_timed_out(event) => {
+                    let output = self.cancel_stroke(CancelReason::Timeout, event.time);
+                    reaction.outputs.push(output);
+                }
+                GestureMode::Drawing => self.record_stroke_point(event, &mut reaction.outputs),
+                GestureMode::Passthrough => {}
+                GestureMode::Idle => {
+                    // ── 悬停检测：鼠标是否在宠物命中范围内 ──
+                    self.update_hover_state(x, y, &mut reaction.outputs);
+                }
+            },
+
+            // ── 释放：被吞没的释放也照常处理（画手势用的中键松开时要结束这一笔）──
+            MouseEventKind::Up(button) => match self.mode {
+                GestureMode::Pressing if button == MouseButton::Left => {
+                    // 工作线程还没来得及按截止时刻触发的长按，松开时补上（需在离开 Pressing 之前判断）
+                    if let Some(output) = self.poll_long_press(event.time) {
+                        reaction.outputs.push(output);
+                    }
+                    self.mode = GestureMode::Idle;
+                    self.points.clear();
+                    // 长按后松开不再算点击
+                    if !self.long_press_fired {
+                        reaction.outputs.push(self.click(event));
+                    }
+                }
+                GestureMode::Dragging if button == MouseButton::Left => {
+                    self.mode = GestureMode::Idle;
+                    self.is_hovering = false;
+                    self.update_hover_state(x, y, &mut reaction.outputs);
+                    let points = std::mem::take(&mut self.points);
+                    let duration_ms = self.stroke_elapsed(event);
+                    let (velocity_x, velocity_y) = self.motion.release_velocity(duration_ms);
+                    reaction
+                        .outputs
+                        .push(GestureOutput::DragPath(Trajectory::new(
+                            points,
+                            duration_ms,
+                        )));
+                    reaction.outputs.push(GestureOutput::DragEnd {
+                        x: self.pet_phys_x,
+                        y: self.pet_phys_y,
+                        velocity_x,
+                        velocity_y,
+                    });
+                }
+                // 绘制相关模式只认按下时用的那个键
+                _ if button != self.stroke_button => {}
+                GestureMode::Drawing if self.is_timed_out(event) => {
+                    let output = self.cancel_stroke(CancelReason::Timeout, event.time);
+                    reaction.outputs.push(output);
+                    self.mode = GestureMode::Idle;
+                }
+                GestureMode::Drawing => {
+                    self.mode = GestureMode::Idle;
+                    let points = std::mem::take(&mut self.points);
+                    let duration_ms = self.stroke_elapsed(event);
+                    reaction
+                        .outputs
+                        .push(GestureOutput::Stroke(Trajectory::new(points, duration_ms)));
+                }
+                GestureMode::Holding | GestureMode::Passthrough => self.mode = GestureMode::Idle,
+                GestureMode::Pressing | GestureMode::Dragging | GestureMode::Idle => {}
+            },
+        }
+
+        reaction
+    }
+
+    /// 长按判定的截止时刻（后端时间戳）；不在点按宠物或已触发过时返回 None
+    pub fn long_press_deadline(&self) -> Option<u32> {
+        (self.mode == GestureMode::Pressing && !self.long_press_fired)
+            .then(|| self.stroke_start.wrapping_add(LONG_PRESS_MS))
+    }
+
+    /// 按住宠物不动到时长后触发一次长按；`now` 为后端时间戳
+    ///
+    /// 鼠标完全不动时不会有事件，工作线程按 `long_press_deadline` 定时调用。
+    pub fn poll_long_press(&mut self, now: u32) -> Option<GestureOutput> {
+        let deadline = self.long_press_deadline()?;
+        // 回绕安全的 now >= deadline
+        if (now.wrapping_sub(deadline) as i32) < 0 {
+            return None;
+        }
+        self.long_press_fired = true;
+        eprintln!("[gesture] 🖱️ 长按宠物");
+        Some(GestureOutput::PetLongPress {
+            x: self.press_x,
+            y: self.press_y,
+        })
+    }
+
+    /// 这一笔的超时时刻（后端时间戳）；不在绘制或不限时长时返回 None
+    pub fn stroke_deadline(&self) -> Option<u32> {
+        let timeout = self.stroke_timeout_ms?;
+        (self.mode == GestureMode::Drawing).then(|| self.stroke_start.wrapping_add(timeout))
+    }
+
+    /// 这一笔超过最长时长时取消；`now` 为后端时间戳
+    ///
+    /// 按住不动时没有事件，工作线程按 `stroke_deadline` 定时调用。
+    pub fn poll_stroke_timeout(&mut self, now: u32) -> Option<GestureOutput> {
+        let deadline = self.stroke_deadline()?;
+        // 回绕安全的 now >= deadline
+        if (now.wrapping_sub(deadline) as i32) < 0 {
+            return None;
+        }
+        Some(self.cancel_stroke(CancelReason::Timeout, now))
+    }
+
+    /// 松开点按：与上一次点击足够近（时间 / 位置）时算双击
+    fn click(&mut self, event: &MouseEvent) -> GestureOutput {
+        let (x, y) = (event.x, event.y);
+        let near = self.pet_hit_radius * DRAG_START_RATIO * 2.0;
+        let double = self.last_click.is_some_and(|(time, last_x, last_y)| {
+            event.time.wrapping_sub(time) <= DOUBLE_CLICK_MS
+                && (x - last_x).hypot(y - last_y) <= near
+        });
+        if double {
+            // 第三次点击重新开始计数
+            self.last_click = None;
+            GestureOutput::PetDoubleClick { x, y }
+        } else {
+            self.last_click = Some((event.time, x, y));
+            GestureOutput::PetClick { x, y }
+        }
+    }
+
+    /// 拖拽中：宠物跟随鼠标，记录甩出判定用的轨迹，检测摇晃
+    fn drag_to(&mut self, event: &MouseEvent, outputs: &mut Vec<GestureOutput>) {
+        self.pet_phys_x = event.x + self.drag_offset_x;
+        self.pet_phys_y = event.y + self.drag_offset_y;
+        // 拖拽轨迹不做间距过滤：甩出判定需要松手前的每个采样
+        if self.points.len() >= MAX_DRAG_POINTS {
+            self.points.drain(..MAX_DRAG_POINTS / 2);
+        }
+        let point = TrajectoryPoint::new(event.x, event.y, self.stroke_elapsed(event));
+        self.points.push(point);
+        outputs.push(GestureOutput::DragMove {
+            x: self.pet_phys_x,
+            y: self.pet_phys_y,
+        });
+        if let Some(reversals) = self.motion.push(point) {
+            outputs.push(GestureOutput::PetShaken {
+                x: self.pet_phys_x,
+                y: self.pet_phys_y,
+                reversals,
+            });
+        }
+    }
+
+    /// 进入绘制模式
+    fn begin_stroke(&mut self, event: &MouseEvent, outputs: &mut Vec<GestureOutput>) {
+        self.mode = GestureMode::Drawing;
+        self.points.clear();
+        self.stroke_start = event.time;
+        eprintln!(
+            "[gesture] 🖱️ 绘制开始 ({}, {})",
+            event.x as i32, event.y as i32
+        );
+        outputs.push(GestureOutput::StrokeBegin {
+            x: event.x,
+            y: event.y,
+        });
+    }
+
+    /// 记录一个绘制采样点
+    ///
+    /// 原样记录（只跳过原地重复的事件），去抖等预处理由识别器按需声明
+    fn record_stroke_point(&mut self, event: &MouseEvent, outputs: &mut Vec<GestureOutput>) {
+        let (x, y) = (event.x, event.y);
+        if self
+            .points
+            .last()
+            .is_some_and(|last| (last.x, last.y) == (x, y))
+        {
+            return;
+        }
+        if self.points.len() >= MAX_STROKE_POINTS {
+            let mut index = 0;
+            self.points.retain(|_| {
+                index += 1;
+                index % 2 == 1
+            });
+        }
+        let point = TrajectoryPoint::new(x, y, self.stroke_elapsed(event));
+        self.points.push(point);
+        outputs.push(GestureOutput::StrokeSample(point));
+    }
+
+    /// 取消绘制中的一笔：丢弃轨迹，松开左键前不再收集；`time` 为取消时的后端时间戳
+    fn cancel_stroke(&mut self, reason: CancelReason, time: u32) -> GestureOutput {
+        self.mode = GestureMode::Passthrough;
+        self.points.clear();
+        let duration_ms = time.wrapping_sub(self.stroke_start) as f64;
+        eprintln!(
+            "[gesture] ✖️ 取消绘制（{:?}，{:.0}ms）",
+            reason, duration_ms
+        );
+        GestureOutput::Cancelled {
+            reason,
+            duration_ms,
+        }
+    }
+
+    /// 这一笔是否已超过最长时长（按事件时间戳判断；
+    /// 按住不动时由工作线程定时调用 `poll_stroke_timeout`）
+    fn is_timed_out(&self, event: &MouseEvent) -> bool {
+        self.stroke_timeout_ms
+            .is_some_and(|timeout| self.stroke_elapsed(event) >= timeout as f64)
+    }
+
+    /// 距落笔时刻的毫秒数
+    fn stroke_elapsed(&self, event: &MouseEvent) -> f64 {
+        event.time.wrapping_sub(self.stroke_start) as f64
+    }
+
+    /// 根据当前鼠标位置更新悬停状态，状态变化时产生 hover 事件
+    fn update_hover_state(&mut self, x: f64, y: f64, outputs: &mut Vec<GestureOutput>) {
+        let over = self.is_cursor_over_pet(x, y);
+        if over && !self.is_hovering {
+            self.is_hovering = true;
+            outputs.push(GestureOutput::HoverEnter);
+        } else if !over && self.is_hovering {
+            self.is_hovering = false;
+            outputs.push(GestureOutput::HoverLeave);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gestures::input::{DrawButton, WHEEL_DELTA};
+
+    const PET: (f64, f64, f64) = (100.0, 100.0, 65.0);
+
+    fn state() -> GestureState {
+        let mut state = GestureState::default();
+        state.set_pet(PET.0, PET.1, PET.2);
+        state
+    }
+
+    fn event(kind: MouseEventKind, x: f64, y: f64, time: u32) -> MouseEvent {
+        MouseEvent::new(kind, x, y).at(time)
+    }
+
+    /// 在 (x, y) 按下并松开左键
+    fn tap(state: &mut GestureState, x: f64, y: f64, down: u32, up: u32) -> Vec<GestureOutput> {
+        state.handle(&event(MouseEventKind::Down(MouseButton::Left), x, y, down));
+        state
+            .handle(&event(MouseEventKind::Up(MouseButton::Left), x, y, up))
+            .outputs
+    }
+
+    #[test]
+    fn click_then_double_click() {
+        let mut state = state();
+        let click = GestureOutput::PetClick { x: 100.0, y: 100.0 };
+        assert_eq!(tap(&mut state, 100.0, 100.0, 0, 80), [click]);
+        assert_eq!(
+            tap(&mut state, 102.0, 101.0, 200, 260),
+            [GestureOutput::PetDoubleClick { x: 102.0, y: 101.0 }]
+        );
+        // 第三次点击重新计数
+        let click = GestureOutput::PetClick { x: 102.0, y: 101.0 };
+        assert_eq!(tap(&mut state, 102.0, 101.0, 400, 450), [click]);
+    }
+
+    #[test]
+    fn slow_or_distant_second_click_is_single() {
+        let mut state = state();
+        tap(&mut state, 100.0, 100.0, 0, 50);
+        let late = DOUBLE_CLICK_MS + 100;
+        let slow = tap(&mut state, 100.0, 100.0, late, late + 50);
+        assert_eq!(slow, [GestureOutput::PetClick { x: 100.0, y: 100.0 }]);
+        // 间隔够短但离上一次点击太远
+        let far = tap(&mut state, 140.0, 100.0, late + 150, late + 200);
+        assert_eq!(far, [GestureOutput::PetClick { x: 140.0, y: 100.0 }]);
+    }
+
+    #[test]
+    fn long_press_fires_once_and_suppresses_click() {
+        let mut state = state();
+        state.handle(&event(
+            MouseEventKind::Down(MouseButton::Left),
+            100.0,
+            100.0,
+            1_000,
+        ));
+        assert_eq!(state.long_press_deadline(), Some(1_000 + LONG_PRESS_MS));
+        assert_eq!(state.poll_long_press(1_000 + LONG_PRESS_MS - 1), None);
+        assert_eq!(
+            state.poll_long_press(1_000 + LONG_PRESS_MS),
+            Some(GestureOutput::PetLongPress { x: 100.0, y: 100.0 })
+        );
+        assert_eq!(state.long_press_deadline(), None);
+        assert_eq!(state.poll_long_press(2_000), None);
+
+        let up = event(MouseEventKind::Up(MouseButton::Left), 100.0, 100.0, 2_000);
+        assert!(state.handle(&up).outputs.is_empty());
+        assert_eq!(state.mode(), GestureMode::Idle);
+    }
+
+    #[test]
+    fn late_release_reports_long_press_instead_of_click() {
+        // 工作线程还没来得及触发长按，松开时补发
+        let mut state = state();
+        let outputs = tap(&mut state, 100.0, 100.0, 0, LONG_PRESS_MS + 20);
+        assert_eq!(
+            outputs,
+            [GestureOutput::PetLongPress { x: 100.0, y: 100.0 }]
+        );
+    }
+
+    fn wheel(x: f64, y: f64, time: u32) -> MouseEvent {
+        event(
+            MouseEventKind::Wheel {
+                delta_x: 0,
+                delta_y: -WHEEL_DELTA,
+            },
+            x,
+            y,
+            time,
+        )
+    }
+
+    #[test]
+    fn wheel_over_pet_is_reported_and_swallowed() {
+        let mut state = state();
+        let mut scroll = wheel(110.0, 90.0, 0);
+        scroll.modifiers.ctrl = true;
+        let reaction = state.handle(&scroll);
+        assert!(reaction.swallow);
+        assert_eq!(
+            reaction.outputs,
+            [GestureOutput::PetWheel {
+                x: 110.0,
+                y: 90.0,
+                delta_x: 0,
+                delta_y: -WHEEL_DELTA,
+                modifiers: Modifiers {
+                    ctrl: true,
+                    ..Default::default()
+                },
+            }]
+        );
+        assert_eq!(state.mode(), GestureMode::Idle);
+    }
+
+    #[test]
+    fn wheel_off_pet_or_while_drawing_passes_through() {
+        let mut state = state();
+        let off_pet = state.handle(&wheel(400.0, 400.0, 0));
+        assert!(!off_pet.swallow && off_pet.outputs.is_empty());
+
+        // 宠物外起笔，划过宠物时滚动
+        state.handle(&event(
+            MouseEventKind::Down(MouseButton::Left),
+            400.0,
+            400.0,
+            100,
+        ));
+        state.handle(&event(MouseEventKind::Move, 120.0, 100.0, 120));
+        let drawing = state.handle(&wheel(120.0, 100.0, 140));
+        assert!(!drawing.swallow && drawing.outputs.is_empty());
+        assert_eq!(state.mode(), GestureMode::Drawing);
+    }
+
+    fn middle_drawing() -> GestureState {
+        let mut state = state();
+        state.set_buttons(ButtonConfig {
+            draw_button: DrawButton::Middle,
+            ..Default::default()
+        });
+        state
+    }
+
+    #[test]
+    fn middle_button_stroke_is_swallowed() {
+        let mut state = middle_drawing();
+        let down = state.handle(&event(
+            MouseEventKind::Down(MouseButton::Middle),
+            500.0,
+            500.0,
+            0,
+        ));
+        assert!(down.swallow);
+        assert_eq!(
+            down.outputs,
+            [GestureOutput::StrokeBegin { x: 500.0, y: 500.0 }]
+        );
+        state.handle(&event(MouseEventKind::Move, 540.0, 500.0, 20));
+        let up = state.handle(&event(
+            MouseEventKind::Up(MouseButton::Middle),
+            540.0,
+            500.0,
+            40,
+        ));
+        assert!(up.swallow);
+        assert!(matches!(up.outputs[..], [GestureOutput::Stroke(ref stroke)] if stroke.len() == 1));
+        assert_eq!(state.mode(), GestureMode::Idle);
+
+        // 宠物外的左键拖动不再画手势，也不吞没
+        let left = state.handle(&event(
+            MouseEventKind::Down(MouseButton::Left),
+            500.0,
+            500.0,
+            100,
+        ));
+        assert!(!left.swallow && left.outputs.is_empty());
+        assert_eq!(state.mode(), GestureMode::Idle);
+    }
+
+    #[test]
+    fn left_press_on_pet_does_not_interrupt_middle_stroke() {
+        let mut state = middle_drawing();
+        state.handle(&event(
+            MouseEventKind::Down(MouseButton::Middle),
+            500.0,
+            500.0,
+            0,
+        ));
+        state.handle(&event(MouseEventKind::Move, 300.0, 300.0, 20));
+        let left = state.handle(&event(
+            MouseEventKind::Down(MouseButton::Left),
+            100.0,
+            100.0,
+            40,
+        ));
+        assert!(left.outputs.is_empty());
+        state.handle(&event(
+            MouseEventKind::Up(MouseButton::Left),
+            100.0,
+            100.0,
+            60,
+        ));
+        assert_eq!(state.mode(), GestureMode::Drawing);
+
+        let up = state.handle(&event(
+            MouseEventKind::Up(MouseButton::Middle),
+            100.0,
+            100.0,
+            80,
+        ));
+        assert!(matches!(up.outputs[..], [GestureOutput::Stroke(_)]));
+        assert_eq!(state.mode(), GestureMode::Idle);
+    }
+
+    #[test]
+    fn held_stroke_times_out_without_events() {
+        let mut state = state();
+        state.set_stroke_timeout(Some(2_000));
+        state.handle(&event(
+            MouseEventKind::Down(MouseButton::Left),
+            400.0,
+            400.0,
+            1_000,
+        ));
+        assert_eq!(state.mode(), GestureMode::Drawing);
+        assert_eq!(state.stroke_deadline(), Some(3_000));
+        assert_eq!(state.poll_stroke_timeout(2_999), None);
+
+        let output = state.poll_stroke_timeout(3_000);
+        assert_eq!(
+            output,
+            Some(GestureOutput::Cancelled {
+                reason: CancelReason::Timeout,
+                duration_ms: 2_000.0
+            })
+        );
+        assert_eq!(state.mode(), GestureMode::Passthrough);
+        assert_eq!(state.stroke_deadline(), None);
+        // 取消后松开不再产生笔画
+        let up = event(MouseEventKind::Up(MouseButton::Left), 400.0, 400.0, 3_100);
+        assert!(state.handle(&up).outputs.is_empty());
+        assert_eq!(state.mode(), GestureMode::Idle);
+    }
+}
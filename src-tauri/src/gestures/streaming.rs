@@ -0,0 +1,136 @@
+//! 增量识别会话
+//!
+//! 落笔时为每个提供 `StrokeTracker` 的识别器创建一个增量识别器，
+//! 绘制过程中逐点喂给它们；同一个采样点上给出的所有候选交给 `arbiter` 仲裁，
+//! 只有唯一胜出时才确认（歧义时继续画，等抬笔或下一次确认）。
+//! 确认后本笔识别结束（只触发一次），抬笔时这一笔不再交给识别器，也不会进入多笔画分组。
+//!
+//! 会话本身不做 I/O，识别结果由 global.rs 发出。
+
+use super::arbiter::{self, Scored, Verdict};
+use super::recognizers::{Candidate, StrokeTracker};
+use super::trajectory::TrajectoryPoint;
+
+/// 已注册识别器的增量识别器
+pub struct TrackerEntry {
+    pub recognizer: &'static str,
+    pub threshold: f64,
+    pub tracker: Box<dyn StrokeTracker>,
+}
+
+/// 一笔的增量识别状态
+pub struct StrokeStream {
+    trackers: Vec<TrackerEntry>,
+    scale: f64,
+    /// 已提前识别的识别器
+    fired: Option<&'static str>,
+}
+
+impl StrokeStream {
+    pub fn new(trackers: Vec<TrackerEntry>, scale: f64) -> Self {
+        Self {
+            trackers,
+            scale,
+            fired: None,
+        }
+    }
+
+    /// 喂入一个采样点；某个识别器确认时返回 (识别器名, 候选)
+    pub fn push(&mut self, point: TrajectoryPoint) -> Option<(&'static str, Candidate)> {
+        if self.fired.is_some() {
+            return None;
+        }
+        let scale = self.scale;
+        // 每个识别器都要喂到这个点，不能在第一个候选处短路
+        let candidates: Vec<Scored> = self
+            .trackers
+            .iter_mut()
+            .filter_map(|entry| {
+                let candidate = entry.tracker.push(point, scale)?;
+                Some(Scored::new(entry.recognizer, candidate, entry.threshold))
+            })
+            .collect();
+        if candidates.is_empty() {
+            return None;
+        }
+        let Verdict::Accepted {
+            recognizer,
+            result,
+            score,
+        } = arbiter::arbitrate(candidates).verdict
+        else {
+            return None;
+        };
+        self.fired = Some(recognizer);
+        // 已确认，释放其余识别器的状态
+        self.trackers.clear();
+        Some((recognizer, Candidate::new(result, score)))
+    }
+
+    /// 本笔是否已提前识别
+    pub fn fired(&self) -> bool {
+        self.fired.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gestures::recognizers::GestureResult;
+
+    /// 在第 `at` 个采样点给出固定分数的候选
+    struct Fixed {
+        at: usize,
+        seen: usize,
+        score: f64,
+    }
+
+    impl StrokeTracker for Fixed {
+        fn push(&mut self, _point: TrajectoryPoint, _scale: f64) -> Option<Candidate> {
+            self.seen += 1;
+            let result = GestureResult::Heart {
+                center_x: 0.0,
+                center_y: 0.0,
+                size: 1.0,
+            };
+            (self.seen == self.at).then(|| Candidate::new(result, self.score))
+        }
+    }
+
+    fn entry(recognizer: &'static str, at: usize, score: f64) -> TrackerEntry {
+        TrackerEntry {
+            recognizer,
+            threshold: 0.5,
+            tracker: Box::new(Fixed { at, seen: 0, score }),
+        }
+    }
+
+    fn feed(stream: &mut StrokeStream, count: usize) -> Vec<(usize, &'static str)> {
+        (1..=count)
+            .filter_map(|i| {
+                let point = TrajectoryPoint::new(i as f64, 0.0, i as f64);
+                stream.push(point).map(|(recognizer, _)| (i, recognizer))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn best_candidate_wins_not_first_registered() {
+        let mut stream = StrokeStream::new(vec![entry("a", 3, 0.6), entry("b", 3, 0.9)], 1.0);
+        assert_eq!(feed(&mut stream, 6), [(3, "b")]);
+        assert!(stream.fired());
+    }
+
+    #[test]
+    fn ambiguous_candidates_do_not_fire() {
+        let mut stream = StrokeStream::new(vec![entry("a", 2, 0.8), entry("b", 2, 0.79)], 1.0);
+        assert!(feed(&mut stream, 4).is_empty());
+        assert!(!stream.fired());
+    }
+
+    #[test]
+    fn below_threshold_does_not_fire() {
+        let mut stream = StrokeStream::new(vec![entry("a", 2, 0.3), entry("b", 4, 0.7)], 1.0);
+        assert_eq!(feed(&mut stream, 6), [(4, "b")]);
+    }
+}
@@ -0,0 +1,279 @@
+//! 吞没规则 —— 钩子线程与状态机共用
+//!
+//! 钩子线程（`PetSnapshot::should_swallow`）必须当场决定是否吞没，
+//! 状态机（`GestureState::handle`）也要给出同样的结论用于测试与脚本驱动，
+//! 两边都调用这里的 `decide`，规则只写一份：
+//!   - 右键：绘制中（取消这一笔）或按在宠物上时吞没按下
+//!   - 中键 / 侧键：配置开启且按在宠物上时吞没按下
+//!   - 画手势的键为中键时：宠物外按下中键（开始一笔）吞没，避免下层窗口自动滚动 / 粘贴
+//!   - 按下被吞没的键，配套吞没对应的释放，防止触发下层窗口的菜单 / 粘贴
+//!   - 滚轮：在宠物上且不在绘制时吞没（绘制中的滚轮照常交给下层窗口）
+//!   - Esc：绘制中吞没（取消这一笔，不再传给前台应用）
+//!
+//! 已吞没按下的键用 `MouseButton::mask` 组成的位掩码记录，由调用方保存。
+
+use super::input::{ButtonConfig, DrawButton, MouseButton, MouseEventKind};
+
+/// 判定所需的上下文（由调用方按各自的状态填写）
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SwallowContext {
+    /// 事件发生在宠物命中范围内
+    pub over_pet: bool,
+    /// 正在绘制
+    pub drawing: bool,
+    /// 画手势的键与宠物上中键 / 侧键的吞没（前端配置）
+    pub buttons: ButtonConfig,
+}
+
+/// 决定事件是否吞没，并更新已吞没按下的键掩码 `swallowed`
+pub fn decide(kind: &MouseEventKind, context: SwallowContext, swallowed: &mut u8) -> bool {
+    match *kind {
+        MouseEventKind::Down(button) => {
+            let swallow = match button {
+                MouseButton::Left => false,
+                MouseButton::Right => context.drawing || context.over_pet,
+                MouseButton::Middle | MouseButton::X1 | MouseButton::X2 if context.over_pet => {
+                    context.buttons.swallow_extra_buttons
+                }
+                // 宠物外按下画手势用的中键：这一笔（含释放）都不交给下层窗口
+                MouseButton::Middle => context.buttons.draw_button == DrawButton::Middle,
+                MouseButton::X1 | MouseButton::X2 => false,
+            };
+            if swallow {
+                *swallowed |= button.mask();
+            } else {
+                *swallowed &= !button.mask();
+            }
+            swallow
+        }
+        MouseEventKind::Up(button) => {
+            let swallow = *swallowed & button.mask() != 0;
+            *swallowed &= !button.mask();
+            swallow
+        }
+        MouseEventKind::Wheel { .. } => context.over_pet && !context.drawing,
+        MouseEventKind::Escape => context.drawing,
+        MouseEventKind::Move => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gestures::input::{MouseEvent, WHEEL_DELTA};
+    use crate::gestures::pipeline::PetSnapshot;
+    use crate::gestures::state::{GestureMode, GestureState};
+
+    const PET: (f64, f64, f64) = (100.0, 100.0, 65.0);
+
+    #[test]
+    fn release_follows_swallowed_press() {
+        let over = SwallowContext {
+            over_pet: true,
+            ..Default::default()
+        };
+        let away = SwallowContext::default();
+        let mut swallowed = 0;
+        assert!(decide(
+            &MouseEventKind::Down(MouseButton::Right),
+            over,
+            &mut swallowed
+        ));
+        // 释放时已经离开宠物，仍然配套吞没
+        assert!(decide(
+            &MouseEventKind::Up(MouseButton::Right),
+            away,
+            &mut swallowed
+        ));
+        assert!(!decide(
+            &MouseEventKind::Up(MouseButton::Right),
+            away,
+            &mut swallowed
+        ));
+        assert_eq!(swallowed, 0);
+    }
+
+    #[test]
+    fn extra_buttons_need_config() {
+        let over = SwallowContext {
+            over_pet: true,
+            ..Default::default()
+        };
+        let mut swallowed = 0;
+        assert!(!decide(
+            &MouseEventKind::Down(MouseButton::Middle),
+            over,
+            &mut swallowed
+        ));
+        let buttons = ButtonConfig {
+            swallow_extra_buttons: true,
+            ..Default::default()
+        };
+        let over = SwallowContext { buttons, ..over };
+        assert!(decide(
+            &MouseEventKind::Down(MouseButton::X1),
+            over,
+            &mut swallowed
+        ));
+        assert_eq!(swallowed, MouseButton::X1.mask());
+    }
+
+    #[test]
+    fn middle_draw_button_swallows_stroke_off_pet() {
+        let buttons = ButtonConfig {
+            draw_button: DrawButton::Middle,
+            ..Default::default()
+        };
+        let away = SwallowContext {
+            buttons,
+            ..Default::default()
+        };
+        let mut swallowed = 0;
+        assert!(decide(
+            &MouseEventKind::Down(MouseButton::Middle),
+            away,
+            &mut swallowed
+        ));
+        assert!(!decide(&MouseEventKind::Move, away, &mut swallowed));
+        assert!(decide(
+            &MouseEventKind::Up(MouseButton::Middle),
+            away,
+            &mut swallowed
+        ));
+        // 宠物上仍按中键 / 侧键的吞没配置
+        let over = SwallowContext {
+            over_pet: true,
+            ..away
+        };
+        assert!(!decide(
+            &MouseEventKind::Down(MouseButton::Middle),
+            over,
+            &mut swallowed
+        ));
+        // 侧键不受画手势的键影响
+        assert!(!decide(
+            &MouseEventKind::Down(MouseButton::X1),
+            away,
+            &mut swallowed
+        ));
+    }
+
+    /// 同一段脚本分别交给状态机与钩子线程的快照，返回钩子线程的吞没结论；
+    /// 每个事件两边的结论必须一致
+    fn run_script(buttons: ButtonConfig, script: &[(MouseEventKind, f64, f64)]) -> Vec<bool> {
+        let mut state = GestureState::default();
+        state.set_pet(PET.0, PET.1, PET.2);
+        state.set_buttons(buttons);
+        let snapshot = PetSnapshot::default();
+        snapshot.set_buttons(buttons);
+        snapshot.publish(Some((PET.0, PET.1)), PET.2, false, false);
+
+        let mut swallowed = Vec::new();
+        for (index, &(kind, x, y)) in script.iter().enumerate() {
+            let event = MouseEvent::new(kind, x, y).at(index as u32 * 16);
+            // 钩子线程先判定，工作线程随后处理并发布新状态
+            let hook = snapshot.should_swallow(&event);
+            let reaction = state.handle(&event);
+            assert_eq!(
+                hook, reaction.swallow,
+                "第 {} 个事件 {:?} 结论不一致",
+                index, kind
+            );
+            let mode = state.mode();
+            snapshot.publish(
+                state.pet_position(),
+                state.pet_hit_radius(),
+                mode == GestureMode::Dragging,
+                mode == GestureMode::Drawing,
+            );
+            swallowed.push(hook);
+        }
+        swallowed
+    }
+
+    #[test]
+    fn snapshot_agrees_with_state() {
+        let wheel = MouseEventKind::Wheel {
+            delta_x: 0,
+            delta_y: WHEEL_DELTA,
+        };
+        let script = [
+            (MouseEventKind::Move, 500.0, 500.0),
+            (MouseEventKind::Down(MouseButton::Right), 100.0, 100.0),
+            (MouseEventKind::Move, 400.0, 400.0),
+            (MouseEventKind::Up(MouseButton::Right), 400.0, 400.0),
+            (wheel, 110.0, 90.0),
+            (wheel, 400.0, 400.0),
+            (MouseEventKind::Down(MouseButton::Middle), 100.0, 100.0),
+            (MouseEventKind::Up(MouseButton::Middle), 300.0, 300.0),
+            (MouseEventKind::Down(MouseButton::X2), 400.0, 400.0),
+            (MouseEventKind::Up(MouseButton::X2), 100.0, 100.0),
+            // 宠物外绘制，右键取消
+            (MouseEventKind::Down(MouseButton::Left), 400.0, 400.0),
+            (MouseEventKind::Move, 450.0, 420.0),
+            (MouseEventKind::Down(MouseButton::Right), 460.0, 430.0),
+            (MouseEventKind::Up(MouseButton::Right), 460.0, 430.0),
+            (MouseEventKind::Escape, 460.0, 430.0),
+            (MouseEventKind::Up(MouseButton::Left), 460.0, 430.0),
+            // 宠物外绘制，Esc 取消
+            (MouseEventKind::Down(MouseButton::Left), 400.0, 400.0),
+            (MouseEventKind::Move, 450.0, 420.0),
+            (MouseEventKind::Escape, 450.0, 420.0),
+            (MouseEventKind::Escape, 450.0, 420.0),
+            (MouseEventKind::Up(MouseButton::Left), 450.0, 420.0),
+            (MouseEventKind::Down(MouseButton::Right), 600.0, 600.0),
+            (MouseEventKind::Up(MouseButton::Right), 600.0, 600.0),
+        ];
+        let buttons = ButtonConfig {
+            swallow_extra_buttons: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            run_script(buttons, &script),
+            [
+                false, true, false, true, true, false, true, true, false, false, false, false,
+                true, true, false, false, false, false, true, false, false, false, false
+            ]
+        );
+    }
+
+    #[test]
+    fn snapshot_agrees_with_state_when_drawing_with_middle() {
+        let script = [
+            // 宠物外中键绘制，途中在宠物上滚动滚轮、按下左键
+            (MouseEventKind::Down(MouseButton::Middle), 500.0, 500.0),
+            (MouseEventKind::Move, 300.0, 300.0),
+            (
+                MouseEventKind::Wheel {
+                    delta_x: 0,
+                    delta_y: WHEEL_DELTA,
+                },
+                100.0,
+                100.0,
+            ),
+            (MouseEventKind::Down(MouseButton::Left), 100.0, 100.0),
+            (MouseEventKind::Up(MouseButton::Left), 100.0, 100.0),
+            (MouseEventKind::Escape, 100.0, 100.0),
+            (MouseEventKind::Up(MouseButton::Middle), 100.0, 100.0),
+            // 宠物上的中键：未开启吞没
+            (MouseEventKind::Down(MouseButton::Middle), 100.0, 100.0),
+            (MouseEventKind::Up(MouseButton::Middle), 100.0, 100.0),
+            // 宠物外的左键拖动不再画手势
+            (MouseEventKind::Down(MouseButton::Left), 500.0, 500.0),
+            (MouseEventKind::Down(MouseButton::Right), 520.0, 500.0),
+            (MouseEventKind::Up(MouseButton::Right), 520.0, 500.0),
+            (MouseEventKind::Up(MouseButton::Left), 520.0, 500.0),
+        ];
+        let buttons = ButtonConfig {
+            draw_button: DrawButton::Middle,
+            ..Default::default()
+        };
+        assert_eq!(
+            run_script(buttons, &script),
+            [
+                true, false, false, false, false, true, true, false, false, false, false, false,
+                false
+            ]
+        );
+    }
+}
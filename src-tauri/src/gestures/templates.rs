@@ -0,0 +1,167 @@
+//! 用户模板库 —— 持久化 + 样本录制
+//!
+//! 模板保存在应用数据目录下的 `gesture_templates.json`，启动时由 `init` 载入。
+//!
+//! 录制流程（前端通过命令驱动）：
+//!   1. `start_recording()`：之后的每一笔不再交给识别器，而是归一化后暂存为样本，
+//!      每录到一个样本 emit "gesture-sample-recorded"
+//!   2. `save_template(name)`：把暂存样本写入同名模板（已存在则追加）并落盘，结束录制
+//!   3. `cancel_recording()`：丢弃暂存样本，结束录制
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+
+use super::preprocess;
+use super::recognizers::template::{
+    self, normalize_stroke, TemplateInfo, TemplateLibrary, TemplateSample,
+};
+use super::trajectory::Trajectory;
+
+const TEMPLATE_FILE: &str = "gesture_templates.json";
+
+/// 模板名最大长度（字符）
+const MAX_NAME_LEN: usize = 32;
+
+/// "gesture-sample-recorded" 事件 payload
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct SampleRecordedPayload {
+    /// 本次录制已暂存的样本数
+    pub count: usize,
+    /// 该笔是否因太短被忽略
+    pub rejected: bool,
+}
+
+// ── 全局状态 ────────────────────────────────────────────────────────────────
+
+static LIBRARY: RwLock<Option<Arc<TemplateLibrary>>> = RwLock::new(None);
+static STORE_PATH: Mutex<Option<PathBuf>> = Mutex::new(None);
+/// 录制中的样本；None 表示未在录制
+static RECORDING: Mutex<Option<Vec<TemplateSample>>> = Mutex::new(None);
+
+/// 设置存储目录并载入模板库（启动时调用一次）
+pub fn init(dir: &Path) {
+    let path = dir.join(TEMPLATE_FILE);
+    let library = match std::fs::read_to_string(&path) {
+        Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| {
+            eprintln!("[templates] ⚠️ 模板文件解析失败，使用空模板库：{}", e);
+            TemplateLibrary::default()
+        }),
+        Err(_) => TemplateLibrary::default(),
+    };
+    eprintln!(
+        "[templates] 载入 {} 个模板（{}）",
+        library.templates.len(),
+        path.display()
+    );
+    *LIBRARY.write().unwrap() = Some(Arc::new(library));
+    *STORE_PATH.lock().unwrap() = Some(path);
+}
+
+/// 当前模板库快照
+pub fn library() -> Arc<TemplateLibrary> {
+    LIBRARY.read().unwrap().clone().unwrap_or_default()
+}
+
+pub fn list_templates() -> Vec<TemplateInfo> {
+    library().infos()
+}
+
+// ── 录制 ────────────────────────────────────────────────────────────────────
+
+/// 开始录制（清空上一次未保存的样本）
+pub fn start_recording() {
+    *RECORDING.lock().unwrap() = Some(Vec::new());
+    eprintln!("[templates] ⏺️ 开始录制样本");
+}
+
+/// 是否正在录制（录制中的笔画不做增量识别）
+pub fn is_recording() -> bool {
+    RECORDING.lock().unwrap().is_some()
+}
+
+/// 取消录制，丢弃暂存样本
+pub fn cancel_recording() {
+    *RECORDING.lock().unwrap() = None;
+    eprintln!("[templates] ⏹️ 取消录制");
+}
+
+/// 工作线程调用：录制中时把这一笔存为样本并返回 payload，未录制时返回 None
+///
+/// 样本与识别时经过相同的预处理（`template::STAGES`）。
+pub fn record_stroke(stroke: &Trajectory, scale: f64) -> Option<SampleRecordedPayload> {
+    let mut recording = RECORDING.lock().unwrap();
+    let samples = recording.as_mut()?;
+    let sample = normalize_stroke(&preprocess::apply(stroke, template::STAGES, scale), scale);
+    let rejected = sample.is_none();
+    if let Some(sample) = sample {
+        samples.push(sample);
+    }
+    eprintln!(
+        "[templates] 样本 {}（共 {}）",
+        if rejected {
+            "太短，已忽略"
+        } else {
+            "已记录"
+        },
+        samples.len()
+    );
+    Some(SampleRecordedPayload {
+        count: samples.len(),
+        rejected,
+    })
+}
+
+/// 把暂存样本保存为命名模板并结束录制
+pub fn save_template(name: &str) -> Result<TemplateInfo, String> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_NAME_LEN {
+        return Err(format!("模板名需为 1 ~ {} 个字符", MAX_NAME_LEN));
+    }
+
+    let mut recording = RECORDING.lock().unwrap();
+    let samples = match recording.as_ref() {
+        None => return Err("当前没有在录制".into()),
+        Some(samples) if samples.is_empty() => return Err("还没有录到任何样本".into()),
+        Some(samples) => samples.clone(),
+    };
+
+    let mut library = library().as_ref().clone();
+    let info = library.add_samples(name, samples);
+    persist(&library)?;
+    *LIBRARY.write().unwrap() = Some(Arc::new(library));
+    *recording = None;
+
+    eprintln!(
+        "[templates] 💾 保存模板 {}（{} 个样本）",
+        info.name, info.samples
+    );
+    Ok(info)
+}
+
+/// 删除命名模板
+pub fn delete_template(name: &str) -> Result<(), String> {
+    let mut library = library().as_ref().clone();
+    if !library.remove(name) {
+        return Err(format!("模板不存在: {}", name));
+    }
+    persist(&library)?;
+    *LIBRARY.write().unwrap() = Some(Arc::new(library));
+    eprintln!("[templates] 🗑️ 删除模板 {}", name);
+    Ok(())
+}
+
+/// 写入临时文件后重命名，避免写到一半崩溃导致模板库损坏
+fn persist(library: &TemplateLibrary) -> Result<(), String> {
+    let path = STORE_PATH
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or("模板库尚未初始化")?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let text = serde_json::to_string_pretty(library).map_err(|e| e.to_string())?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, text).map_err(|e| e.to_string())?;
+    std::fs::rename(&tmp, &path).map_err(|e| e.to_string())
+}
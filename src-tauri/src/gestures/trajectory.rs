@@ -0,0 +1,207 @@
+//! 带时间戳的绘制轨迹
+//!
+//! 绘制模式下状态机记录的每个采样点都带有相对落笔时刻的毫秒数，
+//! 识别器因此可以利用速度、时长、停顿、加速度等信息，
+//! 例如拒绝缓慢的无意绕圈、只接受快速果断的画圈。
+//!
+//! 坐标为 **物理像素**；时间来自后端的事件时间戳。
+//! 不提供时间戳的后端（例如合成脚本全部为 0）会得到时长为 0 的轨迹，
+//! 识别器应通过 `has_timing()` 判断后再使用时间相关的特征。
+
+/// 单个轨迹采样点
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TrajectoryPoint {
+    pub x: f64,
+    pub y: f64,
+    /// 相对落笔时刻的毫秒数
+    pub t: f64,
+}
+
+impl TrajectoryPoint {
+    pub fn new(x: f64, y: f64, t: f64) -> Self {
+        Self { x, y, t }
+    }
+
+    pub fn distance_to(&self, other: &TrajectoryPoint) -> f64 {
+        ((self.x - other.x).powi(2) + (self.y - other.y).powi(2)).sqrt()
+    }
+}
+
+/// 一笔完整的轨迹（落笔 → 抬笔）
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Trajectory {
+    points: Vec<TrajectoryPoint>,
+    /// 落笔到抬笔的总时长（毫秒）
+    duration_ms: f64,
+}
+
+impl Trajectory {
+    pub fn new(points: Vec<TrajectoryPoint>, duration_ms: f64) -> Self {
+        Self {
+            points,
+            duration_ms,
+        }
+    }
+
+    /// 由无时间信息的坐标序列构造（时间全部为 0）
+    pub fn from_positions(positions: &[(f64, f64)]) -> Self {
+        let points = positions
+            .iter()
+            .map(|&(x, y)| TrajectoryPoint::new(x, y, 0.0))
+            .collect();
+        Self::new(points, 0.0)
+    }
+
+    pub fn points(&self) -> &[TrajectoryPoint] {
+        &self.points
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// 只取坐标（供只关心形状的识别器使用）
+    pub fn positions(&self) -> Vec<(f64, f64)> {
+        self.points.iter().map(|p| (p.x, p.y)).collect()
+    }
+
+    /// 落笔到抬笔的总时长（毫秒）
+    pub fn duration_ms(&self) -> f64 {
+        self.duration_ms
+    }
+
+    /// 后端是否提供了可用的时间戳
+    pub fn has_timing(&self) -> bool {
+        self.duration_ms > 0.0
+    }
+
+    /// 质心（物理像素）
+    pub fn centroid(&self) -> Option<(f64, f64)> {
+        if self.points.is_empty() {
+            return None;
+        }
+        let n = self.points.len() as f64;
+        let cx = self.points.iter().map(|p| p.x).sum::<f64>() / n;
+        let cy = self.points.iter().map(|p| p.y).sum::<f64>() / n;
+        Some((cx, cy))
+    }
+
+    /// 轨迹总长度（物理像素）
+    pub fn path_length(&self) -> f64 {
+        self.points
+            .windows(2)
+            .map(|w| w[0].distance_to(&w[1]))
+            .sum()
+    }
+
+    /// 平均速度（物理像素 / 毫秒）；没有时间信息时返回 None
+    pub fn average_speed(&self) -> Option<f64> {
+        self.has_timing()
+            .then(|| self.path_length() / self.duration_ms)
+    }
+
+    /// 相邻采样点之间的瞬时速度（物理像素 / 毫秒），时间差为 0 的区段被跳过
+    pub fn segment_speeds(&self) -> Vec<f64> {
+        self.points
+            .windows(2)
+            .filter_map(|w| {
+                let dt = w[1].t - w[0].t;
+                (dt > 0.0).then(|| w[0].distance_to(&w[1]) / dt)
+            })
+            .collect()
+    }
+
+    /// 相邻采样点之间的最长停顿（毫秒），包括落笔后首个采样点之前的等待
+    pub fn longest_pause_ms(&self) -> f64 {
+        let first = self.points.first().map_or(0.0, |p| p.t);
+        self.points
+            .windows(2)
+            .map(|w| w[1].t - w[0].t)
+            .fold(first, f64::max)
+    }
+
+    /// 末尾一段时间窗口内的平均速度（物理像素 / 毫秒），用于判断甩出 / 急停
+    pub fn tail_speed(&self, window_ms: f64) -> Option<f64> {
+        let last = self.points.last()?;
+        let from = self
+            .points
+            .iter()
+            .rposition(|p| last.t - p.t >= window_ms)
+            .unwrap_or(0);
+        let dt = last.t - self.points[from].t;
+        if dt <= 0.0 {
+            return None;
+        }
+        let length: f64 = self.points[from..]
+            .windows(2)
+            .map(|w| w[0].distance_to(&w[1]))
+            .sum();
+        Some(length / dt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 沿 x 轴每 10ms 前进 `step` 像素
+    fn line(steps: &[f64]) -> Trajectory {
+        let mut x = 0.0;
+        let mut points = vec![TrajectoryPoint::new(0.0, 0.0, 0.0)];
+        for (i, step) in steps.iter().enumerate() {
+            x += step;
+            points.push(TrajectoryPoint::new(x, 0.0, (i + 1) as f64 * 10.0));
+        }
+        let duration = steps.len() as f64 * 10.0;
+        Trajectory::new(points, duration)
+    }
+
+    #[test]
+    fn untimed_strokes_have_no_speed() {
+        let stroke = Trajectory::from_positions(&[(0.0, 0.0), (30.0, 40.0)]);
+        assert!(!stroke.has_timing());
+        assert_eq!(stroke.path_length(), 50.0);
+        assert_eq!(stroke.average_speed(), None);
+        assert!(stroke.segment_speeds().is_empty());
+        assert_eq!(stroke.tail_speed(50.0), None);
+    }
+
+    #[test]
+    fn speeds() {
+        let stroke = line(&[10.0, 10.0, 30.0, 30.0]);
+        assert_eq!(stroke.duration_ms(), 40.0);
+        assert_eq!(stroke.average_speed(), Some(2.0));
+        assert_eq!(stroke.segment_speeds(), [1.0, 1.0, 3.0, 3.0]);
+        assert_eq!(stroke.centroid(), Some((32.0, 0.0)));
+    }
+
+    #[test]
+    fn tail_speed_uses_last_window() {
+        let stroke = line(&[10.0, 10.0, 30.0, 30.0]);
+        // 最后 20ms 只包含两段快速区段
+        assert_eq!(stroke.tail_speed(20.0), Some(3.0));
+        // 窗口比整笔还长时退化为整笔平均速度
+        assert_eq!(stroke.tail_speed(1000.0), Some(2.0));
+    }
+
+    #[test]
+    fn longest_pause_includes_wait_before_first_sample() {
+        let points = vec![
+            TrajectoryPoint::new(0.0, 0.0, 120.0),
+            TrajectoryPoint::new(5.0, 0.0, 130.0),
+            TrajectoryPoint::new(9.0, 0.0, 300.0),
+        ];
+        assert_eq!(
+            Trajectory::new(points.clone(), 300.0).longest_pause_ms(),
+            170.0
+        );
+        assert_eq!(
+            Trajectory::new(points[..2].to_vec(), 130.0).longest_pause_ms(),
+            120.0
+        );
+    }
+}
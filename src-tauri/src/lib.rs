@@ -1,50 +1,157 @@
 use tauri::{
-    AppHandle, Manager,
     menu::{Menu, MenuItem},
     tray::{TrayIconBuilder, TrayIconEvent},
+    AppHandle, Emitter, Manager,
 };
 
-mod gestures;
-mod window_manager;
-mod input_inject; // Phase3+ 占位
+mod capture;
+pub mod display;
+pub mod gestures;
+mod input_inject;
+mod window_manager; // Phase3+ 占位
 
-use gestures::recognizers::circle::CircleRecognizer;
-use gestures::recognizers::GestureRecognizer;
+use gestures::activation::ActivationPolicy;
+use gestures::input::ButtonConfig;
+use gestures::recognizers::template::TemplateInfo;
+use gestures::registry::{RecognizerSpec, RegistryError};
 
 // ── 宠物位置同步 ────────────────────────────────────────────────────────────
 
 /// 前端在宠物位置变化时调用，同步给全局钩子用于拖拽命中判定
 #[tauri::command]
-fn update_pet_position(app: AppHandle, x: f64, y: f64) -> Result<(), String> {
-    let window = app.get_webview_window("main").ok_or("window not found")?;
-    let scale = window.scale_factor().map_err(|e| e.to_string())?;
-    gestures::set_pet_position(x, y, scale);
+fn update_pet_position(x: f64, y: f64) -> Result<(), String> {
+    gestures::set_pet_position(x, y);
     Ok(())
 }
 
 // ── 手势识别器注册 ──────────────────────────────────────────────────────────
 
 /// 前端切换宠物时调用，注册该宠物需要的手势识别器
+///
+/// 每个条目为 `{ name, params }`，params 可省略（全部取默认值），字段见各识别器的 `*Params`；
+/// "point-cloud" 为多笔画识别器，需配合 set_multi_stroke_mode 开启。
+/// 任一条目有误时不做任何替换，返回全部错误。
+#[tauri::command]
+fn register_recognizers(recognizers: Vec<RecognizerSpec>) -> Result<(), Vec<RegistryError>> {
+    let registered = gestures::registry::build(&recognizers).inspect_err(|errors| {
+        eprintln!("[lib] ⚠️ 识别器注册失败: {:?}", errors);
+    })?;
+    gestures::set_recognizers(registered.single);
+    gestures::set_multi_stroke_recognizers(registered.multi);
+    Ok(())
+}
+
+/// 开启 / 关闭多笔画模式；timeout_ms 为笔画间隔超时，缺省使用默认值
+#[tauri::command]
+fn set_multi_stroke_mode(enabled: bool, timeout_ms: Option<u64>) -> Result<(), String> {
+    let timeout = timeout_ms
+        .map(std::time::Duration::from_millis)
+        .unwrap_or(gestures::multistroke::DEFAULT_STROKE_TIMEOUT);
+    if enabled && timeout.is_zero() {
+        return Err("timeout_ms 必须大于 0".into());
+    }
+    gestures::set_multi_stroke_timeout(enabled.then_some(timeout));
+    Ok(())
+}
+
+/// 设置手势激活策略：避免选中文字、框选文件等普通拖动被当成手势
+///
+/// `{ mode: "always" | "modifier" | "dwell" | "apps", ... }`，字段见 `ActivationPolicy`。
+#[tauri::command]
+fn set_gesture_activation(policy: ActivationPolicy) -> Result<(), String> {
+    policy.validate()?;
+    gestures::set_activation_policy(policy);
+    Ok(())
+}
+
+/// 设置一笔的最长时长：超过后取消这一笔并发出 "gesture-cancelled"；timeout_ms 缺省表示不限
 #[tauri::command]
-fn register_recognizers(names: Vec<String>) -> Result<(), String> {
-    let mut list: Vec<Box<dyn GestureRecognizer>> = Vec::new();
-    for name in &names {
-        match name.as_str() {
-            "circle" => list.push(Box::new(CircleRecognizer)),
-            other => eprintln!("[lib] ⚠️ 未知识别器: {}", other),
-        }
+fn set_gesture_timeout(timeout_ms: Option<u64>) -> Result<(), String> {
+    if timeout_ms == Some(0) {
+        return Err("timeout_ms 必须大于 0".into());
     }
-    gestures::set_recognizers(list);
+    gestures::set_stroke_max_duration(timeout_ms.map(std::time::Duration::from_millis));
     Ok(())
 }
 
+/// 设置中键 / 侧键：`{ draw_button?: "left" | "middle", swallow_extra_buttons?: bool }`
+///
+/// draw_button 为 middle 时改用中键拖动画手势（左键拖动与工作软件冲突时）；
+/// swallow_extra_buttons 为 true 时宠物上的中键 / 侧键不再传给下层窗口。
+/// 输入后端无法吞没事件时（X11）拒绝用中键画手势。
+#[tauri::command]
+fn set_button_config(config: ButtonConfig) -> Result<(), String> {
+    gestures::set_button_config(config)
+}
+
+// ── 手势模板 ────────────────────────────────────────────────────────────────
+
+/// 开始录制模板样本：之后的每一笔都作为样本暂存，不触发识别
+#[tauri::command]
+fn start_gesture_recording() -> Result<(), String> {
+    gestures::templates::start_recording();
+    Ok(())
+}
+
+/// 取消录制，丢弃暂存样本
+#[tauri::command]
+fn cancel_gesture_recording() -> Result<(), String> {
+    gestures::templates::cancel_recording();
+    Ok(())
+}
+
+/// 把录制的样本保存为命名模板（同名模板追加样本）
+#[tauri::command]
+fn save_gesture_template(name: String) -> Result<TemplateInfo, String> {
+    gestures::templates::save_template(&name)
+}
+
+/// 列出所有模板
+#[tauri::command]
+fn list_gesture_templates() -> Vec<TemplateInfo> {
+    gestures::templates::list_templates()
+}
+
+/// 删除命名模板
+#[tauri::command]
+fn delete_gesture_template(name: String) -> Result<(), String> {
+    gestures::templates::delete_template(&name)
+}
+
+// ── 框选截图 ────────────────────────────────────────────────────────────────
+
+/// 截取桌面上的逻辑像素矩形（通常来自 "gesture-rectangle"），保存为应用数据目录下的 PNG，
+/// 并 emit "region-captured"
+///
+/// 截屏与 PNG 编码较慢，放到阻塞线程池执行，不占用 IPC 线程。
+#[tauri::command]
+async fn capture_region(
+    app: AppHandle,
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+) -> Result<capture::CapturedRegion, String> {
+    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    let captured = tauri::async_runtime::spawn_blocking(move || {
+        capture::capture_region(&dir, x, y, width, height)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    let _ = app.emit("region-captured", captured.clone());
+    Ok(captured)
+}
+
 // ── 面板窗口控制 ────────────────────────────────────────────────────────────
 
 /// 在指定屏幕位置显示面板窗口（逻辑像素）
 #[tauri::command]
 fn show_panel(app: AppHandle, x: f64, y: f64) -> Result<(), String> {
-    let panel = app.get_webview_window("panel").ok_or("panel window not found")?;
-    panel.set_position(tauri::LogicalPosition::new(x, y))
+    let panel = app
+        .get_webview_window("panel")
+        .ok_or("panel window not found")?;
+    panel
+        .set_position(tauri::LogicalPosition::new(x, y))
         .map_err(|e| e.to_string())?;
     panel.show().map_err(|e| e.to_string())?;
     panel.set_focus().map_err(|e| e.to_string())?;
@@ -54,49 +161,34 @@ fn show_panel(app: AppHandle, x: f64, y: f64) -> Result<(), String> {
 /// 隐藏面板窗口
 #[tauri::command]
 fn hide_panel(app: AppHandle) -> Result<(), String> {
-    let panel = app.get_webview_window("panel").ok_or("panel window not found")?;
+    let panel = app
+        .get_webview_window("panel")
+        .ok_or("panel window not found")?;
     panel.hide().map_err(|e| e.to_string())?;
     Ok(())
 }
 
 // ── 通用窗口控制 ────────────────────────────────────────────────────────────
 
-/// 获取主显示器的缩放因子
+/// 获取主窗口所在显示器的缩放因子（逐显示器信息见 get_monitors）
 #[tauri::command]
 fn get_scale_factor(app: AppHandle) -> Result<f64, String> {
     let window = app.get_webview_window("main").ok_or("window not found")?;
     window.scale_factor().map_err(|e| e.to_string())
 }
 
-// ★ 修复：获取虚拟桌面尺寸（覆盖所有显示器）+ 左上角偏移
-#[cfg(windows)]
-fn get_virtual_screen() -> (f64, f64, f64, f64) {
-    use windows::Win32::UI::WindowsAndMessaging::{
-        GetSystemMetrics, SM_XVIRTUALSCREEN, SM_YVIRTUALSCREEN,
-        SM_CXVIRTUALSCREEN, SM_CYVIRTUALSCREEN,
-    };
-    unsafe {
-        let x = GetSystemMetrics(SM_XVIRTUALSCREEN) as f64;
-        let y = GetSystemMetrics(SM_YVIRTUALSCREEN) as f64;
-        let w = GetSystemMetrics(SM_CXVIRTUALSCREEN) as f64;
-        let h = GetSystemMetrics(SM_CYVIRTUALSCREEN) as f64;
-        (x, y, w, h)
-    }
+/// 获取显示器拓扑：每个显示器的标识、物理矩形、工作区与缩放因子
+#[tauri::command]
+fn get_monitors() -> display::MonitorTopology {
+    display::topology().as_ref().clone()
 }
 
 /// ★ 修复：获取虚拟桌面物理尺寸 + 左上角偏移（覆盖所有显示器）
 /// 返回 (origin_x, origin_y, width, height) — 全部为物理像素
-#[cfg(windows)]
-#[tauri::command]
-fn get_screen_size() -> (i32, i32, u32, u32) {
-    let (x, y, w, h) = get_virtual_screen();
-    (x as i32, y as i32, w as u32, h as u32)
-}
-
-#[cfg(not(windows))]
 #[tauri::command]
 fn get_screen_size() -> (i32, i32, u32, u32) {
-    (0, 0, 1920, 1080)
+    let rect = display::virtual_screen();
+    (rect.x, rect.y, rect.width, rect.height)
 }
 
 /// 重新置顶窗口（解决被任务栏预览等覆盖的问题）
@@ -111,14 +203,18 @@ fn reassert_always_on_top(app: AppHandle) -> Result<(), String> {
 #[tauri::command]
 fn set_ignore_cursor_events(app: AppHandle, ignore: bool) -> Result<(), String> {
     let window = app.get_webview_window("main").ok_or("window not found")?;
-    window.set_ignore_cursor_events(ignore).map_err(|e| e.to_string())
+    window
+        .set_ignore_cursor_events(ignore)
+        .map_err(|e| e.to_string())
 }
 
 /// 设置窗口位置（通用，前端指定 label）
 #[tauri::command]
 fn set_window_position(app: AppHandle, x: f64, y: f64) -> Result<(), String> {
     let window = app.get_webview_window("main").ok_or("window not found")?;
-    window.set_position(tauri::LogicalPosition::new(x, y)).map_err(|e| e.to_string())
+    window
+        .set_position(tauri::LogicalPosition::new(x, y))
+        .map_err(|e| e.to_string())
 }
 
 /// 设置窗口大小（通用）
@@ -126,7 +222,9 @@ fn set_window_position(app: AppHandle, x: f64, y: f64) -> Result<(), String> {
 fn set_window_size(app: AppHandle, width: f64, height: f64) -> Result<(), String> {
     let window = app.get_webview_window("main").ok_or("window not found")?;
     let _ = window.set_min_size(Some(tauri::LogicalSize::new(1.0_f64, 1.0_f64)));
-    window.set_size(tauri::LogicalSize::new(width, height)).map_err(|e| e.to_string())
+    window
+        .set_size(tauri::LogicalSize::new(width, height))
+        .map_err(|e| e.to_string())
 }
 
 /// 获取窗口位置（逻辑像素）
@@ -175,64 +273,74 @@ pub fn run() {
                     }
                     _ => {}
                 })
-                .on_tray_icon_event(|_tray, event| {
-                    if let TrayIconEvent::Click { .. } = event {}
-                })
+                .on_tray_icon_event(|_tray, event| if let TrayIconEvent::Click { .. } = event {})
                 .build(app)?;
 
             // ── 主窗口：全屏 + 穿透 ──────────────────────────────────────
-            let main_win = app.get_webview_window("main")
+            let main_win = app
+                .get_webview_window("main")
                 .expect("main window not found");
 
             // ★ 关键修复：获取虚拟桌面尺寸（覆盖所有显示器）
-            let (virt_x, virt_y, phys_w, phys_h) = {
-                #[cfg(windows)]
-                {
-                    get_virtual_screen()
-                }
-                #[cfg(not(windows))]
-                { (0.0, 0.0, 1920.0, 1080.0) }
-            };
-
-            // ★ 关键修复：直接用 PhysicalPosition / PhysicalSize 设置窗口
-            //
-            //   之前的做法：physical → ÷ scale → LogicalSize → Tauri 内部 × scale → physical
-            //   问题：dev 和 release 模式下 DPI 感知可能不同，导致 GetSystemMetrics
-            //         返回的值与 main_win.scale_factor() 不匹配，round-trip 计算错误。
-            //
-            //   修复后：直接用物理像素坐标设置窗口，完全绕开 scale 转换的不一致性。
-            //   GetSystemMetrics 返回的坐标在当前进程的 DPI 感知模式下总是与
-            //   PhysicalPosition/PhysicalSize 对齐的。
-            let _ = main_win.set_min_size(Some(tauri::PhysicalSize::new(1u32, 1u32)));
-            let _ = main_win.set_position(tauri::PhysicalPosition::new(
-                virt_x as i32,
-                virt_y as i32,
-            ));
-            let _ = main_win.set_size(tauri::PhysicalSize::new(
-                phys_w as u32,
-                (phys_h as u32).saturating_sub(1),
-            ));
+            let topology = display::refresh_topology();
+            for m in &topology.monitors {
+                eprintln!(
+                    "[setup] monitor {}: bounds={:?} work_area={:?} scale={:.2}{}",
+                    m.id,
+                    m.bounds,
+                    m.work_area,
+                    m.scale,
+                    if m.primary { " (primary)" } else { "" }
+                );
+            }
+            let virt = topology.virtual_bounds;
+
+            // ★ 关键修复：直接用物理像素铺满虚拟桌面（详见 window_manager::fit_main_window）
+            window_manager::fit_main_window(&main_win, &topology);
             let _ = main_win.set_ignore_cursor_events(true);
 
             // 获取实际 scale（仅用于日志，不再用于窗口尺寸计算）
             let scale = main_win.scale_factor().unwrap_or(1.0);
             eprintln!(
-                "[setup] virtual screen: origin=({:.0},{:.0}) size={:.0}x{:.0} phys (scale={:.2})",
-                virt_x, virt_y, phys_w, phys_h, scale
+                "[setup] virtual screen: origin=({},{}) size={}x{} phys (scale={:.2})",
+                virt.x, virt.y, virt.width, virt.height, scale
             );
 
+            // ── 载入用户手势模板 ──────────────────────────────────────────
+            match app.path().app_data_dir() {
+                Ok(dir) => gestures::templates::init(&dir),
+                Err(e) => eprintln!("[setup] ⚠️ 无法获取应用数据目录，模板不会保存：{}", e),
+            }
+
             // ── 启动全局手势监听 ──────────────────────────────────────────
             let app_handle = app.handle().clone();
             gestures::start_global_listener(app_handle);
 
+            // ── 监听显示配置变化（热插拔 / DPI 变化）─────────────────────
+            let app_handle = app.handle().clone();
+            display::spawn_watcher(move |topology| {
+                window_manager::on_display_changed(&app_handle, topology);
+            });
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             update_pet_position,
             register_recognizers,
+            set_multi_stroke_mode,
+            set_gesture_activation,
+            set_gesture_timeout,
+            set_button_config,
+            start_gesture_recording,
+            cancel_gesture_recording,
+            save_gesture_template,
+            list_gesture_templates,
+            delete_gesture_template,
+            capture_region,
             show_panel,
             hide_panel,
             get_scale_factor,
+            get_monitors,
             get_screen_size,
             reassert_always_on_top,
             set_ignore_cursor_events,
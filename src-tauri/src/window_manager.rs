@@ -1,7 +1,76 @@
 // window_manager.rs
 // 窗口管理辅助函数
-// Phase 1 主要处理：宠物窗口跟随鼠标拖动时的坐标转换
-
-// 目前功能通过 Tauri commands 直接暴露给前端
+//
+// - 主覆盖窗口铺满虚拟桌面（启动时 + 显示配置变化时）
+// - 显示配置变化后把宠物钳制回可见工作区，并通知前端
+//
 // 此模块预留用于后续更复杂的多窗口管理逻辑
 // 例如：派遣动画时临时扩大窗口区域、同屏模式下的覆盖窗口等
+
+use std::sync::Arc;
+
+use tauri::{AppHandle, Emitter, Manager, WebviewWindow};
+
+use crate::display::MonitorTopology;
+use crate::gestures;
+use crate::gestures::global::DragPayload;
+
+/// 宠物与工作区边缘的最小距离（逻辑像素）
+const PET_EDGE_MARGIN: f64 = 60.0;
+
+/// "display-changed" 事件 payload
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct DisplayChangedPayload {
+    pub topology: MonitorTopology,
+    /// 钳制后的宠物位置（逻辑像素）；前端尚未同步过宠物位置或正在拖拽时为 None
+    pub pet: Option<DragPayload>,
+}
+
+/// 把主窗口铺满虚拟桌面
+///
+/// ★ 关键修复：直接用 PhysicalPosition / PhysicalSize 设置窗口
+///
+///   之前的做法：physical → ÷ scale → LogicalSize → Tauri 内部 × scale → physical
+///   问题：dev 和 release 模式下 DPI 感知可能不同，导致 GetSystemMetrics
+///         返回的值与 main_win.scale_factor() 不匹配，round-trip 计算错误。
+///
+///   修复后：直接用物理像素坐标设置窗口，完全绕开 scale 转换的不一致性。
+///   显示器枚举返回的坐标在当前进程的 DPI 感知模式下总是与
+///   PhysicalPosition/PhysicalSize 对齐的。
+pub fn fit_main_window(main_win: &WebviewWindow, topology: &MonitorTopology) {
+    let virt = topology.virtual_bounds;
+    let _ = main_win.set_min_size(Some(tauri::PhysicalSize::new(1u32, 1u32)));
+    let _ = main_win.set_position(tauri::PhysicalPosition::new(virt.x, virt.y));
+    let _ = main_win.set_size(tauri::PhysicalSize::new(
+        virt.width,
+        virt.height.saturating_sub(1),
+    ));
+}
+
+/// 显示配置变化：重新铺满主窗口 → 钳制宠物 → emit "display-changed"
+pub fn on_display_changed(app: &AppHandle, topology: Arc<MonitorTopology>) {
+    if let Some(main_win) = app.get_webview_window("main") {
+        fit_main_window(&main_win, &topology);
+    }
+
+    let pet = gestures::pet_physical_position().map(|(x, y)| {
+        let margin = PET_EDGE_MARGIN * topology.scale_at(x, y);
+        let (cx, cy) = topology.clamp_to_work_area(x, y, margin);
+        if (cx, cy) != (x, y) {
+            eprintln!(
+                "[window] 宠物 ({:.0}, {:.0}) 不在可见工作区内，移动到 ({:.0}, {:.0})",
+                x, y, cx, cy
+            );
+        }
+        gestures::set_pet_physical_position(cx, cy);
+        // 缩放因子可能变了，即使物理位置不变，逻辑位置也要重新下发
+        let (lx, ly) = topology.to_logical(cx, cy);
+        DragPayload { x: lx, y: ly }
+    });
+
+    let payload = DisplayChangedPayload {
+        topology: topology.as_ref().clone(),
+        pet,
+    };
+    let _ = app.emit("display-changed", payload);
+}
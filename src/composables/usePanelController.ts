@@ -20,13 +20,15 @@
  *   - 3 秒到期 → 关闭面板
  *
  * ★ 多显示器修复：
- *   面板位置计算改为从 Rust 获取虚拟桌面尺寸（覆盖所有显示器），
+ *   面板位置计算改为从 Rust 获取显示器拓扑（get_monitors），
+ *   钳制到宠物所在显示器的工作区，按该显示器自己的缩放因子换算，
  *   不再依赖 window.screen.availWidth/Height（始终返回主显示器尺寸）。
  */
 
 import { ref } from "vue";
 import { listen } from "@tauri-apps/api/event";
 import { invoke } from "@tauri-apps/api/core";
+import type { MonitorTopology, PhysicalRect } from "@/types";
 
 // ── 面板尺寸常量（与 tauri.conf.json 保持一致）─────────────────────────────
 
@@ -36,45 +38,55 @@ const PANEL_GAP = 20;
 const PET_HALF_SIZE = 60; // 宠物碰撞半径（逻辑像素）
 const AUTO_CLOSE_MS = 3000;
 
-// ── 虚拟桌面信息缓存 ────────────────────────────────────────────────────────
-// 避免每次计算面板位置都调用 Rust，缓存虚拟桌面信息。
-// 缓存在首次使用时填充，之后复用（显示器布局在运行期间很少变化）。
-
-let cachedScreen: {
-  originX: number;
-  originY: number;
-  width: number;
-  height: number;
-} | null = null;
-
-async function getVirtualScreen(): Promise<{
-  originX: number;
-  originY: number;
-  width: number;
-  height: number;
-}> {
-  if (cachedScreen) return cachedScreen;
+// ── 显示器拓扑 ──────────────────────────────────────────────────────────────
+// 每次计算面板位置都向 Rust 查询（Rust 侧有缓存，调用开销很小），
+// 并按宠物所在显示器的缩放因子把工作区换算为逻辑像素。
 
+interface LogicalArea {
+  minX: number;
+  minY: number;
+  maxX: number;
+  maxY: number;
+}
+
+/** 物理像素矩形 → 逻辑像素边界 */
+function toLogicalArea(rect: PhysicalRect, scale: number): LogicalArea {
+  return {
+    minX: rect.x / scale,
+    minY: rect.y / scale,
+    maxX: (rect.x + rect.width) / scale,
+    maxY: (rect.y + rect.height) / scale,
+  };
+}
+
+/** 宠物（逻辑像素）所在显示器的工作区；不在任何显示器内时取最近的一个 */
+async function getWorkAreaAt(petX: number, petY: number): Promise<LogicalArea> {
   try {
-    const [ox, oy, w, h] = await invoke<[number, number, number, number]>("get_screen_size");
-    const scale = await invoke<number>("get_scale_factor");
-    cachedScreen = {
-      originX: ox / scale,
-      originY: oy / scale,
-      width: w / scale,
-      height: h / scale,
-    };
-  } catch {
-    // 回退：使用主显示器尺寸
-    cachedScreen = {
-      originX: 0,
-      originY: 0,
-      width: window.screen.availWidth,
-      height: window.screen.availHeight,
-    };
+    const topology = await invoke<MonitorTopology>("get_monitors");
+    let best: LogicalArea | null = null;
+    let bestDist = Infinity;
+    for (const m of topology.monitors) {
+      const b = toLogicalArea(m.bounds, m.scale);
+      const dx = Math.max(b.minX - petX, 0, petX - b.maxX);
+      const dy = Math.max(b.minY - petY, 0, petY - b.maxY);
+      const dist = dx * dx + dy * dy;
+      if (dist < bestDist) {
+        bestDist = dist;
+        best = toLogicalArea(m.work_area, m.scale);
+      }
+    }
+    if (best) return best;
+  } catch (e) {
+    console.warn("[PanelController] get_monitors 失败:", e);
   }
 
-  return cachedScreen;
+  // 回退：使用主显示器尺寸
+  return {
+    minX: 0,
+    minY: 0,
+    maxX: window.screen.availWidth,
+    maxY: window.screen.availHeight,
+  };
 }
 
 // ── 面板位置计算（异步，支持多显示器）────────────────────────────────────────
@@ -83,13 +95,8 @@ async function computePanelPosition(
   petX: number,
   petY: number
 ): Promise<{ x: number; y: number }> {
-  const screen = await getVirtualScreen();
-
-  // 虚拟桌面的有效范围
-  const minX = screen.originX;
-  const minY = screen.originY;
-  const maxX = screen.originX + screen.width;
-  const maxY = screen.originY + screen.height;
+  // 宠物所在显示器的工作区（不含任务栏）
+  const { minX, minY, maxX, maxY } = await getWorkAreaAt(petX, petY);
 
   let x: number;
   let y: number;
@@ -110,7 +117,7 @@ async function computePanelPosition(
   // 纵向：与宠物大致齐平
   y = petY - 40;
 
-  // 边界钳制（使用虚拟桌面范围，支持副屏负坐标）
+  // 边界钳制（使用所在显示器的工作区，支持副屏负坐标）
   x = Math.max(minX + 5, Math.min(x, maxX - PANEL_W - 5));
   y = Math.max(minY + 5, Math.min(y, maxY - PANEL_H - 5));
 
@@ -242,9 +249,6 @@ export function usePanelController() {
    * onPetHoverEnter / onPetHoverLeave。
    */
   async function init() {
-    // ★ 预热虚拟桌面缓存（避免首次弹面板时延迟）
-    getVirtualScreen().catch(() => {});
-
     unlisteners.push(
       await listen("panel-hover-enter", () => onPanelHoverEnter()),
       await listen("panel-hover-leave", () => onPanelHoverLeave()),
@@ -8,11 +8,16 @@
  */
 
 import { invoke } from "@tauri-apps/api/core";
+import type { RecognizerSpec } from "@/types";
 
 /** 海鸥使用的手势 */
 export const seagullGestures = {
-  /** 需要注册到 Rust 侧的识别器名称列表 */
-  recognizers: ["circle"] as const,
+  /**
+   * 需要注册到 Rust 侧的识别器及参数（params 省略的字段取 Rust 侧默认值）
+   *
+   * circle 的默认值与旧版扇区判定一样宽松（不查速度）；只是开口弧线不再算圈
+   */
+  recognizers: [{ name: "circle" }] as RecognizerSpec[],
 
   /** 识别器 → Tauri 事件名的映射（用于 listen） */
   events: {
@@ -27,6 +32,6 @@ export const seagullGestures = {
  */
 export async function registerSeagullGestures(): Promise<void> {
   await invoke("register_recognizers", {
-    names: [...seagullGestures.recognizers],
+    recognizers: seagullGestures.recognizers,
   });
 }
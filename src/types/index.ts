@@ -1,8 +1,198 @@
 // ── 手势 ────────────────────────────────────────────────────────────────────
-export interface CircleGesturePayload {
+/** 所有手势事件共有的字段 */
+export interface GesturePayloadBase {
+  /** 落笔到抬笔的时长（毫秒）；后端不提供时间戳时为 0；提前识别时为落笔到识别时刻 */
+  duration_ms: number;
+  /** 是否在抬笔前由增量识别提前确认（如 circle 的 early_detection） */
+  early: boolean;
+}
+
+export interface CircleGesturePayload extends GesturePayloadBase {
   center_x: number;
   center_y: number;
+  /** 等效半径 */
   radius: number;
+  /** 长半轴 / 短半轴 */
+  radius_x: number;
+  radius_y: number;
+  /** 长轴方向（度） */
+  rotation: number;
+  direction: "clockwise" | "counterclockwise";
+  /** 圈数（螺旋会大于 1） */
+  revolutions: number;
+  /** 短轴 / 长轴（正圆为 1） */
+  axis_ratio: number;
+  /** 闭合度 0 ~ 1 */
+  closure: number;
+}
+
+/** "gesture-heart"：一笔画出的爱心 */
+export interface HeartGesturePayload extends GesturePayloadBase {
+  center_x: number;
+  center_y: number;
+  /** 包围盒最长边 */
+  size: number;
+}
+
+/** "gesture-rectangle"：一笔画出的矩形（规整后的包围矩形，可直接传给 capture_region） */
+export interface RectangleGesturePayload extends GesturePayloadBase {
+  /** 左上角 */
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+  /** 矩形中心所在显示器的标识（与 MonitorInfo.id 对应） */
+  monitor: string;
+  /** 相对该显示器左上角的位置 */
+  monitor_x: number;
+  monitor_y: number;
+}
+
+/** "gesture-zigzag"：之字形 / 来回涂抹 */
+export interface ZigzagGesturePayload extends GesturePayloadBase {
+  start_x: number;
+  start_y: number;
+  end_x: number;
+  end_y: number;
+  /** 方向反转次数 */
+  reversals: number;
+}
+
+/** "gesture-flick"：直线甩动（from_pet 为 true 时是拖拽宠物甩出） */
+export interface FlickGesturePayload extends GesturePayloadBase {
+  start_x: number;
+  start_y: number;
+  end_x: number;
+  end_y: number;
+  /** 方向角（度，0 = 向右，90 = 向下） */
+  angle: number;
+  length: number;
+  /** 松手速度（逻辑像素 / 秒） */
+  speed: number;
+  from_pet: boolean;
+}
+
+/** "gesture-template"：用户模板匹配成功 */
+export interface TemplateGesturePayload extends GesturePayloadBase {
+  name: string;
+  /** 匹配分数 0 ~ 1 */
+  score: number;
+  center_x: number;
+  center_y: number;
+  size: number;
+}
+
+/** "gesture-cross" / "gesture-plus" / "gesture-star"：多笔画形状（需开启 set_multi_stroke_mode） */
+export interface MultiStrokeGesturePayload extends GesturePayloadBase {
+  center_x: number;
+  center_y: number;
+  /** 包围盒最长边 */
+  size: number;
+  /** 笔画数 */
+  strokes: number;
+  /** 匹配分数 0 ~ 1 */
+  score: number;
+}
+
+/** 识别候选摘要 */
+export interface GestureCandidate {
+  recognizer: string;
+  /** 手势类型，如 "circle" */
+  gesture: string;
+  /** 置信度 0 ~ 1 */
+  score: number;
+  /** 该识别器的最低置信度 */
+  threshold: number;
+}
+
+/** "gesture-ambiguous"：多个识别器分数太接近，没有触发任何手势 */
+export interface AmbiguousGesturePayload {
+  /** 分数接近的候选（从高到低） */
+  candidates: GestureCandidate[];
+  duration_ms: number;
+}
+
+/** "gesture-debug"：一次识别中落选的候选 */
+export interface GestureDebugPayload {
+  source: "stroke" | "drag" | "multi-stroke";
+  /** 采纳的识别器；没有采纳时为 null */
+  accepted: string | null;
+  rejected: (GestureCandidate & {
+    reason: "below_threshold" | "outscored" | "ambiguous";
+  })[];
+}
+
+/** 路径上的一点（逻辑像素，t 为相对落笔时刻的毫秒数） */
+export interface PathPoint {
+  x: number;
+  y: number;
+  t: number;
+}
+
+/**
+ * "gesture-progress"：绘制中节流发出（约 30Hz）的当前折线（已简化）；
+ * "gesture-path"：抬笔时的完整折线，先于识别结果到达
+ */
+export interface GesturePathPayload {
+  points: PathPoint[];
+  duration_ms: number;
+}
+
+/** "gesture-cancelled"：绘制中的一笔被取消（Esc / 绘制中按右键 / 超过 set_gesture_timeout 的时长） */
+export interface GestureCancelledPayload {
+  reason: "escape" | "right_click" | "timeout";
+  duration_ms: number;
+}
+
+/** register_recognizers 的单个条目 */
+export interface RecognizerSpec {
+  /** "circle" / "heart" / "rectangle" / "zigzag" / "flick" / "template" / "point-cloud" */
+  name: string;
+  /** 识别器参数，省略的字段取默认值；所有识别器都支持 min_score */
+  params?: Record<string, unknown>;
+}
+
+/** register_recognizers 失败时返回的错误（数组） */
+export type RegistryError =
+  | { kind: "unknown_recognizer"; index: number; name: string; known: string[] }
+  | { kind: "invalid_params"; index: number; name: string; message: string };
+
+/** set_gesture_activation：宠物外的左键拖动何时算作画手势 */
+export type GestureActivationPolicy =
+  | { mode: "always" }
+  | { mode: "modifier"; key: "ctrl" | "shift" | "alt" | "meta" }
+  /** 按住不动 hold_ms 后开始绘制，此前移动超过 tolerance（逻辑像素）视为普通拖动 */
+  | { mode: "dwell"; hold_ms?: number; tolerance?: number }
+  /** 前台应用进程名（不区分大小写，可省略 ".exe"） */
+  | { mode: "apps"; allow?: string[]; deny?: string[] };
+
+/** set_button_config：中键 / 侧键配置（省略的字段取默认值） */
+export interface ButtonConfig {
+  /** 画手势用的键，默认 "left"；为 "middle" 时宠物外的左键拖动不再当作手势（X11 等无法吞没事件的后端会拒绝） */
+  draw_button?: "left" | "middle";
+  /** 宠物上的中键 / 侧键是否吞没（不传给下层窗口），默认 false */
+  swallow_extra_buttons?: boolean;
+}
+
+/** list_gesture_templates / save_gesture_template 返回的模板摘要 */
+export interface TemplateInfo {
+  name: string;
+  samples: number;
+}
+
+/** "gesture-sample-recorded"：录制模式下每画一笔触发 */
+export interface SampleRecordedPayload {
+  count: number;
+  /** 该笔太短被忽略 */
+  rejected: boolean;
+}
+
+/** capture_region 的返回值 / "region-captured" 事件 */
+export interface CapturedRegion {
+  /** PNG 文件的绝对路径（应用数据目录下的 captures/） */
+  path: string;
+  /** 实际截取的物理像素矩形（已裁剪到虚拟桌面内） */
+  bounds: PhysicalRect;
 }
 
 // ── 拖拽（来自 Rust 全局钩子）────────────────────────────────────────────────
@@ -11,6 +201,83 @@ export interface DragPayload {
   y: number;
 }
 
+/** "pet-drag-end"：最终位置 + 松手速度（逻辑像素 / 秒，松手前停住时为 0） */
+export interface DragEndPayload extends DragPayload {
+  velocity_x: number;
+  velocity_y: number;
+}
+
+/** "pet-shaken"：拖拽中来回摇晃宠物 */
+export interface PetShakenPayload extends DragPayload {
+  /** 判定窗口内的方向反转次数 */
+  reversals: number;
+}
+
+/**
+ * "pet-click" / "pet-double-click" / "pet-long-press"：点按宠物（移动超过阈值才算拖拽）；
+ * "pet-middle-click" / "pet-x1-click" / "pet-x2-click"：在宠物上按下中键 / 侧键
+ */
+export interface PetPointerPayload {
+  /** 鼠标位置 */
+  x: number;
+  y: number;
+  /** 鼠标相对宠物中心的偏移 */
+  offset_x: number;
+  offset_y: number;
+}
+
+/** 事件发生时按住的修饰键 */
+export interface Modifiers {
+  ctrl: boolean;
+  shift: boolean;
+  alt: boolean;
+  /** Windows 键 / Super */
+  meta: boolean;
+}
+
+/** "pet-wheel"：在宠物上滚动滚轮（下层窗口不会跟着滚动） */
+export interface PetWheelPayload {
+  /** 鼠标位置 */
+  x: number;
+  y: number;
+  /** 滚动格数，向下 / 向右为正（与 DOM WheelEvent 一致），高精度滚轮可能是小数 */
+  delta_x: number;
+  delta_y: number;
+  modifiers: Modifiers;
+}
+
+// ── 显示器拓扑（来自 Rust get_monitors）──────────────────────────────────────
+/** 物理像素矩形（虚拟桌面坐标系，左上角可能为负） */
+export interface PhysicalRect {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+}
+
+export interface MonitorInfo {
+  id: string;
+  /** 显示器完整矩形（物理像素） */
+  bounds: PhysicalRect;
+  /** 去掉任务栏后的工作区（物理像素） */
+  work_area: PhysicalRect;
+  /** DPI 缩放因子，逻辑像素 = 物理像素 ÷ scale */
+  scale: number;
+  primary: boolean;
+}
+
+export interface MonitorTopology {
+  monitors: MonitorInfo[];
+  virtual_bounds: PhysicalRect;
+}
+
+/** "display-changed" 事件：显示器热插拔 / 缩放变化 */
+export interface DisplayChangedPayload {
+  topology: MonitorTopology;
+  /** 钳制回可见工作区后的宠物位置（逻辑像素）；未同步或拖拽中为 null */
+  pet: DragPayload | null;
+}
+
 // ── 朝向系统 ────────────────────────────────────────────────────────────────
 /**
  * 宠物朝向角度（弧度），以正右方为 0，逆时针为正。